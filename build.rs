@@ -1,15 +1,20 @@
 fn main() {
-    prost_build::compile_protos(
-        &[
-            "protos/car_motion.proto",
-            "protos/event_data.proto",
-            "protos/final_classification.proto",
-            "protos/participants.proto",
-            "protos/session_data.proto",
-            "protos/session_history.proto",
-            "protos/packet_header.proto",
-        ],
-        &["protos/"],
-    )
-    .unwrap();
+    // Serialize is needed for the json output mode of the live data
+    prost_build::Config::new()
+        .type_attribute(".", "#[derive(serde::Serialize)]")
+        .compile_protos(
+            &[
+                "protos/car_motion.proto",
+                "protos/event_data.proto",
+                "protos/final_classification.proto",
+                "protos/participants.proto",
+                "protos/session_data.proto",
+                "protos/session_history.proto",
+                "protos/packet_header.proto",
+                "protos/records.proto",
+                "protos/socket_control.proto",
+            ],
+            &["protos/"],
+        )
+        .unwrap();
 }
@@ -0,0 +1,5 @@
+-- Add migration script here
+
+CREATE TYPE visibility AS ENUM ('Public', 'Members', 'Invite');
+
+ALTER TABLE championship ADD COLUMN visibility visibility NOT NULL DEFAULT 'Public';
@@ -0,0 +1,10 @@
+-- Add migration script here
+
+CREATE TYPE championship_role AS ENUM ('Owner', 'Admin', 'Steward', 'Driver', 'Viewer');
+
+ALTER TABLE user_championships ADD COLUMN role championship_role NOT NULL DEFAULT 'Driver';
+
+UPDATE user_championships uc
+SET role = 'Owner'
+FROM championship c
+WHERE c.id = uc.championship_id AND c.owner_id = uc.user_id;
@@ -0,0 +1,18 @@
+-- Add migration script here
+
+CREATE TABLE
+    championship_invites (
+        id INTEGER PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        invited_by INTEGER NOT NULL,
+        role championship_role NOT NULL DEFAULT 'Driver',
+        expires_at TIMESTAMPTZ NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (championship_id, user_id),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (invited_by) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "championship_invites" ("championship_id");
@@ -0,0 +1,28 @@
+-- Add migration script here
+
+CREATE TABLE
+    championship_join_codes (
+        code VARCHAR(16) PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        created_by INTEGER NOT NULL,
+        max_uses INTEGER CHECK (max_uses > 0),
+        uses INTEGER NOT NULL DEFAULT 0,
+        expires_at TIMESTAMPTZ,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (created_by) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "championship_join_codes" ("championship_id");
+
+CREATE TABLE
+    championship_join_requests (
+        championship_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        code VARCHAR(16),
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (championship_id, user_id),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (code) REFERENCES championship_join_codes (code) ON DELETE SET NULL
+    );
@@ -0,0 +1,41 @@
+-- Add migration script here
+
+CREATE TABLE
+    championship_teams (
+        id INTEGER PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        name VARCHAR(50) NOT NULL,
+        game_team_id SMALLINT NOT NULL CHECK (
+            game_team_id >= 0
+            AND game_team_id <= 255
+        ),
+        primary_color CHAR(7) NOT NULL,
+        secondary_color CHAR(7),
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (championship_id, name),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "championship_teams" ("championship_id");
+
+-- A row per stint of a driver in a team, transfers close the current stint
+CREATE TABLE
+    team_drivers (
+        id SERIAL PRIMARY KEY,
+        team_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        joined_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        left_at TIMESTAMPTZ,
+        FOREIGN KEY (team_id) REFERENCES championship_teams (id) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "team_drivers" ("team_id");
+
+CREATE INDEX ON "team_drivers" ("user_id");
+
+-- A driver can only have one open stint in the same team
+CREATE UNIQUE INDEX ON "team_drivers" ("team_id", "user_id")
+WHERE
+    left_at IS NULL;
@@ -0,0 +1,72 @@
+-- Add migration script here
+
+CREATE TYPE incident_kind AS ENUM ('Penalty', 'Overtake');
+
+-- Penalty and overtake events reported by the game, car indexes are the ones of the session
+CREATE TABLE
+    session_incidents (
+        id SERIAL PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        kind incident_kind NOT NULL,
+        lap_num SMALLINT,
+        vehicle_idx SMALLINT NOT NULL,
+        other_vehicle_idx SMALLINT,
+        penalty_type SMALLINT,
+        infringement_type SMALLINT,
+        time SMALLINT,
+        places_gained SMALLINT,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "session_incidents" ("championship_id", "session_uid");
+
+CREATE TYPE protest_status AS ENUM ('Pending', 'Upheld', 'Dismissed');
+
+CREATE TYPE sanction AS ENUM ('TimePenalty', 'GridDrop', 'PointsDeduction', 'Disqualification');
+
+CREATE TABLE
+    championship_protests (
+        id SERIAL PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        incident_id INTEGER,
+        filed_by INTEGER NOT NULL,
+        accused_id INTEGER NOT NULL,
+        lap_num SMALLINT,
+        description VARCHAR(1000) NOT NULL,
+        status protest_status NOT NULL DEFAULT 'Pending',
+        steward_id INTEGER,
+        decision VARCHAR(1000),
+        ruled_at TIMESTAMPTZ,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (incident_id) REFERENCES session_incidents (id) ON DELETE SET NULL,
+        FOREIGN KEY (filed_by) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (accused_id) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (steward_id) REFERENCES "users" (id) ON DELETE SET NULL
+    );
+
+CREATE INDEX ON "championship_protests" ("championship_id");
+
+-- Sanctions decided by the stewards, applied on top of the classification of the session.
+-- Disqualifications don't take a value and penalties are kept when the steward deletes the account
+CREATE TABLE
+    championship_penalties (
+        id SERIAL PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        user_id INTEGER NOT NULL,
+        protest_id INTEGER,
+        sanction sanction NOT NULL,
+        value SMALLINT NOT NULL CHECK (value >= 0),
+        applied_by INTEGER,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (protest_id) REFERENCES championship_protests (id) ON DELETE SET NULL,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (applied_by) REFERENCES "users" (id) ON DELETE SET NULL
+    );
+
+CREATE INDEX ON "championship_penalties" ("championship_id", "session_uid");
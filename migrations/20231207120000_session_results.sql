@@ -0,0 +1,66 @@
+-- Add migration script here
+
+-- Points of every position in race sessions, starting with the winner
+ALTER TABLE championship ADD COLUMN points_table SMALLINT[] NOT NULL DEFAULT '{25,18,15,12,10,8,6,4,2,1}';
+
+CREATE TABLE
+    championship_sessions (
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        session_type SMALLINT NOT NULL,
+        track_id SMALLINT NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (championship_id, session_uid),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE
+    );
+
+-- Final classification reported by the game, the adjusted columns are recalculated
+-- from the original ones and the adjustments every time a new one is applied, the
+-- adjusted points come from the points table of the championship
+CREATE TABLE
+    session_results (
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        car_idx SMALLINT NOT NULL,
+        user_id INTEGER,
+        position SMALLINT NOT NULL,
+        num_laps SMALLINT NOT NULL,
+        grid_position SMALLINT NOT NULL,
+        points SMALLINT NOT NULL,
+        num_pit_stops SMALLINT NOT NULL,
+        result_status SMALLINT NOT NULL,
+        best_lap_time_in_ms INTEGER NOT NULL,
+        total_race_time DOUBLE PRECISION NOT NULL,
+        penalties_time SMALLINT NOT NULL,
+        num_penalties SMALLINT NOT NULL,
+        adjusted_position SMALLINT NOT NULL,
+        adjusted_penalties_time SMALLINT NOT NULL,
+        adjusted_points SMALLINT NOT NULL,
+        disqualified BOOLEAN NOT NULL DEFAULT FALSE,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (championship_id, session_uid, car_idx),
+        FOREIGN KEY (championship_id, session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE SET NULL
+    );
+
+CREATE INDEX ON "session_results" ("user_id");
+
+CREATE TYPE adjustment_kind AS ENUM ('TimePenalty', 'Disqualification', 'PositionChange');
+
+-- Audit trail of every change made to a classification, kept when the steward deletes the account
+CREATE TABLE
+    result_adjustments (
+        id SERIAL PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        car_idx SMALLINT NOT NULL,
+        kind adjustment_kind NOT NULL,
+        value SMALLINT NOT NULL,
+        reason VARCHAR(500) NOT NULL,
+        applied_by INTEGER,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (championship_id, session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE CASCADE,
+        FOREIGN KEY (applied_by) REFERENCES "users" (id) ON DELETE SET NULL
+    );
+
+CREATE INDEX ON "result_adjustments" ("championship_id", "session_uid");
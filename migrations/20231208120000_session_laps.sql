@@ -0,0 +1,31 @@
+-- Add migration script here
+
+-- Completed laps of every car, sector times include the whole minutes
+CREATE TABLE
+    session_laps (
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        car_idx SMALLINT NOT NULL,
+        lap_num SMALLINT NOT NULL,
+        lap_time_in_ms INTEGER NOT NULL,
+        sector1_time_in_ms INTEGER NOT NULL,
+        sector2_time_in_ms INTEGER NOT NULL,
+        sector3_time_in_ms INTEGER NOT NULL,
+        lap_valid_bit_flags SMALLINT NOT NULL,
+        PRIMARY KEY (championship_id, session_uid, car_idx, lap_num),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE
+    );
+
+-- The end lap of the current stint is 255 until the car changes tyres
+CREATE TABLE
+    session_stints (
+        championship_id INTEGER NOT NULL,
+        session_uid BIGINT NOT NULL,
+        car_idx SMALLINT NOT NULL,
+        stint SMALLINT NOT NULL,
+        end_lap SMALLINT NOT NULL,
+        tyre_actual_compound SMALLINT NOT NULL,
+        tyre_visual_compound SMALLINT NOT NULL,
+        PRIMARY KEY (championship_id, session_uid, car_idx, stint),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE
+    );
@@ -0,0 +1,23 @@
+-- Add migration script here
+
+-- Personal best of every member per track, category and season, the track record is the fastest of them.
+-- The name used in the game is kept next to the member that drove the lap
+CREATE TABLE
+    track_records (
+        track_id SMALLINT NOT NULL,
+        category category NOT NULL,
+        season SMALLINT NOT NULL,
+        user_id INTEGER NOT NULL,
+        driver_name VARCHAR(48) NOT NULL,
+        lap_time_in_ms INTEGER NOT NULL,
+        championship_id INTEGER,
+        session_uid BIGINT NOT NULL,
+        set_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (track_id, category, season, user_id),
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE,
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE SET NULL
+    );
+
+CREATE INDEX ON "track_records" ("track_id", "category", "season", "lap_time_in_ms");
+
+CREATE INDEX ON "track_records" ("user_id");
@@ -0,0 +1,33 @@
+-- Add migration script here
+
+CREATE TYPE weekend_format AS ENUM ('Standard', 'Sprint', 'ReverseGrid');
+
+-- Sessions are linked once they are stored, the grid of the race is derived from the qualifying one.
+-- Sprint rounds also link the sprint shootout and the sprint race, the feature race
+-- keeps taking its grid from the qualifying session
+CREATE TABLE
+    championship_rounds (
+        id SERIAL PRIMARY KEY,
+        championship_id INTEGER NOT NULL,
+        round_num SMALLINT NOT NULL,
+        track_id SMALLINT NOT NULL,
+        format weekend_format NOT NULL DEFAULT 'Standard',
+        reverse_grid_count SMALLINT CHECK (
+            reverse_grid_count >= 2
+            AND reverse_grid_count <= 22
+        ),
+        qualifying_session_uid BIGINT,
+        race_session_uid BIGINT,
+        sprint_qualifying_session_uid BIGINT,
+        sprint_session_uid BIGINT,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (championship_id, round_num),
+        FOREIGN KEY (championship_id) REFERENCES championship (id) ON DELETE CASCADE,
+        FOREIGN KEY (championship_id, qualifying_session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE SET NULL (qualifying_session_uid),
+        FOREIGN KEY (championship_id, race_session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE SET NULL (race_session_uid),
+        FOREIGN KEY (championship_id, sprint_qualifying_session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE SET NULL (sprint_qualifying_session_uid),
+        FOREIGN KEY (championship_id, sprint_session_uid) REFERENCES championship_sessions (championship_id, session_uid) ON DELETE SET NULL (sprint_session_uid)
+    );
+
+CREATE INDEX ON "championship_rounds" ("championship_id");
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+-- Drivers are linked by matching the name in the game with the username of a member,
+-- stewards can assign the car to other member when they don't match
+ALTER TABLE session_results ADD COLUMN driver_name VARCHAR(48);
+
+CREATE INDEX ON "championship_penalties" ("user_id");
@@ -0,0 +1,25 @@
+-- Add migration script here
+
+-- The secret is saved when the setup starts and it's only used once the first code is verified,
+-- last_used_step keeps a code from being used twice
+CREATE TABLE
+    user_two_factor (
+        user_id INTEGER PRIMARY KEY,
+        secret VARCHAR(64) NOT NULL,
+        enabled BOOLEAN NOT NULL DEFAULT FALSE,
+        last_used_step BIGINT NOT NULL DEFAULT 0,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+-- Recovery codes are only shown once, the SHA-256 of the code is stored
+CREATE TABLE
+    two_factor_recovery_codes (
+        id SERIAL PRIMARY KEY,
+        user_id INTEGER NOT NULL,
+        code_hash VARCHAR(64) NOT NULL,
+        used_at TIMESTAMPTZ,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "two_factor_recovery_codes" ("user_id");
@@ -0,0 +1,19 @@
+-- Add migration script here
+
+CREATE TYPE api_key_scope AS ENUM ('ReadProfile', 'ReadChampionships', 'ReadLiveData', 'ManageSockets');
+
+-- Keys are only shown once, the SHA-256 of the key is stored and the prefix identifies it in the list
+CREATE TABLE
+    api_keys (
+        id SERIAL PRIMARY KEY,
+        user_id INTEGER NOT NULL,
+        name VARCHAR(32) NOT NULL,
+        prefix VARCHAR(16) NOT NULL,
+        key_hash VARCHAR(64) NOT NULL UNIQUE,
+        scopes api_key_scope[] NOT NULL,
+        last_used_at TIMESTAMPTZ,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX ON "api_keys" ("user_id");
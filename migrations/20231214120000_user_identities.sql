@@ -0,0 +1,19 @@
+-- Add migration script here
+
+ALTER TYPE provider ADD VALUE 'Discord';
+
+ALTER TYPE provider ADD VALUE 'Steam';
+
+-- External accounts linked to the user, the provider of the user is the one it was created with.
+-- Accounts created with google before this table are linked again on their next login
+CREATE TABLE
+    user_identities (
+        provider provider NOT NULL,
+        subject VARCHAR(255) NOT NULL,
+        user_id INTEGER NOT NULL,
+        email VARCHAR(100),
+        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (provider, subject),
+        UNIQUE (user_id, provider),
+        FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+    );
@@ -0,0 +1,11 @@
+-- Add migration script here
+
+-- Deactivated users are inactive too, only the ones that never verified their email are purged
+ALTER TABLE users ADD COLUMN verified_at TIMESTAMPTZ;
+
+-- Users that were updated after signing up are kept, it isn't known if they verified
+UPDATE users
+SET verified_at = created_at
+WHERE active OR provider <> 'Local' OR updated_at > created_at;
+
+CREATE INDEX ON "users" ("created_at") WHERE verified_at IS NULL;
@@ -1,21 +1,28 @@
 mod championship;
 mod f123;
+mod rate_limit;
+mod stats;
 mod token;
 mod user;
 
-use self::{championship::ChampionshipCache, token::TokenCache, user::UserCache};
+use self::{
+    championship::ChampionshipCache, rate_limit::RateLimitCache, token::TokenCache, user::UserCache,
+};
 use crate::{
     config::{constants::REDIS_CACHE_EXPIRATION, Database},
     error::AppResult,
 };
 use async_trait::async_trait;
 pub(crate) use f123::*;
+pub(crate) use stats::StatsCache;
 
 #[derive(Clone)]
 pub struct RedisCache {
     pub user: UserCache,
     pub championship: ChampionshipCache,
     pub token: TokenCache,
+    pub stats: StatsCache,
+    pub rate_limit: RateLimitCache,
 }
 
 impl RedisCache {
@@ -24,6 +31,8 @@ impl RedisCache {
             user: UserCache::new(db),
             championship: ChampionshipCache::new(db),
             token: TokenCache::new(db),
+            stats: StatsCache::new(db),
+            rate_limit: RateLimitCache::new(db),
         }
     }
 }
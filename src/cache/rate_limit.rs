@@ -0,0 +1,101 @@
+use crate::{
+    config::{constants::*, Database, RateLimitRule},
+    error::AppResult,
+};
+use chrono::Utc;
+use deadpool_redis::redis::{self, AsyncCommands};
+
+const RATE_LIMIT_KEY: &str = "rate_limit";
+const FAILED_LOGINS_KEY: &str = "auth:failed_logins";
+const LOCKOUT_KEY: &str = "auth:lockout";
+
+// Log of the requests in the window as a sorted set by time.
+// KEYS: window. ARGV: now, window, limit, member (milliseconds).
+// Returns the milliseconds until the oldest request leaves the window, 0 if allowed
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+    redis.call('ZREMRANGEBYSCORE', KEYS[1], 0, ARGV[1] - ARGV[2])
+
+    if redis.call('ZCARD', KEYS[1]) >= tonumber(ARGV[3]) then
+        local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+        return math.max(oldest[2] + ARGV[2] - ARGV[1], 1)
+    end
+
+    redis.call('ZADD', KEYS[1], ARGV[1], ARGV[4])
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    return 0
+"#;
+
+#[derive(Clone)]
+pub struct RateLimitCache {
+    db: Database,
+}
+
+impl RateLimitCache {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+
+    // Counts the request if it's allowed, otherwise returns how long to wait
+    #[inline(always)]
+    pub async fn hit(&self, rule: &RateLimitRule, key: &str) -> AppResult<Option<u64>> {
+        let now = Utc::now().timestamp_millis();
+        let mut conn = self.db.redis.get().await?;
+
+        let retry_after: u64 = redis::cmd("EVAL")
+            .arg(SLIDING_WINDOW_SCRIPT)
+            .arg(1)
+            .arg(format!("{RATE_LIMIT_KEY}:{}:{key}", rule.name))
+            .arg(now)
+            .arg(rule.window.as_millis() as u64)
+            .arg(rule.limit)
+            .arg(format!("{now}:{}", fastrand::u32(..)))
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok((retry_after > 0).then_some(retry_after))
+    }
+
+    // Failed logins in a row, the count is kept for a day since the last one
+    #[inline(always)]
+    pub async fn failed_login(&self, user_id: &i32) -> AppResult<i64> {
+        let key = format!("{FAILED_LOGINS_KEY}:{user_id}");
+        let mut conn = self.db.redis.get().await?;
+
+        let (failures, _): (i64, i32) = redis::pipe()
+            .incr(&key, 1)
+            .expire(&key, FAILED_LOGINS_EXPIRATION as i64)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(failures)
+    }
+
+    #[inline(always)]
+    pub async fn reset_failed_logins(&self, user_id: &i32) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        conn.del(format!("{FAILED_LOGINS_KEY}:{user_id}")).await?;
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub async fn lock(&self, user_id: &i32, duration: u64) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        conn.set_ex(format!("{LOCKOUT_KEY}:{user_id}"), 1, duration)
+            .await?;
+
+        Ok(())
+    }
+
+    // Seconds left of the lockout, None if the account isn't locked
+    #[inline(always)]
+    pub async fn lockout(&self, user_id: &i32) -> AppResult<Option<u64>> {
+        let mut conn = self.db.redis.get().await?;
+
+        let ttl: i64 = conn.ttl(format!("{LOCKOUT_KEY}:{user_id}")).await?;
+
+        Ok((ttl > 0).then_some(ttl as u64))
+    }
+}
@@ -0,0 +1,89 @@
+use super::EntityCache;
+use crate::{
+    config::{constants::*, Database},
+    entity::DriverStats,
+    error::{AppResult, CacheError},
+};
+use async_trait::async_trait;
+use deadpool_redis::redis::{self, AsyncCommands};
+use rkyv::{Deserialize, Infallible};
+use tracing::error;
+
+#[derive(Clone)]
+pub struct StatsCache {
+    db: Database,
+}
+
+impl StatsCache {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+
+    #[inline(always)]
+    pub async fn delete_all(&self, users: &[i32]) -> AppResult<()> {
+        if users.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+
+        users.iter().for_each(|user_id| {
+            pipe.del(format!("{REDIS_STATS_PREFIX}:{}", user_id));
+        });
+
+        let mut conn = self.db.redis.get().await?;
+        pipe.query_async(&mut conn).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EntityCache for StatsCache {
+    type Entity = DriverStats;
+    const EXPIRATION: u64 = REDIS_STATS_EXPIRATION;
+
+    #[inline(always)]
+    async fn get(&self, id: &i32) -> AppResult<Option<Self::Entity>> {
+        let bytes: Option<Vec<u8>> = {
+            let mut conn = self.db.redis.get().await?;
+            conn.get(format!("{REDIS_STATS_PREFIX}:{}", id)).await?
+        };
+
+        if let Some(bytes) = bytes {
+            let archived = unsafe { rkyv::archived_root::<Self::Entity>(&bytes) };
+
+            let Ok(stats) = archived.deserialize(&mut Infallible);
+
+            return Ok(Some(stats));
+        }
+
+        Ok(None)
+    }
+
+    #[inline(always)]
+    async fn set(&self, entity: &Self::Entity) -> AppResult<()> {
+        let Ok(bytes) = rkyv::to_bytes::<_, 256>(entity) else {
+            error!("Failed to serialize driver stats to cache");
+            Err(CacheError::Serialize)?
+        };
+
+        let mut conn = self.db.redis.get().await?;
+
+        conn.set_ex(
+            format!("{REDIS_STATS_PREFIX}:{}", entity.user_id),
+            &bytes[..],
+            Self::EXPIRATION,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    async fn delete(&self, id: &i32) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+        conn.del(format!("{REDIS_STATS_PREFIX}:{}", id)).await?;
+        Ok(())
+    }
+}
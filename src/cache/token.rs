@@ -1,10 +1,39 @@
 use crate::{
     config::{constants::*, Database},
-    dtos::TokenType,
+    dtos::{DeviceInfo, OAuthState, RefreshSession, TokenType},
     error::AppResult,
 };
+use chrono::Utc;
 use core::panic;
-use deadpool_redis::redis::AsyncCommands;
+use deadpool_redis::redis::{self, AsyncCommands};
+use std::collections::HashMap;
+
+const SESSIONS_KEY: &str = "tokens:sessions";
+const GENERATION_KEY: &str = "tokens:generation";
+const OAUTH_STATE_KEY: &str = "tokens:oauth_state";
+const AUTH_CODE_KEY: &str = "tokens:auth_code";
+const VIEWER_INVITE_KEY: &str = "tokens:viewer_invite";
+
+// KEYS: session, sessions of the user. ARGV: jti, new jti, now, expiration
+const ROTATE_SESSION_SCRIPT: &str = r#"
+    if redis.call('HGET', KEYS[1], 'jti') ~= ARGV[1] then
+        return 0
+    end
+
+    redis.call('HSET', KEYS[1], 'jti', ARGV[2], 'last_used_at', ARGV[3])
+    redis.call('EXPIRE', KEYS[1], ARGV[4])
+    redis.call('EXPIRE', KEYS[2], ARGV[4])
+    return 1
+"#;
+
+// KEYS: challenge. Only counts the attempt while the challenge exists so it keeps its expiration
+const CHALLENGE_ATTEMPT_SCRIPT: &str = r#"
+    if redis.call('EXISTS', KEYS[1]) == 0 then
+        return false
+    end
+
+    return redis.call('INCR', KEYS[1])
+"#;
 
 #[derive(Clone)]
 pub struct TokenCache {
@@ -45,57 +74,346 @@ impl TokenCache {
     }
 
     #[inline(always)]
-    pub async fn set_refresh_token(&self, token: &str, fingerprint: &str) -> AppResult<()> {
+    pub async fn remove_token(&self, token: &str, token_type: &TokenType) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        conn.del(&format!("{}:{}", token_type.base_key(), token))
+            .await?;
+
+        Ok(())
+    }
+
+    // The pending address is stored with the token, the token only proves who asked for it
+    #[inline(always)]
+    pub async fn set_email_change(&self, token: &str, email: &str) -> AppResult<()> {
         let mut conn = self.db.redis.get().await?;
 
         conn.set_ex(
-            &format!("{}:{}", TokenType::RefreshBearer.base_key(), fingerprint),
-            token,
-            REFRESH_TOKEN_EXPIRATION,
+            format!("{}:{token}", TokenType::EmailChange.base_key()),
+            email,
+            GENERIC_TOKEN_EXPIRATION,
         )
         .await?;
 
         Ok(())
     }
 
+    // Returns the pending address, the link can't be used again either way
     #[inline(always)]
-    pub async fn get_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<String> {
+    pub async fn take_email_change(&self, token: &str) -> AppResult<Option<String>> {
         let mut conn = self.db.redis.get().await?;
 
-        let token = conn
-            .get(&format!(
-                "{}:{}:{}",
-                TokenType::RefreshBearer.base_key(),
-                user_id,
-                fingerprint
-            ))
+        let email = conn
+            .get_del(format!("{}:{token}", TokenType::EmailChange.base_key()))
             .await?;
 
-        Ok(token)
+        Ok(email)
     }
 
+    // Two factor challenges count the failed attempts instead of being a flag
     #[inline(always)]
-    pub async fn remove_token(&self, token: &str, token_type: &TokenType) -> AppResult<()> {
+    pub async fn set_challenge(&self, token: &str) -> AppResult<()> {
         let mut conn = self.db.redis.get().await?;
 
-        conn.del(&format!("{}:{}", token_type.base_key(), token))
+        conn.set_ex(
+            format!("{}:{token}", TokenType::TwoFactor.base_key()),
+            0,
+            GENERIC_TOKEN_EXPIRATION,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Returns the attempts made with the challenge including this one, None if it doesn't exist
+    #[inline(always)]
+    pub async fn challenge_attempt(&self, token: &str) -> AppResult<Option<i64>> {
+        let mut conn = self.db.redis.get().await?;
+
+        let attempts: Option<i64> = redis::cmd("EVAL")
+            .arg(CHALLENGE_ATTEMPT_SCRIPT)
+            .arg(1)
+            .arg(format!("{}:{token}", TokenType::TwoFactor.base_key()))
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(attempts)
+    }
+
+    #[inline(always)]
+    pub async fn set_oauth_state(&self, state: &str, oauth_state: &OAuthState) -> AppResult<()> {
+        let key = format!("{OAUTH_STATE_KEY}:{state}");
+        let mut conn = self.db.redis.get().await?;
+
+        redis::pipe()
+            .hset_multiple(&key, &oauth_state.to_fields())
+            .expire(&key, OAUTH_STATE_EXPIRATION as i64)
+            .query_async(&mut *conn)
             .await?;
 
         Ok(())
     }
 
+    // Read and removed at once so the same callback can't be replayed
     #[inline(always)]
-    pub async fn remove_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<()> {
+    pub async fn take_oauth_state(&self, state: &str) -> AppResult<Option<OAuthState>> {
+        let key = format!("{OAUTH_STATE_KEY}:{state}");
         let mut conn = self.db.redis.get().await?;
 
-        conn.del(&format!(
-            "{}:{}:{}",
-            TokenType::RefreshBearer.base_key(),
-            user_id,
-            fingerprint
-        ))
-        .await?;
+        let (fields, _): (HashMap<String, String>, i32) = redis::pipe()
+            .atomic()
+            .hgetall(&key)
+            .del(&key)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(OAuthState::from_fields(&fields))
+    }
+
+    // The code is bound to the fingerprint of the client that started the login
+    #[inline(always)]
+    pub async fn set_auth_code(
+        &self,
+        code: &str,
+        user_id: &i32,
+        fingerprint: &str,
+    ) -> AppResult<()> {
+        let key = format!("{AUTH_CODE_KEY}:{code}");
+        let mut conn = self.db.redis.get().await?;
+
+        redis::pipe()
+            .hset_multiple(
+                &key,
+                &[
+                    ("user_id", user_id.to_string()),
+                    ("fingerprint", fingerprint.to_owned()),
+                ],
+            )
+            .expire(&key, AUTH_CODE_EXPIRATION as i64)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // Returns the user and fingerprint of the code, it can't be used again either way
+    #[inline(always)]
+    pub async fn take_auth_code(&self, code: &str) -> AppResult<Option<(i32, String)>> {
+        let key = format!("{AUTH_CODE_KEY}:{code}");
+        let mut conn = self.db.redis.get().await?;
+
+        let (fields, _): (HashMap<String, String>, i32) = redis::pipe()
+            .atomic()
+            .hgetall(&key)
+            .del(&key)
+            .query_async(&mut *conn)
+            .await?;
+
+        let user_id = fields.get("user_id").and_then(|id| id.parse().ok());
+        let fingerprint = fields.get("fingerprint").cloned();
+
+        Ok(user_id.zip(fingerprint))
+    }
+
+    // Not expiring, users that never had their tokens revoked are in the generation 0
+    #[inline(always)]
+    pub async fn generation(&self, user_id: &i32) -> AppResult<i64> {
+        let mut conn = self.db.redis.get().await?;
+
+        let generation: Option<i64> = conn.get(format!("{GENERATION_KEY}:{user_id}")).await?;
+
+        Ok(generation.unwrap_or_default())
+    }
+
+    #[inline(always)]
+    pub async fn increment_generation(&self, user_id: &i32) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        conn.incr(format!("{GENERATION_KEY}:{user_id}"), 1).await?;
+
+        Ok(())
+    }
+
+    // Not expiring either, championships that never shared a link are in the generation 0
+    #[inline(always)]
+    pub async fn invite_generation(&self, championship_id: &i32) -> AppResult<i64> {
+        let mut conn = self.db.redis.get().await?;
+
+        let generation: Option<i64> = conn
+            .get(format!("{VIEWER_INVITE_KEY}:{championship_id}"))
+            .await?;
+
+        Ok(generation.unwrap_or_default())
+    }
+
+    #[inline(always)]
+    pub async fn increment_invite_generation(&self, championship_id: &i32) -> AppResult<i64> {
+        let mut conn = self.db.redis.get().await?;
+
+        let generation = conn
+            .incr(format!("{VIEWER_INVITE_KEY}:{championship_id}"), 1)
+            .await?;
+
+        Ok(generation)
+    }
+
+    // Every session is a hash with the id of the last refresh token issued and the device,
+    // the set of the user keeps track of them to list or revoke all of them
+    #[inline(always)]
+    pub async fn set_session(
+        &self,
+        user_id: &i32,
+        session_id: &str,
+        jti: &str,
+        device: &DeviceInfo<'_>,
+    ) -> AppResult<()> {
+        let key = session_key(user_id, session_id);
+        let sessions_key = format!("{SESSIONS_KEY}:{user_id}");
+        let now = Utc::now().timestamp();
+
+        let mut fields = vec![
+            ("jti", jti.to_owned()),
+            ("fingerprint", device.fingerprint.to_owned()),
+            ("created_at", now.to_string()),
+            ("last_used_at", now.to_string()),
+        ];
+
+        if let Some(ip) = &device.ip {
+            fields.push(("ip", ip.clone()));
+        }
+
+        if let Some(user_agent) = &device.user_agent {
+            fields.push(("user_agent", user_agent.clone()));
+        }
+
+        let mut conn = self.db.redis.get().await?;
+
+        redis::pipe()
+            .hset_multiple(&key, &fields)
+            .expire(&key, REFRESH_TOKEN_EXPIRATION as i64)
+            .sadd(&sessions_key, session_id)
+            .expire(&sessions_key, REFRESH_TOKEN_EXPIRATION as i64)
+            .query_async(&mut *conn)
+            .await?;
 
         Ok(())
     }
+
+    #[inline(always)]
+    pub async fn session(
+        &self,
+        user_id: &i32,
+        session_id: &str,
+    ) -> AppResult<Option<RefreshSession>> {
+        let mut conn = self.db.redis.get().await?;
+
+        let fields: HashMap<String, String> =
+            conn.hgetall(session_key(user_id, session_id)).await?;
+
+        Ok(RefreshSession::from_fields(session_id, &fields))
+    }
+
+    // Expired sessions are removed from the set of the user while listing them
+    #[inline(always)]
+    pub async fn sessions(&self, user_id: &i32) -> AppResult<Vec<RefreshSession>> {
+        let sessions_key = format!("{SESSIONS_KEY}:{user_id}");
+        let mut conn = self.db.redis.get().await?;
+
+        let session_ids: Vec<String> = conn.smembers(&sessions_key).await?;
+
+        if session_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+
+        session_ids.iter().for_each(|session_id| {
+            pipe.hgetall(session_key(user_id, session_id));
+        });
+
+        let all_fields: Vec<HashMap<String, String>> = pipe.query_async(&mut *conn).await?;
+        let mut sessions = Vec::with_capacity(session_ids.len());
+        let mut expired = Vec::new();
+
+        for (session_id, fields) in session_ids.iter().zip(all_fields) {
+            match RefreshSession::from_fields(session_id, &fields) {
+                Some(session) => sessions.push(session),
+                None => expired.push(session_id),
+            }
+        }
+
+        if !expired.is_empty() {
+            conn.srem(&sessions_key, expired).await?;
+        }
+
+        sessions.sort_by_key(|session| session.created_at);
+
+        Ok(sessions)
+    }
+
+    // Swaps the id of the refresh token only if it's still the last one issued,
+    // false means the token was already used
+    #[inline(always)]
+    pub async fn rotate_session(
+        &self,
+        user_id: &i32,
+        session_id: &str,
+        jti: &str,
+        new_jti: &str,
+    ) -> AppResult<bool> {
+        let mut conn = self.db.redis.get().await?;
+
+        let rotated: i32 = redis::cmd("EVAL")
+            .arg(ROTATE_SESSION_SCRIPT)
+            .arg(2)
+            .arg(session_key(user_id, session_id))
+            .arg(format!("{SESSIONS_KEY}:{user_id}"))
+            .arg(jti)
+            .arg(new_jti)
+            .arg(Utc::now().timestamp())
+            .arg(REFRESH_TOKEN_EXPIRATION)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(rotated == 1)
+    }
+
+    #[inline(always)]
+    pub async fn remove_session(&self, user_id: &i32, session_id: &str) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        redis::pipe()
+            .del(session_key(user_id, session_id))
+            .srem(format!("{SESSIONS_KEY}:{user_id}"), session_id)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub async fn remove_sessions(&self, user_id: &i32) -> AppResult<()> {
+        let sessions_key = format!("{SESSIONS_KEY}:{user_id}");
+        let mut conn = self.db.redis.get().await?;
+
+        let session_ids: Vec<String> = conn.smembers(&sessions_key).await?;
+        let mut pipe = redis::pipe();
+
+        session_ids.iter().for_each(|session_id| {
+            pipe.del(session_key(user_id, session_id));
+        });
+
+        pipe.del(&sessions_key);
+        pipe.query_async(&mut *conn).await?;
+
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn session_key(user_id: &i32, session_id: &str) -> String {
+    format!(
+        "{}:{user_id}:{session_id}",
+        TokenType::RefreshBearer.base_key()
+    )
 }
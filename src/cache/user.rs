@@ -43,6 +43,17 @@ impl UserCache {
 
         Ok(None)
     }
+
+    // Lookups by the old address would keep finding the user after an email change
+    #[inline(always)]
+    pub async fn delete_email(&self, email: &str) -> AppResult<()> {
+        let mut conn = self.db.redis.get().await?;
+
+        conn.del(format!("{REDIS_USER_PREFIX}:{EMAIL}:{}", email))
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
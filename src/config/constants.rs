@@ -1,18 +1,84 @@
+use super::RateLimitRule;
 use std::time::Duration;
 
-// Google auth
-pub const GOOGLE_REDIRECT: &str = "https://intellitelemetry.live/auth/google/callback";
+// External auth
+pub const OAUTH_FRONTEND_URL: &str = "https://intellitelemetry.live/auth";
+pub const OAUTH_STATE_EXPIRATION: u64 = 10 * 60;
+pub const OAUTH_STATE_LENGTH: usize = 32;
+pub const AUTH_CODE_EXPIRATION: u64 = 60;
+pub const PKCE_VERIFIER_LENGTH: usize = 64;
+pub const GOOGLE_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 pub const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
-pub const GOOGLE_USER_INFO: &str = "https://www.googleapis.com/oauth2/v2/userinfo";
+pub const GOOGLE_ISSUERS: [&str; 2] = ["https://accounts.google.com", "accounts.google.com"];
+pub const DISCORD_AUTHORIZE_URL: &str = "https://discord.com/oauth2/authorize";
+pub const DISCORD_TOKEN_URL: &str = "https://discord.com/api/oauth2/token";
+pub const DISCORD_USER_INFO: &str = "https://discord.com/api/users/@me";
+pub const DISCORD_AVATAR_URL: &str = "https://cdn.discordapp.com/avatars";
+pub const STEAM_OPENID_URL: &str = "https://steamcommunity.com/openid/login";
+pub const STEAM_CLAIMED_ID_PREFIX: &str = "https://steamcommunity.com/openid/id/";
+pub const STEAM_PLAYER_SUMMARIES: &str =
+    "https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/";
 
 // Tokens
 pub const GENERIC_TOKEN_EXPIRATION: u64 = 15 * 60;
 pub const REFRESH_TOKEN_EXPIRATION: u64 = 15 * 60 * 24 * 30;
+pub const INVITE_EXPIRATION: i64 = 60 * 60 * 24 * 7;
+pub const JWT_KEYS_MANIFEST: &str = "certs/jwt/keys.json";
+pub const JWT_LEGACY_KEY: &str = "certs/jsonwebtoken.key";
+pub const JWT_LEGACY_PUBLIC_KEY: &str = "certs/jsonwebtoken.crt";
+pub const JWT_LEGACY_KID: &str = "legacy";
+pub const JWT_KEYS_RELOAD_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Two factor
+pub const TOTP_ISSUER: &str = "Intelli";
+pub const TOTP_DIGITS: usize = 6;
+pub const TOTP_STEP: u64 = 30;
+pub const RECOVERY_CODES: usize = 10;
+pub const RECOVERY_CODE_LENGTH: usize = 10;
+pub const MAX_TWO_FACTOR_ATTEMPTS: i64 = 5;
+
+// Rate limits, per ip unless they're for the account
+pub const LOGIN_LIMIT: RateLimitRule = RateLimitRule::new("login", 20, 60);
+pub const LOGIN_ACCOUNT_LIMIT: RateLimitRule = RateLimitRule::new("login:account", 10, 15 * 60);
+pub const TWO_FACTOR_LIMIT: RateLimitRule = RateLimitRule::new("login:2fa", 10, 60);
+pub const REGISTER_LIMIT: RateLimitRule = RateLimitRule::new("register", 5, 60 * 60);
+pub const FORGOT_PASSWORD_LIMIT: RateLimitRule = RateLimitRule::new("forgot_password", 5, 15 * 60);
+pub const FORGOT_PASSWORD_ACCOUNT_LIMIT: RateLimitRule =
+    RateLimitRule::new("forgot_password:account", 3, 60 * 60);
+pub const RESET_PASSWORD_LIMIT: RateLimitRule = RateLimitRule::new("reset_password", 10, 15 * 60);
+pub const RESEND_VERIFICATION_LIMIT: RateLimitRule =
+    RateLimitRule::new("resend_verification", 5, 15 * 60);
+pub const RESEND_VERIFICATION_ACCOUNT_LIMIT: RateLimitRule =
+    RateLimitRule::new("resend_verification:account", 3, 60 * 60);
+pub const EMAIL_CHANGE_ACCOUNT_LIMIT: RateLimitRule =
+    RateLimitRule::new("email_change:account", 3, 60 * 60);
+
+// Lockout, every few failed logins in a row lock the account twice as long as the last time
+pub const LOCKOUT_THRESHOLD: i64 = 5;
+pub const LOCKOUT_BASE_DURATION: u64 = 60;
+pub const LOCKOUT_MAX_DURATION: u64 = 60 * 60 * 24;
+pub const FAILED_LOGINS_EXPIRATION: u64 = 60 * 60 * 24;
+
+// Accounts that never verified their email
+pub const UNVERIFIED_ACCOUNT_DAYS: i32 = 7;
+pub const UNVERIFIED_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Api keys
+pub const API_KEY_PREFIX: &str = "intelli_";
+pub const API_KEY_LENGTH: usize = 40;
+pub const API_KEY_VISIBLE_CHARS: usize = 4;
+pub const API_KEYS_LIMIT: i64 = 10;
+pub const API_KEY_USAGE_INTERVAL_SECS: f64 = 60.0;
+
+// Championships
+pub const JOIN_CODE_LENGTH: usize = 10;
 
 // Redis
 pub const REDIS_USER_PREFIX: &str = "user";
 pub const REDIS_CACHE_EXPIRATION: u64 = 60 * 60 * 24;
 pub const REDIS_CHAMPIONSHIP_PREFIX: &str = "championship";
+pub const REDIS_STATS_PREFIX: &str = "stats";
+pub const REDIS_STATS_EXPIRATION: u64 = 60 * 60;
 pub const REDIS_F123_PREFIX: &str = "f123:championships";
 pub const REDIS_F123_PERSISTENCE: u64 = 15 * 60;
 
@@ -22,8 +88,11 @@ pub const BUFFER_SIZE: usize = 1460;
 pub const SOCKET_HOST: &str = "0.0.0.0";
 pub const SOCKET_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 pub const BATCHING_INTERVAL: Duration = Duration::from_millis(700);
+pub const STREAM_KEEP_ALIVE: Duration = Duration::from_secs(15);
+pub const STREAM_BUFFER: usize = 32;
 
 // Session
 pub const HISTORY_INTERVAL: Duration = Duration::from_secs(1);
 pub const SESSION_INTERVAL: Duration = Duration::from_secs(10);
 pub const MOTION_INTERVAL: Duration = Duration::from_millis(700);
+pub const MOTION_KEYFRAME_INTERVAL: u32 = 10;
@@ -1,6 +1,10 @@
 pub(crate) mod constants;
 mod database;
+mod jwt_keys;
 mod local_tracing;
+mod rate_limit;
 
 pub(crate) use database::*;
+pub(crate) use jwt_keys::*;
 pub(crate) use local_tracing::*;
+pub(crate) use rate_limit::*;
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+// Requests allowed in a sliding window, the name keeps the counters of every route apart
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitRule {
+    pub name: &'static str,
+    pub limit: u64,
+    pub window: Duration,
+}
+
+impl RateLimitRule {
+    pub const fn new(name: &'static str, limit: u64, window_secs: u64) -> Self {
+        Self {
+            name,
+            limit,
+            window: Duration::from_secs(window_secs),
+        }
+    }
+}
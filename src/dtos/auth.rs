@@ -2,40 +2,145 @@ use crate::entity::Provider;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 use serde_trim::{option_string_trim, string_trim};
+use std::collections::HashMap;
 
-#[derive(Deserialize)]
-pub struct GoogleCallbackQuery {
-    pub code: String,
+// Saved between the redirect to the provider and the callback, it can only be used once.
+// The user is set when an account is being linked, the fingerprint when logging in
+#[derive(Debug)]
+pub struct OAuthState {
+    pub provider: Provider,
+    pub verifier: String,
+    pub nonce: String,
+    pub user_id: Option<i32>,
+    pub fingerprint: Option<String>,
+}
+
+impl OAuthState {
+    pub fn to_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("provider", self.provider.name().to_owned()),
+            ("verifier", self.verifier.clone()),
+            ("nonce", self.nonce.clone()),
+        ];
+
+        if let Some(user_id) = self.user_id {
+            fields.push(("user_id", user_id.to_string()));
+        }
+
+        if let Some(fingerprint) = &self.fingerprint {
+            fields.push(("fingerprint", fingerprint.clone()));
+        }
+
+        fields
+    }
+
+    // None when the state expired or was already used
+    pub fn from_fields(fields: &HashMap<String, String>) -> Option<Self> {
+        let provider = match fields.get("provider")?.as_str() {
+            "google" => Provider::Google,
+            "discord" => Provider::Discord,
+            "steam" => Provider::Steam,
+            _ => None?,
+        };
+
+        Some(OAuthState {
+            provider,
+            verifier: fields.get("verifier")?.clone(),
+            nonce: fields.get("nonce")?.clone(),
+            user_id: fields.get("user_id").and_then(|id| id.parse().ok()),
+            fingerprint: fields.get("fingerprint").cloned(),
+        })
+    }
+}
+
+// Account of the user in the provider, the email is only trusted when the provider verified it
+#[derive(Debug)]
+pub struct ExternalAccount {
+    pub provider: Provider,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub username: String,
+    pub avatar: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum OAuthOutcome {
+    Login { user_id: i32, fingerprint: String },
+    Linked,
 }
 
 #[derive(Debug, Serialize)]
-pub struct GoogleTokenRequest<'a> {
-    pub client_id: &'a str,
-    pub client_secret: &'a str,
-    pub code: &'a str,
+pub struct OAuthTokenRequest<'a> {
     pub grant_type: &'a str,
+    pub code: &'a str,
     pub redirect_uri: &'a str,
+    pub client_id: &'a str,
+    pub client_secret: &'a str,
+    pub code_verifier: &'a str,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct GoogleAuthResponse {
+pub struct OAuthTokenResponse {
     pub access_token: String,
-    pub expires_in: i64,
-    pub scope: String,
-    pub token_type: String,
-    pub id_token: String,
+    pub id_token: Option<String>,
 }
 
+// Claims of the OpenID Connect id token, issuer and audience are checked while decoding
 #[derive(Debug, Deserialize)]
-pub struct GoogleUserInfo {
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordUser {
     pub id: String,
-    pub email: String,
-    pub verified_email: bool,
-    pub name: String,
-    pub given_name: Option<String>,
-    pub family_name: Option<String>,
-    pub picture: String,
-    pub locale: String,
+    pub username: String,
+    pub global_name: Option<String>,
+    pub avatar: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SteamSummariesResponse {
+    pub response: SteamPlayers,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SteamPlayers {
+    pub players: Vec<SteamPlayer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SteamPlayer {
+    pub steamid: String,
+    pub personaname: String,
+    pub avatarfull: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ProviderPath {
+    pub provider: Provider,
+}
+
+// One time code the front end gets from the callback redirect instead of the tokens
+#[derive(Deserialize, Validate)]
+pub struct AuthCodeExchange {
+    #[garde(ascii, length(min = 32, max = 32))]
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct AuthorizeUrl {
+    pub url: String,
 }
 
 #[derive(Serialize)]
@@ -44,9 +149,19 @@ pub struct AuthResponse {
     pub refresh_token: String,
 }
 
+// Sent instead of the tokens when the account has two factor authentication enabled
 #[derive(Serialize)]
-pub struct RefreshResponse {
-    pub access_token: String,
+pub struct TwoFactorChallenge {
+    pub two_factor_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TwoFactorLogin {
+    #[garde(length(min = 1))]
+    pub token: String,
+    #[garde(length(min = 6, max = 11))]
+    #[serde(deserialize_with = "string_trim")]
+    pub code: String,
 }
 
 #[derive(Deserialize, Debug, Validate)]
@@ -62,20 +177,6 @@ pub struct RegisterUserDto {
     pub password: Option<String>,
     #[garde(inner(length(min = 10, max = 100)))]
     pub avatar: Option<String>,
-    #[garde(skip)]
-    pub provider: Option<Provider>,
-}
-
-impl From<GoogleUserInfo> for RegisterUserDto {
-    fn from(value: GoogleUserInfo) -> Self {
-        Self {
-            username: value.name,
-            email: value.email,
-            password: None,
-            avatar: Some(value.picture),
-            provider: Some(Provider::Google),
-        }
-    }
 }
 
 #[derive(Deserialize, Validate)]
@@ -107,6 +208,13 @@ pub struct ResetPasswordQuery {
     pub token: String,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResendVerificationDto {
+    #[garde(email)]
+    #[serde(deserialize_with = "string_trim")]
+    pub email: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct VerifyEmailParams {
     pub token: String,
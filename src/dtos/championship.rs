@@ -1,4 +1,7 @@
-use crate::entity::Category;
+use crate::{
+    entity::{Category, ChampionshipRole, Visibility},
+    protos::socket_control::Encoding,
+};
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 use serde_trim::{option_string_trim, string_trim};
@@ -23,6 +26,43 @@ pub struct UpdateChampionship {
     pub category: Option<Category>,
     #[garde(skip)]
     pub season: Option<i16>,
+    #[garde(skip)]
+    pub visibility: Option<Visibility>,
+    #[garde(inner(length(min = 1, max = 22), inner(range(min = 0, max = 100))))]
+    pub points_table: Option<Vec<i16>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateMemberRole {
+    #[garde(skip)]
+    pub role: ChampionshipRole,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TransferOwnership {
+    #[garde(range(min = 600000000, max = 699999999))]
+    pub user_id: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct InviteToken {
+    #[garde(length(min = 1))]
+    #[serde(deserialize_with = "string_trim")]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateJoinCode {
+    #[garde(inner(range(min = 1, max = 1000)))]
+    pub max_uses: Option<i32>,
+    // Hours until the code expires, never expires if not set
+    #[garde(inner(range(min = 1, max = 8760)))]
+    pub expires_in: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JoinCodeResponse {
+    pub code: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -31,6 +71,18 @@ pub struct SocketStatus {
     pub connections: usize,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SocketAuthQuery {
+    pub ticket: Option<String>,
+    #[serde(default)]
+    pub encoding: Encoding,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ViewerTicket {
+    pub ticket: String,
+}
+
 #[allow(dead_code)]
 pub struct ChampionshipCacheData {
     pub session_data: Vec<u8>,
@@ -52,3 +104,25 @@ pub struct ChampionshipAndUserIdPath {
     #[garde(range(min = 600000000, max = 699999999))]
     pub user_id: i32,
 }
+
+#[derive(Deserialize, Validate)]
+pub struct ChampionshipAndInviteIdPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(range(min = 800000000, max = 899999999))]
+    pub invite_id: i32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct JoinCodePath {
+    #[garde(alphanumeric, length(min = 10, max = 16))]
+    pub code: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ChampionshipAndJoinCodePath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(alphanumeric, length(min = 10, max = 16))]
+    pub code: String,
+}
@@ -11,6 +11,18 @@ pub struct VerifyEmail<'a> {
 #[template(path = "email_verified.stpl")]
 pub struct EmailVerified {}
 
+#[derive(TemplateOnce)]
+#[template(path = "confirm_email_change.stpl")]
+pub struct ConfirmEmailChange<'a> {
+    pub confirmation_link: &'a str,
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "email_change_requested.stpl")]
+pub struct EmailChangeRequested<'a> {
+    pub new_email: &'a str,
+}
+
 #[derive(TemplateOnce)]
 #[template(path = "reset_password.stpl")]
 pub struct ResetPassword<'a> {
@@ -21,6 +33,22 @@ pub struct ResetPassword<'a> {
 #[template(path = "password_changed.stpl")]
 pub struct PasswordChanged {}
 
+#[derive(TemplateOnce)]
+#[template(path = "account_locked.stpl")]
+pub struct AccountLocked {
+    pub failed_logins: i64,
+    pub locked_minutes: u64,
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "championship_invite.stpl")]
+pub struct ChampionshipInvitation<'a> {
+    pub invited_by: &'a str,
+    pub championship_name: &'a str,
+    pub accept_link: &'a str,
+    pub decline_link: &'a str,
+}
+
 #[derive(Debug)]
 pub struct EmailUser<'a> {
     pub username: &'a str,
@@ -2,7 +2,12 @@ mod auth;
 mod championship;
 mod email;
 mod f123;
+mod record;
+mod result;
+mod round;
 mod server;
+mod stewarding;
+mod team;
 mod token;
 mod user;
 
@@ -10,6 +15,11 @@ pub(crate) use auth::*;
 pub(crate) use championship::*;
 pub(crate) use email::*;
 pub(crate) use f123::*;
+pub(crate) use record::*;
+pub(crate) use result::*;
+pub(crate) use round::*;
 pub(crate) use server::*;
+pub(crate) use stewarding::*;
+pub(crate) use team::*;
 pub(crate) use token::*;
 pub(crate) use user::*;
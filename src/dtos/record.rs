@@ -0,0 +1,17 @@
+use crate::entity::Category;
+use garde::Validate;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordsQuery {
+    #[garde(skip)]
+    pub category: Category,
+    #[garde(skip)]
+    pub season: i16,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TrackIdPath {
+    #[garde(range(min = 0, max = 127))]
+    pub track_id: i16,
+}
@@ -0,0 +1,35 @@
+use crate::entity::AdjustmentKind;
+use garde::Validate;
+use serde::Deserialize;
+use serde_trim::string_trim;
+
+// Value is in seconds for time penalties and in places for position changes,
+// positive values move the car back. Disqualifications don't take a value
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApplyAdjustment {
+    #[garde(range(min = 0, max = 21))]
+    pub car_idx: i16,
+    #[garde(skip)]
+    pub kind: AdjustmentKind,
+    #[garde(inner(range(min = -21, max = 999)))]
+    pub value: Option<i16>,
+    #[garde(length(min = 3, max = 500))]
+    #[serde(deserialize_with = "string_trim")]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AssignDriver {
+    #[garde(range(min = 0, max = 21))]
+    pub car_idx: i16,
+    #[garde(range(min = 600000000, max = 699999999))]
+    pub user_id: i32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct SessionPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(skip)]
+    pub session_uid: i64,
+}
@@ -0,0 +1,39 @@
+use crate::entity::WeekendFormat;
+use garde::Validate;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRound {
+    #[garde(range(min = 1, max = 50))]
+    pub round_num: i16,
+    #[garde(range(min = 0, max = 127))]
+    pub track_id: i16,
+    #[garde(skip)]
+    pub format: WeekendFormat,
+    #[garde(inner(range(min = 2, max = 22)))]
+    pub reverse_grid_count: Option<i16>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRound {
+    #[garde(skip)]
+    pub format: Option<WeekendFormat>,
+    #[garde(inner(range(min = 2, max = 22)))]
+    pub reverse_grid_count: Option<i16>,
+    #[garde(skip)]
+    pub qualifying_session_uid: Option<i64>,
+    #[garde(skip)]
+    pub race_session_uid: Option<i64>,
+    #[garde(skip)]
+    pub sprint_qualifying_session_uid: Option<i64>,
+    #[garde(skip)]
+    pub sprint_session_uid: Option<i64>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RoundIdPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(range(min = 1))]
+    pub round_id: i32,
+}
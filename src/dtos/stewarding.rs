@@ -0,0 +1,46 @@
+use crate::entity::{ProtestStatus, Sanction};
+use garde::Validate;
+use serde::Deserialize;
+use serde_trim::{option_string_trim, string_trim};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct FileProtest {
+    #[garde(skip)]
+    pub session_uid: i64,
+    #[garde(inner(range(min = 1)))]
+    pub incident_id: Option<i32>,
+    #[garde(range(min = 600000000, max = 699999999))]
+    pub accused_id: i32,
+    #[garde(inner(range(min = 0, max = 255)))]
+    pub lap_num: Option<i16>,
+    #[garde(length(min = 10, max = 1000))]
+    #[serde(deserialize_with = "string_trim")]
+    pub description: String,
+}
+
+// Upheld protests can come with a sanction for the accused driver
+#[derive(Debug, Deserialize, Validate)]
+pub struct RuleProtest {
+    #[garde(skip)]
+    pub status: ProtestStatus,
+    #[serde(default, deserialize_with = "option_string_trim")]
+    #[garde(inner(length(max = 1000)))]
+    pub decision: Option<String>,
+    #[garde(skip)]
+    pub sanction: Option<Sanction>,
+    #[garde(inner(range(min = 1, max = 999)))]
+    pub value: Option<i16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncidentsQuery {
+    pub session_uid: Option<i64>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ProtestIdPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(range(min = 1))]
+    pub protest_id: i32,
+}
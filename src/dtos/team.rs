@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use serde::Deserialize;
+use serde_trim::{option_string_trim, string_trim};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTeam {
+    #[garde(length(min = 2, max = 50))]
+    #[serde(deserialize_with = "string_trim")]
+    pub name: String,
+    // Team id of the game, used to match the participants of the session
+    #[garde(range(min = 0, max = 255))]
+    pub game_team_id: i16,
+    #[garde(custom(hex_color))]
+    pub primary_color: String,
+    #[garde(inner(custom(hex_color)))]
+    pub secondary_color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTeam {
+    #[serde(default, deserialize_with = "option_string_trim")]
+    #[garde(inner(length(min = 2, max = 50)))]
+    pub name: Option<String>,
+    #[garde(inner(range(min = 0, max = 255)))]
+    pub game_team_id: Option<i16>,
+    #[garde(inner(custom(hex_color)))]
+    pub primary_color: Option<String>,
+    #[garde(inner(custom(hex_color)))]
+    pub secondary_color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddTeamDriver {
+    #[garde(range(min = 600000000, max = 699999999))]
+    pub user_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LineupQuery {
+    // Line-up active at this moment, the current one if not set
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TeamIdPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(range(min = 900000000, max = 999999999))]
+    pub team_id: i32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TeamDriverPath {
+    #[garde(range(min = 700000000, max = 799999999))]
+    pub id: i32,
+    #[garde(range(min = 900000000, max = 999999999))]
+    pub team_id: i32,
+    #[garde(range(min = 600000000, max = 699999999))]
+    pub user_id: i32,
+}
+
+// Colours are stored as `#rrggbb`
+fn hex_color(value: &str, _: &()) -> garde::Result {
+    match value.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(()),
+        _ => Err(garde::Error::new("not a valid hex colour")),
+    }
+}
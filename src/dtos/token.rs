@@ -1,20 +1,92 @@
-use chrono::{Duration, Local};
+use crate::config::constants::INVITE_EXPIRATION;
+use chrono::{DateTime, Duration, Local, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 //* Token Type Enum
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum TokenType {
     Bearer,
     Email,
+    EmailChange,
     ResetPassword,
     RefreshBearer,
+    Viewer,
+    ViewerInvite,
+    Invite,
+    TwoFactor,
 }
 
+// Access tokens carry the token generation of the user, bumping it revokes all of them
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TokenClaim {
     pub exp: usize,
     pub sub: i32,
     pub token_type: TokenType,
+    #[serde(default)]
+    pub generation: i64,
+}
+
+// Live stream tickets are bound to a championship instead of a user, invite links carry
+// the invite generation of the championship so a new link revokes the previous ones
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ViewerClaim {
+    pub exp: usize,
+    pub sub: i32,
+    pub user_id: Option<i32>,
+    pub token_type: TokenType,
+    #[serde(default)]
+    pub generation: i64,
+}
+
+// Refresh tokens belong to a session, every refresh issues a new jti and the
+// previous token of the session stops working
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshClaim {
+    pub exp: usize,
+    pub sub: i32,
+    pub sid: String,
+    pub jti: String,
+    pub token_type: TokenType,
+}
+
+// Device the session was started from
+#[derive(Debug)]
+pub struct DeviceInfo<'a> {
+    pub fingerprint: &'a str,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefreshSession {
+    pub id: String,
+    pub fingerprint: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl RefreshSession {
+    // Fields of the session hash, None when the session doesn't exist anymore
+    pub fn from_fields(id: &str, fields: &HashMap<String, String>) -> Option<Self> {
+        let timestamp = |field: &str| {
+            fields
+                .get(field)
+                .and_then(|value| value.parse::<i64>().ok())
+                .and_then(|value| DateTime::from_timestamp(value, 0))
+        };
+
+        Some(RefreshSession {
+            id: id.to_owned(),
+            fingerprint: fields.get("fingerprint")?.clone(),
+            ip: fields.get("ip").cloned(),
+            user_agent: fields.get("user_agent").cloned(),
+            created_at: timestamp("created_at")?,
+            last_used_at: timestamp("last_used_at")?,
+        })
+    }
 }
 
 // Token Type Implementation
@@ -23,6 +95,10 @@ impl TokenType {
         let minutes = match self {
             TokenType::RefreshBearer => Duration::days(7),
             TokenType::Bearer => Duration::days(1),
+            TokenType::ViewerInvite => Duration::days(7),
+            TokenType::Viewer => Duration::minutes(1),
+            TokenType::Invite => Duration::seconds(INVITE_EXPIRATION),
+            TokenType::TwoFactor => Duration::minutes(5),
             _ => Duration::minutes(15),
         };
 
@@ -35,8 +111,10 @@ impl TokenType {
     pub fn base_key(&self) -> &str {
         match self {
             TokenType::Email => "tokens:email",
+            TokenType::EmailChange => "tokens:email_change",
             TokenType::ResetPassword => "tokens:reset_password",
             TokenType::RefreshBearer => "tokens:refresh_access",
+            TokenType::TwoFactor => "tokens:two_factor",
             _ => panic!("Invalid token type"),
         }
     }
@@ -1,6 +1,9 @@
-use crate::entity::{Championship, User};
+use crate::entity::{ApiKey, ApiKeyScope, Championship, ChampionshipRole, User};
 use garde::Validate;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{value::StrDeserializer, Error},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_trim::{option_string_trim, string_trim};
 use std::sync::Arc;
 
@@ -14,11 +17,24 @@ pub struct UpdateUser {
     pub avatar: Option<String>,
 }
 
+// Local users confirm the change with their password
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeEmail {
+    #[serde(deserialize_with = "string_trim")]
+    #[garde(email)]
+    pub email: String,
+    #[garde(skip)]
+    pub password: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct AddUser {
     #[serde(deserialize_with = "string_trim")]
     #[garde(email)]
     pub email: String,
+    #[serde(default)]
+    #[garde(skip)]
+    pub role: Option<ChampionshipRole>,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,3 +48,77 @@ pub struct UserIdPath {
     #[garde(range(min = 600000000, max = 699999999))]
     pub id: i32,
 }
+
+// Authenticator code or one of the recovery codes
+#[derive(Debug, Deserialize, Validate)]
+pub struct TwoFactorCode {
+    #[garde(length(min = 6, max = 11))]
+    #[serde(deserialize_with = "string_trim")]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetup {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SessionIdPath {
+    #[garde(ascii, length(min = 32, max = 32))]
+    pub session_id: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKey {
+    #[serde(deserialize_with = "string_trim")]
+    #[garde(length(min = 1, max = 32))]
+    pub name: String,
+    #[serde(deserialize_with = "scope_list")]
+    #[garde(length(min = 1, max = 4))]
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApiKeyIdPath {
+    #[garde(range(min = 1))]
+    pub key_id: i32,
+}
+
+// The key is only returned when it's created
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+// Forms can't have lists, scopes are sent separated by commas
+fn scope_list<'de, D>(deserializer: D) -> Result<Vec<ApiKeyScope>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let scopes = String::deserialize(deserializer)?;
+    let mut scope_list = Vec::new();
+
+    for scope in scopes
+        .split(',')
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+    {
+        let scope = ApiKeyScope::deserialize(StrDeserializer::<D::Error>::new(scope))?;
+
+        if scope_list.contains(&scope) {
+            Err(D::Error::custom("duplicated scope"))?
+        }
+
+        scope_list.push(scope);
+    }
+
+    Ok(scope_list)
+}
@@ -0,0 +1,123 @@
+use super::FromRow;
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use postgres_derive::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "api_key_scope")]
+pub enum ApiKeyScope {
+    #[postgres(name = "ReadProfile")]
+    ReadProfile,
+    #[postgres(name = "ReadChampionships")]
+    ReadChampionships,
+    #[postgres(name = "ReadLiveData")]
+    ReadLiveData,
+    #[postgres(name = "ManageSockets")]
+    ManageSockets,
+}
+
+impl ApiKeyScope {
+    // Scope needed to call a route with an api key, None means the route needs a session.
+    // Routes are listed one by one so new ones stay session only until they're added here,
+    // managing the account, keys included, and the members of a championship never are
+    pub fn required(method: &str, path: &str) -> Option<Self> {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["user", "data"]) | ("GET", ["user", _, "stats"]) => Some(Self::ReadProfile),
+            ("GET", ["championships", _, "socket", "status"]) => Some(Self::ReadLiveData),
+            ("GET", ["championships", _, "socket", "start" | "stop"]) => Some(Self::ManageSockets),
+            ("GET", ["championships", _, "live", "ticket" | "stream" | "snapshot"]) => {
+                Some(Self::ReadLiveData)
+            }
+            ("GET", ["championships", "all"])
+            | ("GET", ["championships", _])
+            | ("GET", ["championships", _, "teams"])
+            | ("GET", ["championships", _, "teams", "lineup"])
+            | ("GET", ["championships", _, "standings", "constructors"])
+            | ("GET", ["championships", _, "incidents" | "protests" | "penalties"])
+            | ("GET", ["championships", _, "rounds"])
+            | ("GET", ["championships", _, "rounds", _, "grid"])
+            | ("GET", ["championships", _, "sessions"])
+            | ("GET", ["championships", _, "sessions", _, "results" | "laps" | "adjustments"])
+            | ("GET", ["records"])
+            | ("GET", ["records", "tracks" | "drivers", _]) => Some(Self::ReadChampionships),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKey {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub user_id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for ApiKey {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ApiKey {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            name: row.try_get("name")?,
+            prefix: row.try_get("prefix")?,
+            scopes: row.try_get("scopes")?,
+            last_used_at: row.try_get("last_used_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiKeyScope;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/rounds"),
+            Some(ApiKeyScope::ReadChampionships)
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/socket/start"),
+            Some(ApiKeyScope::ManageSockets)
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/socket/status"),
+            Some(ApiKeyScope::ReadLiveData)
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/live/ticket"),
+            Some(ApiKeyScope::ReadLiveData)
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/records/tracks/5"),
+            Some(ApiKeyScope::ReadChampionships)
+        );
+        assert_eq!(ApiKeyScope::required("PUT", "/championships/1"), None);
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/join-codes"),
+            None
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/invites"),
+            None
+        );
+        assert_eq!(
+            ApiKeyScope::required("GET", "/championships/1/join-requests"),
+            None
+        );
+        assert_eq!(
+            ApiKeyScope::required("POST", "/championships/1/live/invite"),
+            None
+        );
+        assert_eq!(ApiKeyScope::required("GET", "/user/api-keys"), None);
+        assert_eq!(ApiKeyScope::required("DELETE", "/user/sessions"), None);
+    }
+}
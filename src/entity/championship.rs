@@ -18,6 +18,65 @@ pub enum Category {
     F2,
 }
 
+#[derive(
+    Debug,
+    Archive,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RDeserialize,
+    RSerialize,
+    Serialize,
+    Deserialize,
+    FromSql,
+    ToSql,
+)]
+#[postgres(name = "visibility")]
+#[archive(check_bytes)]
+pub enum Visibility {
+    #[postgres(name = "Public")]
+    Public,
+    #[postgres(name = "Members")]
+    Members,
+    #[postgres(name = "Invite")]
+    Invite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "championship_role")]
+pub enum ChampionshipRole {
+    #[postgres(name = "Owner")]
+    Owner,
+    #[postgres(name = "Admin")]
+    Admin,
+    #[postgres(name = "Steward")]
+    Steward,
+    #[postgres(name = "Driver")]
+    Driver,
+    #[postgres(name = "Viewer")]
+    Viewer,
+}
+
+impl ChampionshipRole {
+    #[inline(always)]
+    fn level(&self) -> u8 {
+        match self {
+            ChampionshipRole::Owner => 4,
+            ChampionshipRole::Admin => 3,
+            ChampionshipRole::Steward => 2,
+            ChampionshipRole::Driver => 1,
+            ChampionshipRole::Viewer => 0,
+        }
+    }
+
+    // Every role includes the permissions of the roles below it
+    #[inline(always)]
+    pub fn at_least(&self, role: ChampionshipRole) -> bool {
+        self.level() >= role.level()
+    }
+}
+
 #[derive(Debug, Serialize, Clone, Archive, RDeserialize, RSerialize)]
 #[archive(check_bytes)]
 pub struct Championship {
@@ -28,6 +87,9 @@ pub struct Championship {
     pub season: i16,
     pub driver_count: i16,
     pub owner_id: i32,
+    pub visibility: Visibility,
+    // Points of every position in race sessions, starting with the winner
+    pub points_table: Vec<i16>,
     #[serde(skip_serializing)]
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing)]
@@ -44,8 +106,79 @@ impl FromRow for Championship {
             season: row.try_get("season")?,
             driver_count: row.try_get("driver_count")?,
             owner_id: row.try_get("owner_id")?,
+            visibility: row.try_get("visibility")?,
+            points_table: row.try_get("points_table")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
         })
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct ChampionshipInvite {
+    pub id: i32,
+    pub championship_id: i32,
+    pub user_id: i32,
+    pub invited_by: i32,
+    pub role: ChampionshipRole,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for ChampionshipInvite {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ChampionshipInvite {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            user_id: row.try_get("user_id")?,
+            invited_by: row.try_get("invited_by")?,
+            role: row.try_get("role")?,
+            expires_at: row.try_get("expires_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChampionshipJoinCode {
+    pub code: String,
+    pub championship_id: i32,
+    pub created_by: i32,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for ChampionshipJoinCode {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ChampionshipJoinCode {
+            code: row.try_get("code")?,
+            championship_id: row.try_get("championship_id")?,
+            created_by: row.try_get("created_by")?,
+            max_uses: row.try_get("max_uses")?,
+            uses: row.try_get("uses")?,
+            expires_at: row.try_get("expires_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChampionshipJoinRequest {
+    pub championship_id: i32,
+    pub user_id: i32,
+    pub code: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for ChampionshipJoinRequest {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ChampionshipJoinRequest {
+            championship_id: row.try_get("championship_id")?,
+            user_id: row.try_get("user_id")?,
+            code: row.try_get("code")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
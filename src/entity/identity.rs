@@ -0,0 +1,26 @@
+use super::{FromRow, Provider};
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use serde::Serialize;
+
+// External account linked to a user
+#[derive(Debug, Serialize)]
+pub struct Identity {
+    pub provider: Provider,
+    #[serde(skip_serializing)]
+    pub user_id: i32,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for Identity {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(Identity {
+            provider: row.try_get("provider")?,
+            user_id: row.try_get("user_id")?,
+            email: row.try_get("email")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use super::FromRow;
+use crate::error::AppResult;
+use ahash::AHashMap;
+use deadpool_postgres::tokio_postgres::Row;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLap {
+    pub car_idx: i16,
+    pub lap_num: i16,
+    pub lap_time_in_ms: i32,
+    pub sector1_time_in_ms: i32,
+    pub sector2_time_in_ms: i32,
+    pub sector3_time_in_ms: i32,
+    pub lap_valid_bit_flags: i16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TyreStint {
+    pub car_idx: i16,
+    pub stint: i16,
+    pub end_lap: i16,
+    pub tyre_actual_compound: i16,
+    pub tyre_visual_compound: i16,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CarLaps {
+    pub car_idx: i16,
+    pub laps: Vec<SessionLap>,
+    pub stints: Vec<TyreStint>,
+}
+
+// Cars ordered by the time they needed to complete the lap, cars that didn't complete it are left out
+#[derive(Debug, Serialize)]
+pub struct LapPositions {
+    pub lap_num: i16,
+    pub cars: Vec<i16>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LapChart {
+    pub cars: Vec<CarLaps>,
+    pub positions: Vec<LapPositions>,
+}
+
+impl LapChart {
+    // Laps and stints have to be ordered by car and lap/stint
+    pub fn new(laps: Vec<SessionLap>, stints: Vec<TyreStint>) -> Self {
+        let mut cars: Vec<CarLaps> = Vec::new();
+        let mut elapsed: AHashMap<i16, Vec<i64>> = AHashMap::default();
+
+        for lap in laps {
+            let total = elapsed.entry(lap.car_idx).or_default();
+            let previous = total.last().copied().unwrap_or_default();
+            total.push(previous + lap.lap_time_in_ms as i64);
+
+            match cars.last_mut() {
+                Some(car) if car.car_idx == lap.car_idx => car.laps.push(lap),
+                _ => cars.push(CarLaps {
+                    car_idx: lap.car_idx,
+                    laps: vec![lap],
+                    stints: Vec::new(),
+                }),
+            }
+        }
+
+        for stint in stints {
+            if let Some(car) = cars.iter_mut().find(|car| car.car_idx == stint.car_idx) {
+                car.stints.push(stint);
+            }
+        }
+
+        let total_laps = elapsed.values().map(Vec::len).max().unwrap_or_default();
+
+        let positions = (0..total_laps)
+            .map(|lap| {
+                let mut times: Vec<(i64, i16)> = elapsed
+                    .iter()
+                    .filter_map(|(car_idx, total)| total.get(lap).map(|time| (*time, *car_idx)))
+                    .collect();
+
+                times.sort_unstable();
+
+                LapPositions {
+                    lap_num: lap as i16 + 1,
+                    cars: times.into_iter().map(|(_, car_idx)| car_idx).collect(),
+                }
+            })
+            .collect();
+
+        Self { cars, positions }
+    }
+}
+
+impl FromRow for SessionLap {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(SessionLap {
+            car_idx: row.try_get("car_idx")?,
+            lap_num: row.try_get("lap_num")?,
+            lap_time_in_ms: row.try_get("lap_time_in_ms")?,
+            sector1_time_in_ms: row.try_get("sector1_time_in_ms")?,
+            sector2_time_in_ms: row.try_get("sector2_time_in_ms")?,
+            sector3_time_in_ms: row.try_get("sector3_time_in_ms")?,
+            lap_valid_bit_flags: row.try_get("lap_valid_bit_flags")?,
+        })
+    }
+}
+
+impl FromRow for TyreStint {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(TyreStint {
+            car_idx: row.try_get("car_idx")?,
+            stint: row.try_get("stint")?,
+            end_lap: row.try_get("end_lap")?,
+            tyre_actual_compound: row.try_get("tyre_actual_compound")?,
+            tyre_visual_compound: row.try_get("tyre_visual_compound")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LapChart, SessionLap};
+
+    fn lap(car_idx: i16, lap_num: i16, lap_time_in_ms: i32) -> SessionLap {
+        SessionLap {
+            car_idx,
+            lap_num,
+            lap_time_in_ms,
+            sector1_time_in_ms: 0,
+            sector2_time_in_ms: 0,
+            sector3_time_in_ms: 0,
+            lap_valid_bit_flags: 1,
+        }
+    }
+
+    #[test]
+    fn test_positions_by_lap() {
+        let chart = LapChart::new(
+            vec![
+                lap(0, 1, 90_000),
+                lap(0, 2, 95_000),
+                lap(1, 1, 91_000),
+                lap(1, 2, 92_000),
+                lap(2, 1, 89_000),
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(chart.cars.len(), 3);
+        assert_eq!(chart.positions.len(), 2);
+        assert_eq!(chart.positions[0].cars, vec![2, 0, 1]);
+        assert_eq!(chart.positions[1].cars, vec![1, 0]);
+    }
+}
@@ -1,11 +1,31 @@
+mod api_key;
 mod championship;
+mod identity;
+mod lap;
+mod record;
+mod result;
+mod round;
 mod saved_sessions;
+mod stats;
+mod stewarding;
+mod team;
+mod two_factor;
 mod user;
 
 use crate::error::AppResult;
+pub use api_key::*;
 pub use championship::*;
 use deadpool_postgres::tokio_postgres::Row;
+pub use identity::*;
+pub use lap::*;
+pub use record::*;
+pub use result::*;
+pub use round::*;
 // pub use saved_sessions::*;
+pub use stats::*;
+pub use stewarding::*;
+pub use team::*;
+pub use two_factor::*;
 pub use user::*;
 
 pub trait FromRow {
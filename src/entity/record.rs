@@ -0,0 +1,34 @@
+use super::{Category, FromRow};
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct TrackRecord {
+    pub track_id: i16,
+    pub category: Category,
+    pub season: i16,
+    pub user_id: i32,
+    pub driver_name: String,
+    pub lap_time_in_ms: i32,
+    pub championship_id: Option<i32>,
+    pub session_uid: i64,
+    pub set_at: DateTime<Utc>,
+}
+
+impl FromRow for TrackRecord {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(TrackRecord {
+            track_id: row.try_get("track_id")?,
+            category: row.try_get("category")?,
+            season: row.try_get("season")?,
+            user_id: row.try_get("user_id")?,
+            driver_name: row.try_get("driver_name")?,
+            lap_time_in_ms: row.try_get("lap_time_in_ms")?,
+            championship_id: row.try_get("championship_id")?,
+            session_uid: row.try_get("session_uid")?,
+            set_at: row.try_get("set_at")?,
+        })
+    }
+}
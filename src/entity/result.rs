@@ -0,0 +1,138 @@
+use super::FromRow;
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use postgres_derive::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+// Result status reported by the game for cars that finished and the disqualified ones
+pub const RESULT_STATUS_FINISHED: i16 = 3;
+pub const RESULT_STATUS_DISQUALIFIED: i16 = 5;
+
+// Session types of the game, Q1 to one shot qualifying and the three races
+pub const QUALIFYING_SESSIONS: RangeInclusive<i16> = 5..=9;
+pub const RACE_SESSIONS: RangeInclusive<i16> = 10..=12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "adjustment_kind")]
+pub enum AdjustmentKind {
+    #[postgres(name = "TimePenalty")]
+    TimePenalty,
+    #[postgres(name = "Disqualification")]
+    Disqualification,
+    #[postgres(name = "PositionChange")]
+    PositionChange,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChampionshipSession {
+    pub championship_id: i32,
+    pub session_uid: i64,
+    pub session_type: i16,
+    pub track_id: i16,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionResult {
+    pub session_uid: i64,
+    pub car_idx: i16,
+    pub position: i16,
+    pub num_laps: i16,
+    pub grid_position: i16,
+    pub points: i16,
+    pub num_pit_stops: i16,
+    pub result_status: i16,
+    pub best_lap_time_in_ms: i32,
+    pub total_race_time: f64,
+    pub penalties_time: i16,
+    pub num_penalties: i16,
+    pub adjusted_position: i16,
+    pub adjusted_penalties_time: i16,
+    pub adjusted_points: i16,
+    pub disqualified: bool,
+    pub driver_name: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+// Result with the team the driver was racing for when the session was held
+#[derive(Debug, Serialize)]
+pub struct ClassifiedResult {
+    #[serde(flatten)]
+    pub result: SessionResult,
+    pub team_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultAdjustment {
+    pub id: i32,
+    pub session_uid: i64,
+    pub car_idx: i16,
+    pub kind: AdjustmentKind,
+    pub value: i16,
+    pub reason: String,
+    pub applied_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for ChampionshipSession {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ChampionshipSession {
+            championship_id: row.try_get("championship_id")?,
+            session_uid: row.try_get("session_uid")?,
+            session_type: row.try_get("session_type")?,
+            track_id: row.try_get("track_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl FromRow for SessionResult {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(SessionResult {
+            session_uid: row.try_get("session_uid")?,
+            car_idx: row.try_get("car_idx")?,
+            position: row.try_get("position")?,
+            num_laps: row.try_get("num_laps")?,
+            grid_position: row.try_get("grid_position")?,
+            points: row.try_get("points")?,
+            num_pit_stops: row.try_get("num_pit_stops")?,
+            result_status: row.try_get("result_status")?,
+            best_lap_time_in_ms: row.try_get("best_lap_time_in_ms")?,
+            total_race_time: row.try_get("total_race_time")?,
+            penalties_time: row.try_get("penalties_time")?,
+            num_penalties: row.try_get("num_penalties")?,
+            adjusted_position: row.try_get("adjusted_position")?,
+            adjusted_penalties_time: row.try_get("adjusted_penalties_time")?,
+            adjusted_points: row.try_get("adjusted_points")?,
+            disqualified: row.try_get("disqualified")?,
+            driver_name: row.try_get("driver_name")?,
+            user_id: row.try_get("user_id")?,
+        })
+    }
+}
+
+impl FromRow for ClassifiedResult {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ClassifiedResult {
+            result: SessionResult::from_row(row)?,
+            team_id: row.try_get("team_id")?,
+        })
+    }
+}
+
+impl FromRow for ResultAdjustment {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ResultAdjustment {
+            id: row.try_get("id")?,
+            session_uid: row.try_get("session_uid")?,
+            car_idx: row.try_get("car_idx")?,
+            kind: row.try_get("kind")?,
+            value: row.try_get("value")?,
+            reason: row.try_get("reason")?,
+            applied_by: row.try_get("applied_by")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
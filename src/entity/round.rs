@@ -0,0 +1,70 @@
+use super::FromRow;
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use postgres_derive::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+
+// Sprint rounds have a second grid, the one of the sprint race taken from the sprint shootout,
+// reverse grid rounds reverse the top `reverse_grid_count` cars of the qualifying result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "weekend_format")]
+pub enum WeekendFormat {
+    #[postgres(name = "Standard")]
+    Standard,
+    #[postgres(name = "Sprint")]
+    Sprint,
+    #[postgres(name = "ReverseGrid")]
+    ReverseGrid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Round {
+    pub id: i32,
+    pub championship_id: i32,
+    pub round_num: i16,
+    pub track_id: i16,
+    pub format: WeekendFormat,
+    pub reverse_grid_count: Option<i16>,
+    pub qualifying_session_uid: Option<i64>,
+    pub race_session_uid: Option<i64>,
+    pub sprint_qualifying_session_uid: Option<i64>,
+    pub sprint_session_uid: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Expected and reported grid position of a car, cars missing in the race have no grid position
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct GridSlot {
+    pub car_idx: i16,
+    pub expected_position: i16,
+    pub grid_position: Option<i16>,
+    pub irregular: bool,
+}
+
+// Grids of the races of the round, each one is missing until its sessions are linked
+#[derive(Debug, Serialize)]
+pub struct RoundGrid {
+    pub race: Option<Vec<GridSlot>>,
+    pub sprint: Option<Vec<GridSlot>>,
+}
+
+impl FromRow for Round {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(Round {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            round_num: row.try_get("round_num")?,
+            track_id: row.try_get("track_id")?,
+            format: row.try_get("format")?,
+            reverse_grid_count: row.try_get("reverse_grid_count")?,
+            qualifying_session_uid: row.try_get("qualifying_session_uid")?,
+            race_session_uid: row.try_get("race_session_uid")?,
+            sprint_qualifying_session_uid: row.try_get("sprint_qualifying_session_uid")?,
+            sprint_session_uid: row.try_get("sprint_session_uid")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::AppResult;
+use deadpool_postgres::tokio_postgres::Row;
+use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
+use serde::Serialize;
+
+// Career of the driver across every championship, only race sessions are counted.
+// Other users only see the totals of the public championships
+#[derive(Debug, Serialize, Archive, RDeserialize, RSerialize)]
+#[archive(check_bytes)]
+pub struct DriverStats {
+    pub user_id: i32,
+    #[serde(flatten)]
+    pub career: CareerTotals,
+    #[serde(skip_serializing)]
+    pub public_career: CareerTotals,
+    pub championships: Vec<ChampionshipPoints>,
+}
+
+#[derive(Debug, Default, Serialize, Archive, RDeserialize, RSerialize)]
+#[archive(check_bytes)]
+pub struct CareerTotals {
+    pub starts: i64,
+    pub wins: i64,
+    pub podiums: i64,
+    pub poles: i64,
+    pub fastest_laps: i64,
+    pub dnfs: i64,
+    pub average_finish: Option<f64>,
+    pub penalties: i64,
+    pub steward_penalties: i64,
+}
+
+#[derive(Debug, Serialize, Archive, RDeserialize, RSerialize)]
+#[archive(check_bytes)]
+pub struct ChampionshipPoints {
+    pub championship_id: i32,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub public: bool,
+    pub points: i64,
+}
+
+impl DriverStats {
+    pub fn from_rows(
+        user_id: i32,
+        totals: &Row,
+        public_totals: &Row,
+        championships: &[Row],
+    ) -> AppResult<Self> {
+        let championships = championships
+            .iter()
+            .map(|row| {
+                Ok(ChampionshipPoints {
+                    championship_id: row.try_get("championship_id")?,
+                    name: row.try_get("name")?,
+                    public: row.try_get("public")?,
+                    points: row.try_get("points")?,
+                })
+            })
+            .collect::<AppResult<Vec<ChampionshipPoints>>>()?;
+
+        Ok(DriverStats {
+            user_id,
+            career: CareerTotals::from_row(totals)?,
+            public_career: CareerTotals::from_row(public_totals)?,
+            championships,
+        })
+    }
+}
+
+impl CareerTotals {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(CareerTotals {
+            starts: row.try_get("starts")?,
+            wins: row.try_get("wins")?,
+            podiums: row.try_get("podiums")?,
+            poles: row.try_get("poles")?,
+            fastest_laps: row.try_get("fastest_laps")?,
+            dnfs: row.try_get("dnfs")?,
+            average_finish: row.try_get("average_finish")?,
+            penalties: row.try_get("penalties")?,
+            steward_penalties: row.try_get("steward_penalties")?,
+        })
+    }
+}
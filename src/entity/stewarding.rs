@@ -0,0 +1,158 @@
+use super::FromRow;
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use postgres_derive::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "incident_kind")]
+pub enum IncidentKind {
+    #[postgres(name = "Penalty")]
+    Penalty,
+    #[postgres(name = "Overtake")]
+    Overtake,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "protest_status")]
+pub enum ProtestStatus {
+    #[postgres(name = "Pending")]
+    Pending,
+    #[postgres(name = "Upheld")]
+    Upheld,
+    #[postgres(name = "Dismissed")]
+    Dismissed,
+}
+
+// Value of the sanction is in seconds, grid places or points, disqualifications don't take one.
+// Time penalties and disqualifications are also applied to the classification of the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromSql, ToSql)]
+#[postgres(name = "sanction")]
+pub enum Sanction {
+    #[postgres(name = "TimePenalty")]
+    TimePenalty,
+    #[postgres(name = "GridDrop")]
+    GridDrop,
+    #[postgres(name = "PointsDeduction")]
+    PointsDeduction,
+    #[postgres(name = "Disqualification")]
+    Disqualification,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionIncident {
+    pub id: i32,
+    pub championship_id: i32,
+    pub session_uid: i64,
+    pub kind: IncidentKind,
+    pub lap_num: Option<i16>,
+    pub vehicle_idx: i16,
+    pub other_vehicle_idx: Option<i16>,
+    pub penalty_type: Option<i16>,
+    pub infringement_type: Option<i16>,
+    pub time: Option<i16>,
+    pub places_gained: Option<i16>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Protest {
+    pub id: i32,
+    pub championship_id: i32,
+    pub session_uid: i64,
+    pub incident_id: Option<i32>,
+    pub filed_by: i32,
+    pub accused_id: i32,
+    pub lap_num: Option<i16>,
+    pub description: String,
+    pub status: ProtestStatus,
+    pub steward_id: Option<i32>,
+    pub decision: Option<String>,
+    pub ruled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChampionshipPenalty {
+    pub id: i32,
+    pub championship_id: i32,
+    pub session_uid: i64,
+    pub user_id: i32,
+    pub protest_id: Option<i32>,
+    pub sanction: Sanction,
+    pub value: i16,
+    pub applied_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Places a driver drops in the grid of the next race, sum of the grid drops given to the driver
+#[derive(Debug)]
+pub struct GridPenalty {
+    pub user_id: i32,
+    pub places: i16,
+}
+
+impl FromRow for SessionIncident {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(SessionIncident {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            session_uid: row.try_get("session_uid")?,
+            kind: row.try_get("kind")?,
+            lap_num: row.try_get("lap_num")?,
+            vehicle_idx: row.try_get("vehicle_idx")?,
+            other_vehicle_idx: row.try_get("other_vehicle_idx")?,
+            penalty_type: row.try_get("penalty_type")?,
+            infringement_type: row.try_get("infringement_type")?,
+            time: row.try_get("time")?,
+            places_gained: row.try_get("places_gained")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl FromRow for Protest {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(Protest {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            session_uid: row.try_get("session_uid")?,
+            incident_id: row.try_get("incident_id")?,
+            filed_by: row.try_get("filed_by")?,
+            accused_id: row.try_get("accused_id")?,
+            lap_num: row.try_get("lap_num")?,
+            description: row.try_get("description")?,
+            status: row.try_get("status")?,
+            steward_id: row.try_get("steward_id")?,
+            decision: row.try_get("decision")?,
+            ruled_at: row.try_get("ruled_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl FromRow for ChampionshipPenalty {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ChampionshipPenalty {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            session_uid: row.try_get("session_uid")?,
+            user_id: row.try_get("user_id")?,
+            protest_id: row.try_get("protest_id")?,
+            sanction: row.try_get("sanction")?,
+            value: row.try_get("value")?,
+            applied_by: row.try_get("applied_by")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl FromRow for GridPenalty {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(GridPenalty {
+            user_id: row.try_get("user_id")?,
+            places: row.try_get("places")?,
+        })
+    }
+}
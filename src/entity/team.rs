@@ -0,0 +1,72 @@
+use super::FromRow;
+use crate::error::AppResult;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::tokio_postgres::Row;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct Team {
+    pub id: i32,
+    pub championship_id: i32,
+    pub name: String,
+    pub game_team_id: i16,
+    pub primary_color: String,
+    pub secondary_color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Stint of a driver in a team, still active while left_at is empty
+#[derive(Debug, Serialize)]
+pub struct TeamDriver {
+    pub team_id: i32,
+    pub user_id: i32,
+    pub joined_at: DateTime<Utc>,
+    pub left_at: Option<DateTime<Utc>>,
+}
+
+// Points scored in race sessions by the drivers of the team at the time of each session
+#[derive(Debug, Serialize)]
+pub struct ConstructorStanding {
+    pub team_id: i32,
+    pub name: String,
+    pub primary_color: String,
+    pub points: i64,
+}
+
+impl FromRow for Team {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(Team {
+            id: row.try_get("id")?,
+            championship_id: row.try_get("championship_id")?,
+            name: row.try_get("name")?,
+            game_team_id: row.try_get("game_team_id")?,
+            primary_color: row.try_get("primary_color")?,
+            secondary_color: row.try_get("secondary_color")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl FromRow for TeamDriver {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(TeamDriver {
+            team_id: row.try_get("team_id")?,
+            user_id: row.try_get("user_id")?,
+            joined_at: row.try_get("joined_at")?,
+            left_at: row.try_get("left_at")?,
+        })
+    }
+}
+
+impl FromRow for ConstructorStanding {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(ConstructorStanding {
+            team_id: row.try_get("team_id")?,
+            name: row.try_get("name")?,
+            primary_color: row.try_get("primary_color")?,
+            points: row.try_get("points")?,
+        })
+    }
+}
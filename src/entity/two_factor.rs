@@ -0,0 +1,19 @@
+use super::FromRow;
+use crate::error::AppResult;
+use deadpool_postgres::tokio_postgres::Row;
+
+// Never serialized, the secret is only shown once when the setup starts
+#[derive(Debug)]
+pub struct TwoFactor {
+    pub secret: String,
+    pub enabled: bool,
+}
+
+impl FromRow for TwoFactor {
+    fn from_row(row: &Row) -> AppResult<Self> {
+        Ok(TwoFactor {
+            secret: row.try_get("secret")?,
+            enabled: row.try_get("enabled")?,
+        })
+    }
+}
@@ -10,15 +10,44 @@ use std::sync::Arc;
 pub type UserExtension = Arc<User>;
 
 #[derive(
-    Debug, Archive, RDeserialize, RSerialize, Serialize, Deserialize, PartialEq, Eq, FromSql, ToSql,
+    Debug,
+    Clone,
+    Copy,
+    Archive,
+    RDeserialize,
+    RSerialize,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    FromSql,
+    ToSql,
 )]
 #[postgres(name = "provider")]
+#[serde(rename_all = "lowercase")]
 #[archive(check_bytes)]
 pub enum Provider {
     #[postgres(name = "Local")]
     Local,
     #[postgres(name = "Google")]
     Google,
+    #[postgres(name = "Discord")]
+    Discord,
+    #[postgres(name = "Steam")]
+    Steam,
+}
+
+impl Provider {
+    #[inline(always)]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Local => "local",
+            Provider::Google => "google",
+            Provider::Discord => "discord",
+            Provider::Steam => "steam",
+        }
+    }
 }
 
 #[derive(Debug, Archive, RDeserialize, RSerialize, Serialize, PartialEq, Eq, FromSql, ToSql)]
@@ -35,6 +64,19 @@ pub enum Role {
     Admin,
 }
 
+impl Role {
+    // Max number of championships the user can be part of, None means unlimited
+    #[inline(always)]
+    pub fn championship_limit(&self) -> Option<usize> {
+        match self {
+            Role::Free => Some(1),
+            Role::Premium => Some(3),
+            Role::Business => Some(14),
+            Role::Admin => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Archive, RDeserialize, RSerialize)]
 #[archive(check_bytes)]
 pub struct User {
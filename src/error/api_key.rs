@@ -0,0 +1,31 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+    #[error("Invalid api key")]
+    InvalidKey,
+    #[error("Api key not found")]
+    NotFound,
+    #[error("Api key limit reached")]
+    LimitReached,
+    #[error("Api key doesn't have the scope for this request")]
+    MissingScope,
+}
+
+impl web::error::WebResponseError for ApiKeyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiKeyError::InvalidKey => StatusCode::UNAUTHORIZED,
+            ApiKeyError::NotFound => StatusCode::NOT_FOUND,
+            ApiKeyError::LimitReached => StatusCode::CONFLICT,
+            ApiKeyError::MissingScope => StatusCode::FORBIDDEN,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
@@ -1,5 +1,7 @@
 use super::{
-    user::UserError, CacheError, ChampionshipError, CommonError, F123Error, SocketError, TokenError,
+    user::UserError, ApiKeyError, CacheError, ChampionshipError, CommonError, F123Error,
+    OAuthError, RateLimitError, ResultError, RoundError, SocketError, StewardingError, TeamError,
+    TokenError, TwoFactorError,
 };
 use bcrypt::BcryptError;
 use deadpool_postgres::{tokio_postgres::Error as PgError, PoolError};
@@ -28,6 +30,22 @@ pub enum AppError {
     #[error(transparent)]
     F123(#[from] F123Error),
     #[error(transparent)]
+    Team(#[from] TeamError),
+    #[error(transparent)]
+    Stewarding(#[from] StewardingError),
+    #[error(transparent)]
+    Result(#[from] ResultError),
+    #[error(transparent)]
+    Round(#[from] RoundError),
+    #[error(transparent)]
+    TwoFactor(#[from] TwoFactorError),
+    #[error(transparent)]
+    ApiKey(#[from] ApiKeyError),
+    #[error(transparent)]
+    OAuth(#[from] OAuthError),
+    #[error(transparent)]
+    RateLimit(#[from] RateLimitError),
+    #[error(transparent)]
     PgError(#[from] PgError),
     #[error(transparent)]
     PgPool(#[from] PoolError),
@@ -58,6 +76,14 @@ impl web::error::WebResponseError for AppError {
             AppError::Cache(e) => e.status_code(),
             AppError::Socket(e) => e.status_code(),
             AppError::F123(e) => e.status_code(),
+            AppError::Team(e) => e.status_code(),
+            AppError::Stewarding(e) => e.status_code(),
+            AppError::Result(e) => e.status_code(),
+            AppError::Round(e) => e.status_code(),
+            AppError::TwoFactor(e) => e.status_code(),
+            AppError::ApiKey(e) => e.status_code(),
+            AppError::OAuth(e) => e.status_code(),
+            AppError::RateLimit(e) => e.status_code(),
             AppError::PgError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::PgPool(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Bcrypt(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -79,6 +105,14 @@ impl web::error::WebResponseError for AppError {
             AppError::Cache(e) => e.error_response(r),
             AppError::Socket(e) => e.error_response(r),
             AppError::F123(e) => e.error_response(r),
+            AppError::Team(e) => e.error_response(r),
+            AppError::Stewarding(e) => e.error_response(r),
+            AppError::Result(e) => e.error_response(r),
+            AppError::Round(e) => e.error_response(r),
+            AppError::TwoFactor(e) => e.error_response(r),
+            AppError::ApiKey(e) => e.error_response(r),
+            AppError::OAuth(e) => e.error_response(r),
+            AppError::RateLimit(e) => e.error_response(r),
             AppError::PgError(e) => {
                 error!("{e}");
 
@@ -14,10 +14,28 @@ pub enum ChampionshipError {
     LimitReached,
     #[error("Not Owner of Championship")]
     NotOwner,
+    #[error("Not Member of Championship")]
+    NotMember,
     #[error("Cannot remove owner of Championship")]
     CannotRemoveOwner,
     #[error("Interval update time not reached")]
     IntervalNotReached,
+    #[error("Insufficient role in Championship")]
+    InsufficientRole,
+    #[error("Role can't be assigned")]
+    InvalidRole,
+    #[error("User is already a member of the Championship")]
+    AlreadyMember,
+    #[error("Invite not found")]
+    InviteNotFound,
+    #[error("Invite expired")]
+    InviteExpired,
+    #[error("Join code is invalid or expired")]
+    InvalidJoinCode,
+    #[error("Join request not found")]
+    JoinRequestNotFound,
+    #[error("Join request already sent")]
+    JoinRequestExists,
 }
 
 impl web::error::WebResponseError for ChampionshipError {
@@ -28,8 +46,17 @@ impl web::error::WebResponseError for ChampionshipError {
             ChampionshipError::NotFound => StatusCode::NOT_FOUND,
             ChampionshipError::LimitReached => StatusCode::BAD_REQUEST,
             ChampionshipError::NotOwner => StatusCode::UNAUTHORIZED,
+            ChampionshipError::NotMember => StatusCode::UNAUTHORIZED,
             ChampionshipError::CannotRemoveOwner => StatusCode::BAD_REQUEST,
             ChampionshipError::IntervalNotReached => StatusCode::BAD_REQUEST,
+            ChampionshipError::InsufficientRole => StatusCode::UNAUTHORIZED,
+            ChampionshipError::InvalidRole => StatusCode::BAD_REQUEST,
+            ChampionshipError::AlreadyMember => StatusCode::CONFLICT,
+            ChampionshipError::InviteNotFound => StatusCode::NOT_FOUND,
+            ChampionshipError::InviteExpired => StatusCode::BAD_REQUEST,
+            ChampionshipError::InvalidJoinCode => StatusCode::BAD_REQUEST,
+            ChampionshipError::JoinRequestNotFound => StatusCode::NOT_FOUND,
+            ChampionshipError::JoinRequestExists => StatusCode::CONFLICT,
         }
     }
 
@@ -16,6 +16,8 @@ pub enum F123Error {
     Encoding,
     #[error("Error to encode batched data")]
     BatchedEncoding,
+    #[error("Error to compress batched data")]
+    Compression,
 }
 
 impl web::error::WebResponseError for F123Error {
@@ -27,6 +29,7 @@ impl web::error::WebResponseError for F123Error {
             F123Error::ReceivingData => StatusCode::INTERNAL_SERVER_ERROR,
             F123Error::Encoding => StatusCode::INTERNAL_SERVER_ERROR,
             F123Error::BatchedEncoding => StatusCode::INTERNAL_SERVER_ERROR,
+            F123Error::Compression => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
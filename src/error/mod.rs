@@ -1,17 +1,33 @@
+mod api_key;
 mod app;
 mod cache;
 mod championship;
 mod common;
 mod f123;
+mod oauth;
+mod rate_limit;
+mod result;
+mod round;
 mod socket;
+mod stewarding;
+mod team;
 mod token;
+mod two_factor;
 mod user;
 
+pub(crate) use api_key::*;
 pub(crate) use app::*;
 pub(crate) use cache::*;
 pub(crate) use championship::*;
 pub(crate) use common::*;
 pub(crate) use f123::*;
+pub(crate) use oauth::*;
+pub(crate) use rate_limit::*;
+pub(crate) use result::*;
+pub(crate) use round::*;
 pub(crate) use socket::*;
+pub(crate) use stewarding::*;
+pub(crate) use team::*;
 pub(crate) use token::*;
+pub(crate) use two_factor::*;
 pub(crate) use user::*;
@@ -0,0 +1,40 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("Provider not available")]
+    NotConfigured,
+    #[error("Invalid or expired authorization state")]
+    InvalidState,
+    #[error("Provider rejected the authorization")]
+    Rejected,
+    #[error("Provider account has no verified email, link it from an existing account")]
+    EmailRequired,
+    #[error("Provider account already linked")]
+    AlreadyLinked,
+    #[error("Provider account not linked")]
+    NotLinked,
+    #[error("Cannot unlink the only way to login")]
+    LastLoginMethod,
+}
+
+impl web::error::WebResponseError for OAuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            OAuthError::NotConfigured => StatusCode::NOT_FOUND,
+            OAuthError::InvalidState => StatusCode::BAD_REQUEST,
+            OAuthError::Rejected => StatusCode::UNAUTHORIZED,
+            OAuthError::EmailRequired => StatusCode::CONFLICT,
+            OAuthError::AlreadyLinked => StatusCode::CONFLICT,
+            OAuthError::NotLinked => StatusCode::NOT_FOUND,
+            OAuthError::LastLoginMethod => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
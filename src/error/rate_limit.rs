@@ -0,0 +1,32 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+// Both carry the seconds until the request can be made again
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    #[error("Too many requests, try again later")]
+    TooManyRequests(u64),
+    #[error("Account locked after too many failed logins, try again later")]
+    AccountLocked(u64),
+}
+
+impl web::error::WebResponseError for RateLimitError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RateLimitError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            RateLimitError::AccountLocked(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        let retry_after = match self {
+            RateLimitError::TooManyRequests(retry_after) => retry_after,
+            RateLimitError::AccountLocked(retry_after) => retry_after,
+        };
+
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .set_header("retry-after", retry_after.to_string())
+            .body(self.to_string())
+    }
+}
@@ -0,0 +1,31 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ResultError {
+    #[error("Session results not found")]
+    SessionNotFound,
+    #[error("Car not found in the results")]
+    CarNotFound,
+    #[error("Invalid adjustment")]
+    InvalidAdjustment,
+    #[error("Driver already assigned to other car of the session")]
+    DriverAlreadyAssigned,
+}
+
+impl web::error::WebResponseError for ResultError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ResultError::SessionNotFound => StatusCode::NOT_FOUND,
+            ResultError::CarNotFound => StatusCode::NOT_FOUND,
+            ResultError::InvalidAdjustment => StatusCode::BAD_REQUEST,
+            ResultError::DriverAlreadyAssigned => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
@@ -0,0 +1,34 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RoundError {
+    #[error("Round not found")]
+    NotFound,
+    #[error("Round already exists")]
+    AlreadyExists,
+    #[error("Invalid session for the round")]
+    InvalidSession,
+    #[error("Round sessions not linked")]
+    MissingSessions,
+    #[error("Reverse grid rounds need the number of cars to reverse")]
+    InvalidFormat,
+}
+
+impl web::error::WebResponseError for RoundError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RoundError::NotFound => StatusCode::NOT_FOUND,
+            RoundError::AlreadyExists => StatusCode::CONFLICT,
+            RoundError::InvalidSession => StatusCode::BAD_REQUEST,
+            RoundError::MissingSessions => StatusCode::CONFLICT,
+            RoundError::InvalidFormat => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
@@ -0,0 +1,34 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StewardingError {
+    #[error("Incident not found")]
+    IncidentNotFound,
+    #[error("Protest not found")]
+    ProtestNotFound,
+    #[error("Protest already ruled")]
+    AlreadyRuled,
+    #[error("Stewards can't rule on protests they are involved in")]
+    InvolvedSteward,
+    #[error("Invalid ruling")]
+    InvalidRuling,
+}
+
+impl web::error::WebResponseError for StewardingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            StewardingError::IncidentNotFound => StatusCode::NOT_FOUND,
+            StewardingError::ProtestNotFound => StatusCode::NOT_FOUND,
+            StewardingError::AlreadyRuled => StatusCode::CONFLICT,
+            StewardingError::InvolvedSteward => StatusCode::FORBIDDEN,
+            StewardingError::InvalidRuling => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
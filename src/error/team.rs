@@ -0,0 +1,34 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TeamError {
+    #[error("Team not found")]
+    NotFound,
+    #[error("Team already exists")]
+    AlreadyExists,
+    #[error("Team already has two drivers")]
+    Full,
+    #[error("Driver already in the team")]
+    DriverAlreadyInTeam,
+    #[error("Driver not found in the team")]
+    DriverNotFound,
+}
+
+impl web::error::WebResponseError for TeamError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TeamError::NotFound => StatusCode::NOT_FOUND,
+            TeamError::AlreadyExists => StatusCode::CONFLICT,
+            TeamError::Full => StatusCode::BAD_REQUEST,
+            TeamError::DriverAlreadyInTeam => StatusCode::CONFLICT,
+            TeamError::DriverNotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
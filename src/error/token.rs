@@ -16,6 +16,8 @@ pub enum TokenError {
     TokenNotFound,
     #[error("Invalid token type")]
     InvalidTokenType,
+    #[error("Refresh token already used, the session was revoked")]
+    TokenReused,
 }
 
 impl web::error::WebResponseError for TokenError {
@@ -27,6 +29,7 @@ impl web::error::WebResponseError for TokenError {
             TokenError::TokenCreationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             TokenError::TokenNotFound => StatusCode::NOT_FOUND,
             TokenError::InvalidTokenType => StatusCode::BAD_REQUEST,
+            TokenError::TokenReused => StatusCode::UNAUTHORIZED,
         }
     }
 
@@ -0,0 +1,37 @@
+use ntex::{http::StatusCode, web};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TwoFactorError {
+    #[error("Two factor authentication is only available for local accounts")]
+    LocalOnly,
+    #[error("Two factor authentication not set up")]
+    NotSetUp,
+    #[error("Two factor authentication already enabled")]
+    AlreadyEnabled,
+    #[error("Two factor authentication not enabled")]
+    NotEnabled,
+    #[error("Invalid two factor code")]
+    InvalidCode,
+    #[error("Too many attempts, login again")]
+    TooManyAttempts,
+}
+
+impl web::error::WebResponseError for TwoFactorError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TwoFactorError::LocalOnly => StatusCode::BAD_REQUEST,
+            TwoFactorError::NotSetUp => StatusCode::CONFLICT,
+            TwoFactorError::AlreadyEnabled => StatusCode::CONFLICT,
+            TwoFactorError::NotEnabled => StatusCode::CONFLICT,
+            TwoFactorError::InvalidCode => StatusCode::UNAUTHORIZED,
+            TwoFactorError::TooManyAttempts => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        web::HttpResponse::build(self.status_code())
+            .set_header("content-type", "text/html; charset=utf-8")
+            .body(self.to_string())
+    }
+}
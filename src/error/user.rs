@@ -11,8 +11,8 @@ pub enum UserError {
     InvalidCredentials,
     #[error("Not verified user")]
     NotVerified,
-    #[error("Use google to login")]
-    GoogleLogin,
+    #[error("Use the linked provider to login")]
+    ProviderLogin,
     #[error("Unauthorized user")]
     Unauthorized,
     #[error("Cannot Delete Yourself")]
@@ -21,14 +21,12 @@ pub enum UserError {
     AlreadyActive,
     #[error("User is not active")]
     AlreadyInactive,
-    #[error("Invalid Provider")]
-    InvalidProvider,
-    #[error("Using wrong provider")]
-    WrongProvider,
     #[error("Invalid Update")]
     InvalidUpdate,
     #[error("Update Limit Exceeded")]
     UpdateLimitExceeded,
+    #[error("User has been deactivated")]
+    Deactivated,
 }
 
 impl web::error::WebResponseError for UserError {
@@ -38,15 +36,14 @@ impl web::error::WebResponseError for UserError {
             UserError::NotFound => StatusCode::NOT_FOUND,
             UserError::InvalidCredentials => StatusCode::UNAUTHORIZED,
             UserError::NotVerified => StatusCode::UNAUTHORIZED,
-            UserError::GoogleLogin => StatusCode::BAD_REQUEST,
+            UserError::ProviderLogin => StatusCode::BAD_REQUEST,
             UserError::Unauthorized => StatusCode::UNAUTHORIZED,
             UserError::AutoDelete => StatusCode::BAD_REQUEST,
             UserError::AlreadyActive => StatusCode::BAD_REQUEST,
             UserError::AlreadyInactive => StatusCode::BAD_REQUEST,
-            UserError::InvalidProvider => StatusCode::BAD_REQUEST,
-            UserError::WrongProvider => StatusCode::BAD_REQUEST,
             UserError::InvalidUpdate => StatusCode::BAD_REQUEST,
             UserError::UpdateLimitExceeded => StatusCode::BAD_REQUEST,
+            UserError::Deactivated => StatusCode::FORBIDDEN,
         }
     }
 
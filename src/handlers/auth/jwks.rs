@@ -0,0 +1,10 @@
+use crate::{services::TokenServiceTrait, states::AppState};
+use ntex::web;
+
+// Public keys to verify the tokens, including the ones that will start signing soon
+#[inline(always)]
+pub(crate) async fn jwks(state: web::types::State<AppState>) -> impl web::Responder {
+    web::HttpResponse::Ok()
+        .set_header("cache-control", "public, max-age=300")
+        .json(&state.token_service.jwks())
+}
@@ -1,7 +1,113 @@
-mod google;
+use crate::{
+    dtos::{AuthResponse, DeviceInfo, EmailUser, TokenType, TwoFactorChallenge, VerifyEmail},
+    error::AppResult,
+    services::TokenServiceTrait,
+    states::AppState,
+};
+use ntex::web;
+
+mod jwks;
+mod oauth;
 mod user;
 mod verify;
 
-pub(crate) use google::*;
+pub(crate) use jwks::*;
+pub(crate) use oauth::*;
 pub(crate) use user::*;
 pub(crate) use verify::*;
+
+// The address is the one of the peer, forwarded headers can be set by any client
+#[inline(always)]
+fn device_info<'a>(req: &web::HttpRequest, fingerprint: &'a str) -> DeviceInfo<'a> {
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(200).collect());
+
+    DeviceInfo {
+        fingerprint,
+        ip: req.peer_addr().map(|addr| addr.ip().to_string()),
+        user_agent,
+    }
+}
+
+// Last step of every login, the tokens are only issued and the failed logins reset
+// once the code of the two factor challenge is verified
+async fn complete_login(
+    req: &web::HttpRequest,
+    state: &AppState,
+    user_id: i32,
+    fingerprint: &str,
+) -> AppResult<web::HttpResponse> {
+    if state.two_factor_repository.enabled(&user_id).await? {
+        let two_factor_token = state
+            .token_service
+            .generate_token(user_id, TokenType::TwoFactor)
+            .await?;
+
+        state
+            .token_service
+            .save_two_factor_token(&two_factor_token)
+            .await?;
+
+        return Ok(web::HttpResponse::Accepted().json(&TwoFactorChallenge { two_factor_token }));
+    }
+
+    state.rate_limit_service.login_succeeded(&user_id).await?;
+    issue_tokens(req, state, user_id, fingerprint).await
+}
+
+// Access and refresh tokens of a new session of the device
+async fn issue_tokens(
+    req: &web::HttpRequest,
+    state: &AppState,
+    user_id: i32,
+    fingerprint: &str,
+) -> AppResult<web::HttpResponse> {
+    let device = device_info(req, fingerprint);
+
+    let access_token_future = state
+        .token_service
+        .generate_token(user_id, TokenType::Bearer);
+
+    let refresh_token_future = state
+        .token_service
+        .generate_refresh_token(&user_id, &device);
+
+    let (access_token, refresh_token) =
+        tokio::try_join!(access_token_future, refresh_token_future)?;
+
+    Ok(web::HttpResponse::Ok().json(&AuthResponse {
+        access_token,
+        refresh_token,
+    }))
+}
+
+// Used on register and whenever the user asks for a new link
+async fn send_verification_email(
+    state: &AppState,
+    user_id: i32,
+    email_user: EmailUser<'_>,
+) -> AppResult<()> {
+    let token = state
+        .token_service
+        .generate_token(user_id, TokenType::Email)
+        .await?;
+
+    let template = VerifyEmail {
+        verification_link: &format!(
+            "https://intellitelemetry.live/auth/verify-email?token={}",
+            token
+        ),
+    };
+
+    let save_email_future = state.token_service.save_email_token(&token);
+
+    let send_email_future = state
+        .email_service
+        .send_mail(email_user, "Verify Email", template);
+
+    tokio::try_join!(save_email_future, send_email_future)?;
+    Ok(())
+}
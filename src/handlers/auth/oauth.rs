@@ -0,0 +1,85 @@
+use super::complete_login;
+use crate::{
+    config::constants::*,
+    dtos::{AuthCodeExchange, FingerprintQuery, OAuthOutcome, ProviderPath},
+    error::{AppResult, CommonError, UserError},
+    repositories::UserRepositoryTrait,
+    services::TokenServiceTrait,
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+use std::collections::HashMap;
+
+#[inline(always)]
+pub(crate) async fn oauth_login(
+    state: web::types::State<AppState>,
+    path: web::types::Path<ProviderPath>,
+    query: web::types::Query<FingerprintQuery>,
+) -> AppResult<impl web::Responder> {
+    let url = state
+        .oauth_service
+        .authorize_url(&path.provider, None, Some(&query.fingerprint))
+        .await?;
+
+    Ok(web::HttpResponse::Found()
+        .set_header("Location", url)
+        .body("Redirecting..."))
+}
+
+// Steam sends the OpenID assertion as query params, so every param is passed to the provider.
+// The front end only gets a one time code, the tokens never end up in the url
+pub(crate) async fn callback(
+    state: web::types::State<AppState>,
+    path: web::types::Path<ProviderPath>,
+    query: web::types::Query<HashMap<String, String>>,
+) -> AppResult<impl web::Responder> {
+    let provider = path.provider.name();
+
+    let redirect_url = match state.oauth_service.callback(&path.provider, &query).await? {
+        OAuthOutcome::Login {
+            user_id,
+            fingerprint,
+        } => {
+            let code = state
+                .token_service
+                .save_auth_code(&user_id, &fingerprint)
+                .await?;
+
+            format!("{OAUTH_FRONTEND_URL}/{provider}/callback?code={code}")
+        }
+
+        OAuthOutcome::Linked => format!("{OAUTH_FRONTEND_URL}/{provider}/linked"),
+    };
+
+    Ok(web::HttpResponse::Found()
+        .set_header("Location", redirect_url)
+        .body("Redirecting..."))
+}
+
+#[inline(always)]
+pub(crate) async fn exchange_code(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    query: web::types::Query<FingerprintQuery>,
+    form: web::types::Form<AuthCodeExchange>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = state
+        .token_service
+        .exchange_auth_code(&form.code, &query.fingerprint)
+        .await?;
+
+    let Some(user) = state.user_repository.find(&user_id).await? else {
+        Err(UserError::NotFound)?
+    };
+
+    if !user.active {
+        Err(UserError::NotVerified)?
+    }
+
+    complete_login(&req, &state, user.id, &query.fingerprint).await
+}
@@ -1,11 +1,13 @@
+use super::{complete_login, issue_tokens, send_verification_email};
 use crate::{
+    config::constants::{FORGOT_PASSWORD_ACCOUNT_LIMIT, LOGIN_ACCOUNT_LIMIT},
     dtos::{
         AuthResponse, EmailUser, FingerprintQuery, ForgotPasswordDto, LoginUserDto,
-        PasswordChanged, RefreshResponse, RefreshTokenQuery, RegisterUserDto, ResetPassword,
-        ResetPasswordDto, ResetPasswordQuery, TokenType, VerifyEmail,
+        PasswordChanged, RefreshTokenQuery, RegisterUserDto, ResetPassword, ResetPasswordDto,
+        ResetPasswordQuery, TokenType, TwoFactorLogin,
     },
     entity::{Provider, UserExtension},
-    error::{AppResult, CommonError, UserError},
+    error::{AppError, AppResult, CommonError, TwoFactorError, UserError},
     repositories::UserRepositoryTrait,
     services::{TokenServiceTrait, UserServiceTrait},
     states::AppState,
@@ -25,31 +27,13 @@ pub(crate) async fn register(
 
     let user_id = state.user_service.create(&form).await?;
 
-    let token = state
-        .token_service
-        .generate_token(user_id, TokenType::Email)
-        .await?;
-
-    let template = VerifyEmail {
-        verification_link: &format!(
-            "https://intellitelemetry.live/auth/verify-email?token={}",
-            token
-        ),
-    };
-
-    let save_email_future = state.token_service.save_email_token(&token);
-
-    let send_email_future =
-        state
-            .email_service
-            .send_mail((&*form).into(), "Verify Email", template);
-
-    tokio::try_join!(save_email_future, send_email_future)?;
+    send_verification_email(&state, user_id, (&*form).into()).await?;
     Ok(web::HttpResponse::Ok())
 }
 
 #[inline(always)]
 pub(crate) async fn login(
+    req: web::HttpRequest,
     state: web::types::State<AppState>,
     query: web::types::Query<FingerprintQuery>,
     form: web::types::Form<LoginUserDto>,
@@ -58,6 +42,11 @@ pub(crate) async fn login(
         return Err(CommonError::ValidationFailed)?;
     }
 
+    state
+        .rate_limit_service
+        .check(&LOGIN_ACCOUNT_LIMIT, &form.email)
+        .await?;
+
     let Some(user) = state.user_repository.find_by_email(&form.email).await? else {
         return Err(UserError::NotFound)?;
     };
@@ -67,33 +56,55 @@ pub(crate) async fn login(
     }
 
     if user.provider != Provider::Local {
-        return Err(UserError::GoogleLogin)?;
+        return Err(UserError::ProviderLogin)?;
     }
 
+    state.rate_limit_service.check_lockout(&user.id).await?;
+
     if !state
         .user_repository
-        .validate_password(&form.password, &user.password.unwrap())?
+        .validate_password(&form.password, user.password.as_ref().unwrap())?
     {
+        state.rate_limit_service.login_failed(&user).await?;
         return Err(UserError::InvalidCredentials)?;
     }
 
-    let access_token_future = state
-        .token_service
-        .generate_token(user.id, TokenType::Bearer);
+    complete_login(&req, &state, user.id, &query.fingerprint).await
+}
 
-    let refresh_token_future = state
-        .token_service
-        .generate_refresh_token(&user.id, &query.fingerprint);
+#[inline(always)]
+pub(crate) async fn login_two_factor(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    query: web::types::Query<FingerprintQuery>,
+    form: web::types::Form<TwoFactorLogin>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        return Err(CommonError::ValidationFailed)?;
+    }
 
-    let (access_token, refresh_token) =
-        tokio::try_join!(access_token_future, refresh_token_future)?;
+    let user_id = state.token_service.two_factor_attempt(&form.token).await?;
+    state.rate_limit_service.check_lockout(&user_id).await?;
 
-    let auth_response = &AuthResponse {
-        access_token,
-        refresh_token,
-    };
+    // Wrong codes count towards the lockout like wrong passwords
+    if let Err(e) = state.two_factor_service.verify(&user_id, &form.code).await {
+        if matches!(e, AppError::TwoFactor(TwoFactorError::InvalidCode)) {
+            if let Some(user) = state.user_repository.find(&user_id).await? {
+                state.rate_limit_service.login_failed(&user).await?;
+            }
+        }
 
-    Ok(web::HttpResponse::Ok().json(auth_response))
+        return Err(e);
+    }
+
+    state
+        .token_service
+        .remove_two_factor_token(&form.token)
+        .await?;
+
+    state.rate_limit_service.login_succeeded(&user_id).await?;
+
+    issue_tokens(&req, &state, user_id, &query.fingerprint).await
 }
 
 #[inline(always)]
@@ -101,14 +112,17 @@ pub(crate) async fn refresh_token(
     state: web::types::State<AppState>,
     query: web::types::Query<RefreshTokenQuery>,
 ) -> AppResult<impl web::Responder> {
-    let access_token = state
+    let (access_token, refresh_token) = state
         .token_service
         .refresh_access_token(&query.refresh_token, &query.fingerprint)
         .await?;
 
-    let refresh_response = &RefreshResponse { access_token };
+    let auth_response = &AuthResponse {
+        access_token,
+        refresh_token,
+    };
 
-    Ok(web::HttpResponse::Ok().json(refresh_response))
+    Ok(web::HttpResponse::Ok().json(auth_response))
 }
 
 #[inline(always)]
@@ -125,7 +139,7 @@ pub(crate) async fn logout(
 
     state
         .token_service
-        .remove_refresh_token(&user_id, &query.fingerprint)
+        .revoke_device(&user_id, &query.fingerprint)
         .await?;
 
     Ok(web::HttpResponse::Ok())
@@ -140,6 +154,11 @@ pub(crate) async fn forgot_password(
         return Err(CommonError::ValidationFailed)?;
     }
 
+    state
+        .rate_limit_service
+        .check(&FORGOT_PASSWORD_ACCOUNT_LIMIT, &form.email)
+        .await?;
+
     let Some(user) = state.user_repository.find_by_email(&form.email).await? else {
         return Err(UserError::NotFound)?;
     };
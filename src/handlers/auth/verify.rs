@@ -1,10 +1,13 @@
+use super::send_verification_email;
 use crate::{
-    dtos::{EmailUser, EmailVerified, VerifyEmailParams},
-    error::AppResult,
+    config::constants::RESEND_VERIFICATION_ACCOUNT_LIMIT,
+    dtos::{EmailUser, EmailVerified, ResendVerificationDto, VerifyEmailParams},
+    error::{AppResult, CommonError, UserError},
     repositories::UserRepositoryTrait,
     services::UserServiceTrait,
     states::AppState,
 };
+use garde::Validate;
 use ntex::web;
 
 #[inline(always)]
@@ -28,3 +31,52 @@ pub async fn verify_email(
 
     Ok(web::HttpResponse::Created())
 }
+
+#[inline(always)]
+pub async fn verify_email_change(
+    state: web::types::State<AppState>,
+    query: web::types::Query<VerifyEmailParams>,
+) -> AppResult<impl web::Responder> {
+    state
+        .user_service
+        .change_email_with_token(&query.token)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+// New link for users whose previous one expired before they opened it
+#[inline(always)]
+pub async fn resend_verification(
+    state: web::types::State<AppState>,
+    form: web::types::Form<ResendVerificationDto>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    state
+        .rate_limit_service
+        .check(&RESEND_VERIFICATION_ACCOUNT_LIMIT, &form.email)
+        .await?;
+
+    let Some(user) = state.user_repository.find_by_email(&form.email).await? else {
+        Err(UserError::NotFound)?
+    };
+
+    if user.active {
+        Err(UserError::AlreadyActive)?
+    }
+
+    if state.user_repository.verified(&user.id).await? {
+        Err(UserError::Deactivated)?
+    }
+
+    let email_user = EmailUser {
+        username: &user.username,
+        email: &user.email,
+    };
+
+    send_verification_email(&state, user.id, email_user).await?;
+    Ok(web::HttpResponse::Ok())
+}
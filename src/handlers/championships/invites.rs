@@ -0,0 +1,177 @@
+use crate::{
+    dtos::{
+        AddUser, ChampionshipAndInviteIdPath, ChampionshipIdPath, ChampionshipInvitation,
+        EmailUser, InviteToken, TokenType,
+    },
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, ChampionshipError, CommonError, TokenError, UserError},
+    repositories::UserRepositoryTrait,
+    services::TokenServiceTrait,
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn create_invite(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<AddUser>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user = req
+        .extensions()
+        .get::<UserExtension>()
+        .cloned()
+        .ok_or(CommonError::InternalServerError)?;
+
+    let invite = state
+        .championship_service
+        .create_invite(&path.id, &user.id, &form)
+        .await?;
+
+    let Some(championship) = state.championship_repository.find(&path.id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    let Some(invited_user) = state.user_repository.find(&invite.user_id).await? else {
+        Err(UserError::NotFound)?
+    };
+
+    let token = state
+        .token_service
+        .generate_token(invite.id, TokenType::Invite)
+        .await?;
+
+    let template = ChampionshipInvitation {
+        invited_by: &user.username,
+        championship_name: &championship.name,
+        accept_link: &format!("https://intellitelemetry.live/invites/accept?token={token}"),
+        decline_link: &format!("https://intellitelemetry.live/invites/decline?token={token}"),
+    };
+
+    state
+        .email_service
+        .send_mail(
+            EmailUser {
+                username: &invited_user.username,
+                email: &invited_user.email,
+            },
+            "Championship Invitation",
+            template,
+        )
+        .await?;
+
+    Ok(web::HttpResponse::Created())
+}
+
+#[inline(always)]
+pub async fn championship_invites(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    let invites = state.championship_repository.invites(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&invites))
+}
+
+#[inline(always)]
+pub async fn revoke_invite(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipAndInviteIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .revoke_invite(&path.id, &user_id, &path.invite_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn accept_invite(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<InviteToken>,
+) -> AppResult<impl web::Responder> {
+    let (invite_id, user_id) = invite_from_token(&req, &state, &form)?;
+
+    state
+        .championship_service
+        .accept_invite(&invite_id, &user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn decline_invite(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<InviteToken>,
+) -> AppResult<impl web::Responder> {
+    let (invite_id, user_id) = invite_from_token(&req, &state, &form)?;
+
+    state
+        .championship_service
+        .decline_invite(&invite_id, &user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+// Returns the invite id of the token and the id of the logged user
+#[inline(always)]
+fn invite_from_token(
+    req: &web::HttpRequest,
+    state: &AppState,
+    form: &InviteToken,
+) -> AppResult<(i32, i32)> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let token = state.token_service.validate(&form.token)?;
+    if token.claims.token_type != TokenType::Invite {
+        Err(TokenError::InvalidTokenType)?
+    }
+
+    Ok((token.claims.sub, user_id))
+}
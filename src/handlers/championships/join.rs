@@ -0,0 +1,187 @@
+use crate::{
+    dtos::{
+        ChampionshipAndJoinCodePath, ChampionshipAndUserIdPath, ChampionshipIdPath, CreateJoinCode,
+        JoinCodePath, JoinCodeResponse,
+    },
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn create_join_code(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<CreateJoinCode>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let code = state
+        .championship_service
+        .create_join_code(&path.id, &user_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Created().json(&JoinCodeResponse { code }))
+}
+
+#[inline(always)]
+pub async fn join_codes(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    let join_codes = state.championship_repository.join_codes(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&join_codes))
+}
+
+#[inline(always)]
+pub async fn delete_join_code(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipAndJoinCodePath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .delete_join_code(&path.id, &user_id, &path.code)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn request_join(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<JoinCodePath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .request_join(&path.code, &user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Created())
+}
+
+#[inline(always)]
+pub async fn join_requests(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    let join_requests = state
+        .championship_repository
+        .join_requests(&path.id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&join_requests))
+}
+
+#[inline(always)]
+pub async fn approve_join_request(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipAndUserIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .approve_join_request(&path.id, &user_id, &path.user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn reject_join_request(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipAndUserIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .reject_join_request(&path.id, &user_id, &path.user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
@@ -1,19 +1,31 @@
 mod admin;
+mod invites;
+mod join;
+mod results;
+mod rounds;
 mod socket;
 mod sockets;
+mod stewarding;
+mod teams;
 
 use crate::dtos::{ChampionshipAndUserIdPath, ChampionshipIdPath};
 use crate::{
-    dtos::{AddUser, CreateChampionshipDto, UpdateChampionship},
-    entity::{Role, UserExtension},
+    dtos::{CreateChampionshipDto, TransferOwnership, UpdateChampionship, UpdateMemberRole},
+    entity::{ChampionshipRole, UserExtension, Visibility},
     error::{AppResult, ChampionshipError, CommonError},
     states::AppState,
 };
 pub(crate) use admin::*;
 use garde::Validate;
+pub(crate) use invites::*;
+pub(crate) use join::*;
 use ntex::web;
+pub(crate) use results::*;
+pub(crate) use rounds::*;
 pub(crate) use socket::*;
 pub(crate) use sockets::*;
+pub(crate) use stewarding::*;
+pub(crate) use teams::*;
 
 #[inline(always)]
 pub async fn create_championship(
@@ -36,26 +48,10 @@ pub async fn create_championship(
         .championship_len(&user.id)
         .await?;
 
-    match user.role {
-        Role::Free => {
-            if championships_len >= 1 {
-                Err(ChampionshipError::LimitReached)?
-            }
+    if let Some(limit) = user.role.championship_limit() {
+        if championships_len >= limit {
+            Err(ChampionshipError::LimitReached)?
         }
-
-        Role::Premium => {
-            if championships_len >= 3 {
-                Err(ChampionshipError::LimitReached)?
-            }
-        }
-
-        Role::Business => {
-            if championships_len >= 14 {
-                Err(ChampionshipError::LimitReached)?
-            }
-        }
-
-        Role::Admin => {}
     }
 
     state
@@ -92,13 +88,12 @@ pub async fn update(
 }
 
 #[inline(always)]
-pub async fn add_user(
+pub async fn remove_user(
     req: web::HttpRequest,
     state: web::types::State<AppState>,
-    form: web::types::Form<AddUser>,
-    path: web::types::Path<ChampionshipIdPath>,
+    path: web::types::Path<ChampionshipAndUserIdPath>,
 ) -> AppResult<impl web::Responder> {
-    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+    if path.validate(&()).is_err() {
         Err(CommonError::ValidationFailed)?
     }
 
@@ -110,19 +105,20 @@ pub async fn add_user(
 
     state
         .championship_service
-        .add_user(&path.id, &user_id, &form.email)
+        .remove_user(&path.id, &user_id, &path.user_id)
         .await?;
 
     Ok(web::HttpResponse::Ok())
 }
 
 #[inline(always)]
-pub async fn remove_user(
+pub async fn update_member_role(
     req: web::HttpRequest,
     state: web::types::State<AppState>,
+    form: web::types::Form<UpdateMemberRole>,
     path: web::types::Path<ChampionshipAndUserIdPath>,
 ) -> AppResult<impl web::Responder> {
-    if path.validate(&()).is_err() {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
         Err(CommonError::ValidationFailed)?
     }
 
@@ -134,7 +130,32 @@ pub async fn remove_user(
 
     state
         .championship_service
-        .remove_user(&path.id, &user_id, &path.user_id)
+        .update_role(&path.id, &user_id, &path.user_id, form.role)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn transfer_ownership(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<TransferOwnership>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .transfer_ownership(&path.id, &user_id, &form.user_id)
         .await?;
 
     Ok(web::HttpResponse::Ok())
@@ -142,6 +163,7 @@ pub async fn remove_user(
 
 #[inline(always)]
 pub async fn get_championship(
+    req: web::HttpRequest,
     state: web::types::State<AppState>,
     path: web::types::Path<ChampionshipIdPath>,
 ) -> AppResult<impl web::Responder> {
@@ -149,10 +171,23 @@ pub async fn get_championship(
         Err(CommonError::ValidationFailed)?
     }
 
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
     let Some(championship) = state.championship_repository.find(&path.id).await? else {
         Err(ChampionshipError::NotFound)?
     };
 
+    if championship.visibility != Visibility::Public {
+        state
+            .championship_service
+            .authorize(&championship.id, &user_id, ChampionshipRole::Viewer)
+            .await?;
+    }
+
     Ok(web::HttpResponse::Ok().json(&championship))
 }
 
@@ -171,3 +206,26 @@ pub async fn all_championships(
 
     Ok(web::HttpResponse::Ok().json(&championships))
 }
+
+// Data of public championships is visible for everyone, the rest only for members
+#[inline(always)]
+async fn authorize_view(req: &web::HttpRequest, state: &AppState, id: &i32) -> AppResult<()> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let Some(championship) = state.championship_repository.find(id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    if championship.visibility != Visibility::Public {
+        state
+            .championship_service
+            .authorize(id, &user_id, ChampionshipRole::Viewer)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use super::authorize_view;
+use crate::{
+    dtos::{ApplyAdjustment, AssignDriver, ChampionshipIdPath, SessionPath},
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, CommonError, ResultError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn sessions(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let sessions = state.result_repository.sessions(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&sessions))
+}
+
+#[inline(always)]
+pub async fn session_results(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<SessionPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let results = state
+        .result_repository
+        .classification(&path.id, &path.session_uid)
+        .await?;
+
+    if results.is_empty() {
+        Err(ResultError::SessionNotFound)?
+    }
+
+    Ok(web::HttpResponse::Ok().json(&results))
+}
+
+// Lap times, positions at the end of every lap and tyre stints of each car
+#[inline(always)]
+pub async fn lap_chart(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<SessionPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let lap_chart = state
+        .lap_repository
+        .lap_chart(&path.id, &path.session_uid)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&lap_chart))
+}
+
+#[inline(always)]
+pub async fn result_adjustments(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<SessionPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let adjustments = state
+        .result_repository
+        .adjustments(&path.id, &path.session_uid)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&adjustments))
+}
+
+#[inline(always)]
+pub async fn apply_adjustment(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<ApplyAdjustment>,
+    path: web::types::Path<SessionPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Steward)
+        .await?;
+
+    state
+        .result_service
+        .apply_adjustment(&path.id, &path.session_uid, &user_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn assign_driver(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<AssignDriver>,
+    path: web::types::Path<SessionPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Steward)
+        .await?;
+
+    state
+        .result_service
+        .assign_driver(&path.id, &path.session_uid, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
@@ -0,0 +1,127 @@
+use super::authorize_view;
+use crate::{
+    dtos::{ChampionshipIdPath, CreateRound, RoundIdPath, UpdateRound},
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn rounds(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let rounds = state.round_repository.find_all(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&rounds))
+}
+
+#[inline(always)]
+pub async fn create_round(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<CreateRound>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state.round_service.create(&path.id, &form).await?;
+
+    Ok(web::HttpResponse::Created())
+}
+
+#[inline(always)]
+pub async fn update_round(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<UpdateRound>,
+    path: web::types::Path<RoundIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state
+        .round_service
+        .update(&path.id, &path.round_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn delete_round(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<RoundIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state.round_service.delete(&path.id, &path.round_id).await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+// Irregular slots are cars that started from a different position than the derived one
+#[inline(always)]
+pub async fn round_grid(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<RoundIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let grid = state.round_service.grid(&path.id, &path.round_id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&grid))
+}
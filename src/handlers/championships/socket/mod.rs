@@ -1,31 +1,48 @@
 pub(super) mod counter;
+mod stream;
+mod subscriptions;
+mod upgrade;
 
-use self::counter::{decrement, increment};
-use crate::dtos::ChampionshipIdPath;
+use self::{
+    counter::{decrement, increment},
+    subscriptions::Subscriptions,
+    upgrade::LiveSink,
+};
+use crate::dtos::{ChampionshipIdPath, SocketAuthQuery, TokenType, ViewerTicket};
 use crate::error::CommonError;
 use crate::{
-    error::{AppResult, ChampionshipError, SocketError},
+    entity::{Championship, ChampionshipRole, UserExtension, Visibility},
+    error::{AppResult, ChampionshipError, SocketError, TokenError},
+    middlewares::BEARER_PREFIX,
+    protos::socket_control::{ClientMessage, Encoding, TopicBatch, TopicKey},
+    services::TokenServiceTrait,
     states::AppState,
 };
 use garde::Validate;
 use ntex::{
     chain,
     channel::oneshot,
-    fn_service, rt,
-    service::{fn_factory_with_config, fn_shutdown, map_config},
-    util::{select, Bytes, Either},
+    fn_service,
+    http::header,
+    rt,
+    service::fn_shutdown,
+    util::{select, ByteString, Bytes, Either},
     web,
     ws::{self, Message},
     Service,
 };
-use std::{future::ready, io};
+use prost::Message as ProstMessage;
+use std::{cell::RefCell, future::ready, io, rc::Rc};
+pub(crate) use stream::*;
 use tokio::sync::broadcast::Receiver;
+use tracing::warn;
 
 #[inline(always)]
 pub async fn session_socket(
     req: web::HttpRequest,
     state: web::types::State<AppState>,
     path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<SocketAuthQuery>,
 ) -> AppResult<web::HttpResponse> {
     if path.validate(&()).is_err() {
         Err(CommonError::ValidationFailed)?
@@ -35,6 +52,8 @@ pub async fn session_socket(
         Err(ChampionshipError::NotFound)?
     };
 
+    authorize_viewer(&req, &state, &championship, query.ticket.as_deref()).await?;
+
     let socket_active = state
         .f123_service
         .is_championship_socket_active(&championship.id)
@@ -44,18 +63,159 @@ pub async fn session_socket(
         Err(SocketError::NotActive)?
     }
 
-    web::ws::start(
-        req,
-        map_config(fn_factory_with_config(web_socket), move |cfg| {
-            (cfg, state.clone(), path.id)
-        }),
-    )
+    // Negotiated by subprotocol, the query is a fallback for clients that can't offer one
+    let protocol_encoding = req
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|protocols| protocols.to_str().ok())
+        .and_then(Encoding::from_protocols);
+
+    let encoding = protocol_encoding.unwrap_or(query.encoding);
+    let protocol = protocol_encoding.map(|encoding| encoding.protocol());
+
+    upgrade::start(&req, protocol, |sink| {
+        web_socket(sink, state, championship.id, encoding)
+    })
     .await
 }
 
+#[inline(always)]
+pub async fn viewer_ticket(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let Some(championship) = state.championship_repository.find(&path.id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    if championship.visibility != Visibility::Public {
+        let users = state
+            .championship_repository
+            .users(&championship.id)
+            .await?;
+
+        if !users.contains(&user_id) {
+            Err(ChampionshipError::NotMember)?
+        }
+    }
+
+    let ticket = state
+        .token_service
+        .generate_viewer_ticket(championship.id, Some(user_id), TokenType::Viewer)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&ViewerTicket { ticket }))
+}
+
+// Every new invite link revokes the previous ones of the championship
+#[inline(always)]
+pub async fn invite_ticket(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let Some(championship) = state.championship_repository.find(&path.id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    state
+        .championship_service
+        .authorize(&championship.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    let ticket = state
+        .token_service
+        .generate_viewer_ticket(championship.id, None, TokenType::ViewerInvite)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&ViewerTicket { ticket }))
+}
+
+// Browsers can't set headers on websocket upgrades, so a viewer ticket in the query
+// is accepted as an alternative to the bearer token
+#[inline(always)]
+async fn authorize_viewer(
+    req: &web::HttpRequest,
+    state: &AppState,
+    championship: &Championship,
+    ticket: Option<&str>,
+) -> AppResult<()> {
+    if championship.visibility == Visibility::Public {
+        return Ok(());
+    }
+
+    let user_id = match ticket {
+        Some(ticket) => {
+            let claims = state
+                .token_service
+                .validate_viewer_ticket(ticket, &championship.id)
+                .await?;
+
+            if claims.token_type == TokenType::ViewerInvite {
+                if championship.visibility == Visibility::Invite {
+                    return Ok(());
+                }
+
+                Err(ChampionshipError::NotMember)?
+            }
+
+            claims.user_id.ok_or(TokenError::InvalidToken)?
+        }
+
+        None => {
+            let header = req
+                .headers()
+                .get("Authorization")
+                .ok_or(TokenError::MissingToken)?;
+
+            let header_str = header.to_str().map_err(|_| TokenError::InvalidToken)?;
+            let Some(token) = header_str.strip_prefix(BEARER_PREFIX) else {
+                Err(TokenError::InvalidToken)?
+            };
+
+            state.token_service.validate_access(token).await?
+        }
+    };
+
+    let users = state
+        .championship_repository
+        .users(&championship.id)
+        .await?;
+
+    if !users.contains(&user_id) {
+        Err(ChampionshipError::NotMember)?
+    }
+
+    Ok(())
+}
+
 #[inline(always)]
 async fn web_socket(
-    (sink, state, championship_id): (web::ws::WsSink, web::types::State<AppState>, i32),
+    sink: LiveSink,
+    state: web::types::State<AppState>,
+    championship_id: i32,
+    encoding: Encoding,
 ) -> AppResult<impl Service<ws::Frame, Response = Option<Message>, Error = io::Error>> {
     let (tx, close_rx) = oneshot::channel();
 
@@ -65,14 +225,18 @@ async fn web_socket(
             .get_cache_data(&championship_id)
             .await?;
 
-        if let Some(data) = cache {
-            if sink.send(Message::Binary(Bytes::from(data))).await.is_err() {
+        let message = cache
+            .and_then(|data| TopicBatch::from_compressed(TopicKey::ALL, Bytes::from(data)))
+            .and_then(|batch| socket_message(&batch, encoding));
+
+        if let Some(message) = message {
+            if sink.send(message).await.is_err() {
                 return Err(SocketError::FailedToSendMessage.into());
             };
         }
     }
 
-    let Some(rx) = state
+    let Some((rx, subscribers)) = state
         .f123_service
         .subscribe_to_championship_events(&championship_id)
         .await
@@ -80,10 +244,35 @@ async fn web_socket(
         return Err(SocketError::NotFound.into());
     };
 
+    let subscriptions = Rc::new(RefCell::new(Subscriptions::new(subscribers)));
+
     increment(championship_id);
-    rt::spawn(send_data(sink, rx, close_rx));
+    rt::spawn(send_data(
+        sink,
+        rx,
+        close_rx,
+        subscriptions.clone(),
+        encoding,
+    ));
+
+    let service = fn_service(move |frame| {
+        let response = match frame {
+            ws::Frame::Binary(data) => {
+                match ClientMessage::decode(data) {
+                    Ok(message) => subscriptions.borrow_mut().apply(message),
+                    Err(e) => warn!("Invalid socket control message: {}", e),
+                }
 
-    let service = fn_service(move |_| ready(Ok(None)));
+                None
+            }
+
+            ws::Frame::Ping(data) => Some(Message::Pong(data)),
+            ws::Frame::Close(reason) => Some(Message::Close(reason)),
+            _ => None,
+        };
+
+        ready(Ok(response))
+    });
 
     let on_shutdown = fn_shutdown(move || {
         decrement(championship_id);
@@ -95,13 +284,33 @@ async fn web_socket(
 
 #[inline(always)]
 async fn send_data(
-    sink: web::ws::WsSink,
-    mut rx: Receiver<Bytes>,
+    sink: LiveSink,
+    mut rx: Receiver<TopicBatch>,
     mut close_rx: oneshot::Receiver<()>,
+    subscriptions: Rc<RefCell<Subscriptions>>,
+    encoding: Encoding,
 ) {
-    while let Either::Left(Ok(data)) = select(rx.recv(), &mut close_rx).await {
-        if sink.send(Message::Binary(data)).await.is_err() {
+    while let Either::Left(Ok(batch)) = select(rx.recv(), &mut close_rx).await {
+        if !subscriptions.borrow().wants(&batch.key) {
+            continue;
+        }
+
+        let Some(message) = socket_message(&batch, encoding) else {
+            continue;
+        };
+
+        if sink.send(message).await.is_err() {
             break;
         }
     }
 }
+
+#[inline(always)]
+fn socket_message(batch: &TopicBatch, encoding: Encoding) -> Option<Message> {
+    let data = batch.encoded(encoding)?;
+
+    match encoding {
+        Encoding::Json => ByteString::try_from(data).ok().map(Message::Text),
+        Encoding::Zstd | Encoding::Protobuf => Some(Message::Binary(data)),
+    }
+}
@@ -0,0 +1,185 @@
+use super::{
+    authorize_viewer,
+    counter::{decrement, increment},
+};
+use crate::{
+    config::constants::{STREAM_BUFFER, STREAM_KEEP_ALIVE},
+    dtos::{ChampionshipIdPath, SocketAuthQuery},
+    error::{AppResult, ChampionshipError, CommonError, SocketError},
+    protos::socket_control::{Encoding, TopicBatch, TopicKey},
+    states::AppState,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use garde::Validate;
+use ntex::{
+    rt,
+    util::{Bytes, Stream},
+    web,
+};
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{
+        broadcast::{error::RecvError, Receiver},
+        mpsc,
+    },
+    time::timeout,
+};
+
+type StreamSender = mpsc::Sender<Result<Bytes, io::Error>>;
+
+// Events waiting to be written to a client, bounded so a slow client can't make them pile up
+struct EventStream(mpsc::Receiver<Result<Bytes, io::Error>>);
+
+impl Stream for EventStream {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_recv(cx)
+    }
+}
+
+// Fallback for clients behind proxies that block websockets, relays the same batches
+// as server sent events, binary encodings are sent base64 encoded.
+// Viewer tickets only last a minute, so the built in EventSource reconnect fails once it
+// expired, clients have to close the source on error and reopen it with a new ticket
+#[inline(always)]
+pub async fn live_stream(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<SocketAuthQuery>,
+) -> AppResult<web::HttpResponse> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let Some(championship) = state.championship_repository.find(&path.id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    authorize_viewer(&req, &state, &championship, query.ticket.as_deref()).await?;
+
+    let Some((rx, _)) = state
+        .f123_service
+        .subscribe_to_championship_events(&championship.id)
+        .await
+    else {
+        Err(SocketError::NotActive)?
+    };
+
+    let encoding = query.encoding;
+    let (tx, stream) = mpsc::channel(STREAM_BUFFER);
+
+    let cache = state
+        .f123_repository
+        .get_cache_data(&championship.id)
+        .await?;
+
+    let message = cache
+        .and_then(|data| TopicBatch::from_compressed(TopicKey::ALL, Bytes::from(data)))
+        .and_then(|batch| event(&batch, encoding));
+
+    if let Some(message) = message {
+        if tx.try_send(Ok(message)).is_err() {
+            Err(SocketError::FailedToSendMessage)?
+        }
+    }
+
+    increment(championship.id);
+    rt::spawn(relay(championship.id, rx, tx, encoding));
+
+    Ok(web::HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .set_header("cache-control", "no-cache")
+        .set_header("x-accel-buffering", "no")
+        .streaming(EventStream(stream)))
+}
+
+// Latest cached batch for polling clients, empty if the session didn't send anything yet
+#[inline(always)]
+pub async fn live_snapshot(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<SocketAuthQuery>,
+) -> AppResult<web::HttpResponse> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let Some(championship) = state.championship_repository.find(&path.id).await? else {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    authorize_viewer(&req, &state, &championship, query.ticket.as_deref()).await?;
+
+    let data = state
+        .f123_repository
+        .get_cache_data(&championship.id)
+        .await?
+        .and_then(|data| TopicBatch::from_compressed(TopicKey::ALL, Bytes::from(data)))
+        .and_then(|batch| batch.encoded(query.encoding));
+
+    let Some(data) = data else {
+        return Ok(web::HttpResponse::NoContent().finish());
+    };
+
+    let content_type = match query.encoding {
+        Encoding::Json => "application/json",
+        Encoding::Zstd | Encoding::Protobuf => "application/octet-stream",
+    };
+
+    Ok(web::HttpResponse::Ok()
+        .content_type(content_type)
+        .set_header("cache-control", "no-cache")
+        .body(data))
+}
+
+#[inline(always)]
+async fn relay(
+    championship_id: i32,
+    mut rx: Receiver<TopicBatch>,
+    tx: StreamSender,
+    encoding: Encoding,
+) {
+    loop {
+        let message = match timeout(STREAM_KEEP_ALIVE, rx.recv()).await {
+            Ok(Ok(batch)) if batch.key == TopicKey::ALL => {
+                let Some(message) = event(&batch, encoding) else {
+                    continue;
+                };
+
+                message
+            }
+
+            Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => continue,
+            Ok(Err(RecvError::Closed)) => break,
+            // Comments keep idle proxies from closing the connection
+            Err(_) => Bytes::from_static(b": keep-alive\n\n"),
+        };
+
+        // Clients that can't keep up are dropped like lagging websockets, they
+        // reconnect and start again from the cached batch
+        if tx.try_send(Ok(message)).is_err() {
+            break;
+        }
+    }
+
+    decrement(championship_id);
+}
+
+#[inline(always)]
+fn event(batch: &TopicBatch, encoding: Encoding) -> Option<Bytes> {
+    let data = batch.encoded(encoding)?;
+
+    let data = match encoding {
+        Encoding::Json => String::from_utf8(data.to_vec()).ok()?,
+        Encoding::Zstd | Encoding::Protobuf => STANDARD.encode(data),
+    };
+
+    Some(Bytes::from(format!("event: batch\ndata: {data}\n\n")))
+}
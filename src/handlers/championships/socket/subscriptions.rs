@@ -0,0 +1,195 @@
+use crate::{
+    protos::socket_control::{client_message::Action, ClientMessage, Topic, TopicKey},
+    services::TopicSubscribers,
+};
+use ahash::AHashSet;
+use tracing::warn;
+
+// Topics a websocket client is listening to, everything by default. Every topic is also
+// counted in the subscribers of the championship so its batches get encoded
+pub struct Subscriptions {
+    keys: AHashSet<TopicKey>,
+    subscribers: TopicSubscribers,
+}
+
+impl Subscriptions {
+    pub fn new(subscribers: TopicSubscribers) -> Self {
+        Self {
+            keys: AHashSet::from_iter([TopicKey::ALL]),
+            subscribers,
+        }
+    }
+
+    pub fn apply(&mut self, message: ClientMessage) {
+        let Ok(action) = Action::try_from(message.action) else {
+            warn!("Unknown socket control action: {}", message.action);
+            return;
+        };
+
+        for subscription in message.subscriptions {
+            let Ok(topic) = Topic::try_from(subscription.topic) else {
+                warn!("Unknown socket topic: {}", subscription.topic);
+                continue;
+            };
+
+            // Car filters only make sense for session history
+            let car_idx = match topic {
+                Topic::SessionHistoryData => subscription
+                    .car_idx
+                    .and_then(|car_idx| u8::try_from(car_idx).ok()),
+                _ => None,
+            };
+
+            let key = TopicKey { topic, car_idx };
+
+            match action {
+                Action::Subscribe => {
+                    // Subscribing to something concrete means the client doesn't want everything anymore
+                    if key == TopicKey::ALL {
+                        self.clear();
+                    } else {
+                        self.keys.remove(&TopicKey::ALL);
+                    }
+
+                    if self.keys.insert(key) && key != TopicKey::ALL {
+                        self.subscribers.add(key);
+                    }
+                }
+
+                Action::Unsubscribe => {
+                    if self.keys.remove(&key) && key != TopicKey::ALL {
+                        self.subscribers.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    // Clients listening to everything only receive the global batch,
+    // the rest receive the batches of each topic they are subscribed to
+    #[inline(always)]
+    pub fn wants(&self, key: &TopicKey) -> bool {
+        if self.keys.contains(&TopicKey::ALL) {
+            return *key == TopicKey::ALL;
+        }
+
+        if key.car_idx.is_some() {
+            let all_cars = TopicKey {
+                topic: key.topic,
+                car_idx: None,
+            };
+
+            if self.keys.contains(&all_cars) {
+                return true;
+            }
+        }
+
+        self.keys.contains(key)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self) {
+        for key in self.keys.drain() {
+            if key != TopicKey::ALL {
+                self.subscribers.remove(&key);
+            }
+        }
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Subscriptions;
+    use crate::{
+        protos::socket_control::{
+            client_message::Action, ClientMessage, Subscription, Topic, TopicKey,
+        },
+        services::TopicSubscribers,
+    };
+
+    fn message(action: Action, subscriptions: Vec<Subscription>) -> ClientMessage {
+        ClientMessage {
+            action: action.into(),
+            subscriptions,
+        }
+    }
+
+    #[test]
+    fn test_defaults_to_global_batch() {
+        let subscriptions = Subscriptions::new(TopicSubscribers::default());
+        let motion = TopicKey {
+            topic: Topic::CarMotion,
+            car_idx: None,
+        };
+
+        assert!(subscriptions.wants(&TopicKey::ALL));
+        assert!(!subscriptions.wants(&motion));
+    }
+
+    #[test]
+    fn test_car_history_subscription() {
+        let subscribers = TopicSubscribers::default();
+        let mut subscriptions = Subscriptions::new(subscribers.clone());
+
+        subscriptions.apply(message(
+            Action::Subscribe,
+            vec![Subscription {
+                topic: Topic::SessionHistoryData.into(),
+                car_idx: Some(3),
+            }],
+        ));
+
+        let history = |car_idx| TopicKey {
+            topic: Topic::SessionHistoryData,
+            car_idx: Some(car_idx),
+        };
+
+        assert!(!subscriptions.wants(&TopicKey::ALL));
+        assert!(subscriptions.wants(&history(3)));
+        assert!(!subscriptions.wants(&history(4)));
+        assert!(subscribers.wanted(&history(3)));
+        assert!(!subscribers.wanted(&history(4)));
+
+        subscriptions.apply(message(
+            Action::Unsubscribe,
+            vec![Subscription {
+                topic: Topic::SessionHistoryData.into(),
+                car_idx: Some(3),
+            }],
+        ));
+
+        assert!(!subscriptions.wants(&history(3)));
+        assert!(!subscribers.wanted(&history(3)));
+    }
+
+    #[test]
+    fn test_subscribers_released_on_drop() {
+        let subscribers = TopicSubscribers::default();
+        let motion = TopicKey {
+            topic: Topic::CarMotion,
+            car_idx: None,
+        };
+
+        {
+            let mut subscriptions = Subscriptions::new(subscribers.clone());
+
+            subscriptions.apply(message(
+                Action::Subscribe,
+                vec![Subscription {
+                    topic: Topic::CarMotion.into(),
+                    car_idx: None,
+                }],
+            ));
+
+            assert!(subscribers.wanted(&motion));
+        }
+
+        assert!(!subscribers.wanted(&motion));
+    }
+}
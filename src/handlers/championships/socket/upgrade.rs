@@ -0,0 +1,105 @@
+use crate::error::AppResult;
+use ntex::{
+    chain,
+    http::{body::BodySize, h1, header},
+    io::{DispatchItem, Dispatcher, DispatcherConfig, IoRef},
+    rt,
+    service::apply_fn,
+    time::Seconds,
+    util::{Either, Ready},
+    web,
+    ws::{
+        self,
+        error::{HandshakeError, ProtocolError, WsError},
+    },
+    Service,
+};
+use std::{future::Future, io};
+
+// Sink of a live data socket, `web::ws::WsSink` can only be built by ntex itself
+#[derive(Clone)]
+pub struct LiveSink {
+    io: IoRef,
+    codec: ws::Codec,
+}
+
+impl LiveSink {
+    #[inline(always)]
+    pub async fn send(&self, message: ws::Message) -> Result<(), ProtocolError> {
+        self.io.encode(message, &self.codec)
+    }
+}
+
+// Same as `web::ws::start` but the negotiated subprotocol is echoed in the handshake,
+// browsers drop the connection when they offered one and the response doesn't include it
+pub async fn start<S, F, Fut>(
+    req: &web::HttpRequest,
+    protocol: Option<&'static str>,
+    factory: F,
+) -> AppResult<web::HttpResponse>
+where
+    S: Service<ws::Frame, Response = Option<ws::Message>, Error = io::Error> + 'static,
+    F: FnOnce(LiveSink) -> Fut,
+    Fut: Future<Output = AppResult<S>>,
+{
+    let mut response = ws::handshake(req.head())?;
+
+    if let Some(protocol) = protocol {
+        response.header(header::SEC_WEBSOCKET_PROTOCOL, protocol);
+    }
+
+    let response = response.finish().into_parts().0;
+
+    let (io, codec) = *req
+        .head()
+        .take_io()
+        .ok_or(HandshakeError::NoWebsocketUpgrade)?;
+
+    io.encode(h1::Message::Item((response, BodySize::Empty)), &codec)
+        .map_err(|_| HandshakeError::NoWebsocketUpgrade)?;
+
+    let codec = ws::Codec::new();
+    let sink = LiveSink {
+        io: io.get_ref(),
+        codec: codec.clone(),
+    };
+
+    let service = chain(factory(sink.clone()).await?).map_err(WsError::Service);
+
+    let service = apply_fn(service, move |item, service| match item {
+        DispatchItem::Item(frame) => {
+            let close = matches!(frame, ws::Frame::Close(_));
+            let io = sink.io.clone();
+
+            Either::Left(async move {
+                let result = service.call(frame).await;
+
+                if close {
+                    rt::spawn(async move { io.close() });
+                }
+
+                result
+            })
+        }
+
+        DispatchItem::WBackPressureEnabled | DispatchItem::WBackPressureDisabled => {
+            Either::Right(Ready::Ok(None))
+        }
+
+        DispatchItem::KeepAliveTimeout => Either::Right(Ready::Err(WsError::KeepAlive)),
+        DispatchItem::ReadTimeout => Either::Right(Ready::Err(WsError::ReadTimeout)),
+        DispatchItem::DecoderError(e) | DispatchItem::EncoderError(e) => {
+            Either::Right(Ready::Err(WsError::Protocol(e)))
+        }
+        DispatchItem::Disconnect(e) => Either::Right(Ready::Err(WsError::Disconnected(e))),
+    });
+
+    let cfg = DispatcherConfig::default();
+    cfg.set_keepalive_timeout(Seconds::ZERO);
+
+    rt::spawn(async move {
+        let _ = Dispatcher::with_config(io, codec, service, &cfg).await;
+    });
+
+    Ok(web::HttpResponse::Ok().finish())
+}
@@ -3,6 +3,7 @@ use crate::dtos::ChampionshipIdPath;
 use crate::error::CommonError;
 use crate::{
     dtos::SocketStatus,
+    entity::{ChampionshipRole, UserExtension, Visibility},
     error::{AppResult, ChampionshipError},
     states::AppState,
 };
@@ -18,6 +19,7 @@ pub async fn active_sockets(state: web::types::State<AppState>) -> AppResult<imp
 
 #[inline(always)]
 pub async fn start_socket(
+    req: web::HttpRequest,
     state: web::types::State<AppState>,
     path: web::types::Path<ChampionshipIdPath>,
 ) -> AppResult<impl web::Responder> {
@@ -25,10 +27,21 @@ pub async fn start_socket(
         Err(CommonError::ValidationFailed)?
     }
 
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
     let Some(championship) = state.championship_repository.find(&path.id).await? else {
         Err(ChampionshipError::NotFound)?
     };
 
+    state
+        .championship_service
+        .authorize(&championship.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
     state
         .f123_service
         .setup_championship_listening_socket(championship.port, Arc::new(championship.id))
@@ -39,6 +52,7 @@ pub async fn start_socket(
 
 #[inline(always)]
 pub async fn socket_status(
+    req: web::HttpRequest,
     state: web::types::State<AppState>,
     path: web::types::Path<ChampionshipIdPath>,
 ) -> AppResult<impl web::Responder> {
@@ -46,10 +60,23 @@ pub async fn socket_status(
         Err(CommonError::ValidationFailed)?
     }
 
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
     let Some(championship) = state.championship_repository.find(&path.id).await? else {
         Err(ChampionshipError::NotFound)?
     };
 
+    if championship.visibility != Visibility::Public {
+        state
+            .championship_service
+            .authorize(&championship.id, &user_id, ChampionshipRole::Viewer)
+            .await?;
+    }
+
     let mut num_connections = 0;
     let socket_active = state
         .f123_service
@@ -72,6 +99,7 @@ pub async fn socket_status(
 
 #[inline(always)]
 pub async fn stop_socket(
+    req: web::HttpRequest,
     state: web::types::State<AppState>,
     path: web::types::Path<ChampionshipIdPath>,
 ) -> AppResult<impl web::Responder> {
@@ -79,6 +107,26 @@ pub async fn stop_socket(
         Err(CommonError::ValidationFailed)?
     }
 
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    if state
+        .championship_repository
+        .find(&path.id)
+        .await?
+        .is_none()
+    {
+        Err(ChampionshipError::NotFound)?
+    };
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
     state.f123_service.stop_socket(path.id).await?;
 
     Ok(web::HttpResponse::Ok())
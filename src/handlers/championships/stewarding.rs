@@ -0,0 +1,146 @@
+use super::authorize_view;
+use crate::{
+    dtos::{ChampionshipIdPath, FileProtest, IncidentsQuery, ProtestIdPath, RuleProtest},
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn incidents(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<IncidentsQuery>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Driver)
+        .await?;
+
+    let incidents = state
+        .stewarding_repository
+        .incidents(&path.id, &query.session_uid)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&incidents))
+}
+
+#[inline(always)]
+pub async fn protests(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Driver)
+        .await?;
+
+    let protests = state.stewarding_repository.protests(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&protests))
+}
+
+#[inline(always)]
+pub async fn file_protest(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<FileProtest>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Driver)
+        .await?;
+
+    state
+        .stewarding_service
+        .file_protest(&path.id, &user_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Created())
+}
+
+#[inline(always)]
+pub async fn rule_protest(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<RuleProtest>,
+    path: web::types::Path<ProtestIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Steward)
+        .await?;
+
+    state
+        .stewarding_service
+        .rule_protest(&path.id, &path.protest_id, &user_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn penalties(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<IncidentsQuery>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let penalties = state
+        .stewarding_repository
+        .penalties(&path.id, &query.session_uid)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&penalties))
+}
@@ -0,0 +1,211 @@
+use super::authorize_view;
+use crate::{
+    dtos::{
+        AddTeamDriver, ChampionshipIdPath, CreateTeam, LineupQuery, TeamDriverPath, TeamIdPath,
+        UpdateTeam,
+    },
+    entity::{ChampionshipRole, UserExtension},
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use chrono::Utc;
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub async fn create_team(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<CreateTeam>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state.team_service.create(&path.id, &form).await?;
+
+    Ok(web::HttpResponse::Created())
+}
+
+#[inline(always)]
+pub async fn teams(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let teams = state.team_repository.find_all(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&teams))
+}
+
+#[inline(always)]
+pub async fn team_lineup(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+    query: web::types::Query<LineupQuery>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let at = query.at.unwrap_or_else(Utc::now);
+    let lineup = state.team_repository.lineup(&path.id, &at).await?;
+
+    Ok(web::HttpResponse::Ok().json(&lineup))
+}
+
+#[inline(always)]
+pub async fn constructor_standings(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ChampionshipIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    authorize_view(&req, &state, &path.id).await?;
+
+    let standings = state
+        .team_repository
+        .constructor_standings(&path.id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&standings))
+}
+
+#[inline(always)]
+pub async fn update_team(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<UpdateTeam>,
+    path: web::types::Path<TeamIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state
+        .team_service
+        .update(&path.id, &path.team_id, &form)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn delete_team(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<TeamIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state.team_service.delete(&path.id, &path.team_id).await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn add_team_driver(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<AddTeamDriver>,
+    path: web::types::Path<TeamIdPath>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() || path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state
+        .team_service
+        .add_driver(&path.id, &path.team_id, &form.user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub async fn remove_team_driver(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<TeamDriverPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .championship_service
+        .authorize(&path.id, &user_id, ChampionshipRole::Admin)
+        .await?;
+
+    state
+        .team_service
+        .remove_driver(&path.id, &path.team_id, &path.user_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
@@ -0,0 +1,56 @@
+use crate::{
+    dtos::{RecordsQuery, TrackIdPath, UserIdPath},
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub(crate) async fn track_records(
+    state: web::types::State<AppState>,
+    query: web::types::Query<RecordsQuery>,
+) -> AppResult<impl web::Responder> {
+    if query.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let records = state
+        .record_repository
+        .track_records(&query.category, &query.season)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&records))
+}
+
+#[inline(always)]
+pub(crate) async fn track_leaderboard(
+    state: web::types::State<AppState>,
+    path: web::types::Path<TrackIdPath>,
+    query: web::types::Query<RecordsQuery>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() || query.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let records = state
+        .record_repository
+        .leaderboard(&path.track_id, &query.category, &query.season)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&records))
+}
+
+#[inline(always)]
+pub(crate) async fn personal_bests(
+    state: web::types::State<AppState>,
+    path: web::types::Path<UserIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let records = state.record_repository.personal_bests(&path.id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&records))
+}
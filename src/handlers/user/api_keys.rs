@@ -0,0 +1,66 @@
+use crate::{
+    dtos::{ApiKeyIdPath, CreateApiKey, CreatedApiKey},
+    entity::UserExtension,
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub(crate) async fn api_keys(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+) -> AppResult<impl web::Responder> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let api_keys = state.api_key_repository.user_keys(&user_id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&api_keys))
+}
+
+#[inline(always)]
+pub(crate) async fn create_api_key(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<CreateApiKey>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let (api_key, key) = state.api_key_service.create(&user_id, &form).await?;
+
+    Ok(web::HttpResponse::Created().json(&CreatedApiKey { api_key, key }))
+}
+
+#[inline(always)]
+pub(crate) async fn revoke_api_key(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ApiKeyIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state.api_key_service.revoke(&user_id, &path.key_id).await?;
+
+    Ok(web::HttpResponse::Ok())
+}
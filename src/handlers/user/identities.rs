@@ -0,0 +1,61 @@
+use crate::{
+    dtos::{AuthorizeUrl, ProviderPath},
+    entity::UserExtension,
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use ntex::web;
+
+#[inline(always)]
+pub(crate) async fn identities(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+) -> AppResult<impl web::Responder> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let identities = state.identity_repository.user_identities(&user_id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&identities))
+}
+
+// Returns the url of the provider, the callback links the account instead of logging in
+#[inline(always)]
+pub(crate) async fn link_identity(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ProviderPath>,
+) -> AppResult<impl web::Responder> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let url = state
+        .oauth_service
+        .authorize_url(&path.provider, Some(user_id), None)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&AuthorizeUrl { url }))
+}
+
+#[inline(always)]
+pub(crate) async fn unlink_identity(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<ProviderPath>,
+) -> AppResult<impl web::Responder> {
+    let user = req
+        .extensions()
+        .get::<UserExtension>()
+        .cloned()
+        .ok_or(CommonError::InternalServerError)?;
+
+    state.oauth_service.unlink(&user, &path.provider).await?;
+
+    Ok(web::HttpResponse::Ok())
+}
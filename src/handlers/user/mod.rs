@@ -1,15 +1,28 @@
 use crate::{
-    dtos::{UpdateUser, UserData},
-    entity::UserExtension,
-    error::{AppResult, CommonError},
-    services::UserServiceTrait,
+    config::constants::EMAIL_CHANGE_ACCOUNT_LIMIT,
+    dtos::{
+        ChangeEmail, ConfirmEmailChange, EmailChangeRequested, EmailUser, TokenType, UpdateUser,
+        UserData, UserIdPath,
+    },
+    entity::{Provider, UserExtension},
+    error::{AppResult, CommonError, UserError},
+    repositories::UserRepositoryTrait,
+    services::{TokenServiceTrait, UserServiceTrait},
     states::AppState,
 };
 pub(crate) use admin::*;
+pub(crate) use api_keys::*;
 use garde::Validate;
+pub(crate) use identities::*;
 use ntex::web;
+pub(crate) use sessions::*;
+pub(crate) use two_factor::*;
 
 mod admin;
+mod api_keys;
+mod identities;
+mod sessions;
+mod two_factor;
 
 #[inline(always)]
 pub(crate) async fn user_data(
@@ -49,3 +62,121 @@ pub(crate) async fn update_user(
     state.user_service.update(&user, &form).await?;
     Ok(web::HttpResponse::Ok())
 }
+
+// The link goes to the new address and the current one is told about the request
+#[inline(always)]
+pub(crate) async fn change_email(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<ChangeEmail>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    };
+
+    let user = req
+        .extensions()
+        .get::<UserExtension>()
+        .cloned()
+        .ok_or(CommonError::InternalServerError)?;
+
+    state
+        .rate_limit_service
+        .check(&EMAIL_CHANGE_ACCOUNT_LIMIT, &user.id.to_string())
+        .await?;
+
+    if form.email == user.email {
+        Err(UserError::InvalidUpdate)?
+    }
+
+    if user.provider == Provider::Local {
+        let Some(password) = &form.password else {
+            Err(CommonError::ValidationFailed)?
+        };
+
+        if !state
+            .user_repository
+            .validate_password(password, user.password.as_ref().unwrap())?
+        {
+            Err(UserError::InvalidCredentials)?
+        }
+    }
+
+    if state.user_repository.user_exists(&form.email).await? {
+        Err(UserError::AlreadyExists)?
+    }
+
+    let token = state
+        .token_service
+        .generate_token(user.id, TokenType::EmailChange)
+        .await?;
+
+    let confirm_template = ConfirmEmailChange {
+        confirmation_link: &format!(
+            "https://intellitelemetry.live/auth/verify-email-change?token={}",
+            token
+        ),
+    };
+
+    let notice_template = EmailChangeRequested {
+        new_email: &form.email,
+    };
+
+    let save_token_future = state
+        .token_service
+        .save_email_change_token(&token, &form.email);
+
+    let confirm_future = state.email_service.send_mail(
+        EmailUser {
+            username: &user.username,
+            email: &form.email,
+        },
+        "Confirm Email Change",
+        confirm_template,
+    );
+
+    let notice_future = state.email_service.send_mail(
+        EmailUser {
+            username: &user.username,
+            email: &user.email,
+        },
+        "Email Change Requested",
+        notice_template,
+    );
+
+    tokio::try_join!(save_token_future, confirm_future, notice_future)?;
+    Ok(web::HttpResponse::Ok())
+}
+
+// Totals and points of championships that aren't public are only shown to the driver
+#[inline(always)]
+pub(crate) async fn user_stats(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<UserIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    if state.user_repository.find(&path.id).await?.is_none() {
+        Err(UserError::NotFound)?
+    }
+
+    let mut stats = state.stats_repository.driver_stats(&path.id).await?;
+
+    if user_id != path.id {
+        stats.career = std::mem::take(&mut stats.public_career);
+        stats
+            .championships
+            .retain(|championship| championship.public);
+    }
+
+    Ok(web::HttpResponse::Ok().json(&stats))
+}
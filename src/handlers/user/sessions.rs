@@ -0,0 +1,72 @@
+use crate::{
+    dtos::SessionIdPath,
+    entity::UserExtension,
+    error::{AppResult, CommonError, TokenError},
+    services::TokenServiceTrait,
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub(crate) async fn sessions(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+) -> AppResult<impl web::Responder> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let sessions = state.token_service.sessions(&user_id).await?;
+
+    Ok(web::HttpResponse::Ok().json(&sessions))
+}
+
+#[inline(always)]
+pub(crate) async fn revoke_session(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    path: web::types::Path<SessionIdPath>,
+) -> AppResult<impl web::Responder> {
+    if path.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let sessions = state.token_service.sessions(&user_id).await?;
+
+    if !sessions.iter().any(|session| session.id == path.session_id) {
+        Err(TokenError::TokenNotFound)?
+    }
+
+    state
+        .token_service
+        .revoke_session(&user_id, &path.session_id)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+// Log out everywhere, the access tokens already issued are revoked too
+#[inline(always)]
+pub(crate) async fn revoke_sessions(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+) -> AppResult<impl web::Responder> {
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state.token_service.revoke_user(&user_id).await?;
+
+    Ok(web::HttpResponse::Ok())
+}
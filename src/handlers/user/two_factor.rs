@@ -0,0 +1,99 @@
+use crate::{
+    dtos::{RecoveryCodes, TwoFactorCode, TwoFactorSetup},
+    entity::UserExtension,
+    error::{AppResult, CommonError},
+    states::AppState,
+};
+use garde::Validate;
+use ntex::web;
+
+#[inline(always)]
+pub(crate) async fn setup_two_factor(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+) -> AppResult<impl web::Responder> {
+    let user = req
+        .extensions()
+        .get::<UserExtension>()
+        .cloned()
+        .ok_or(CommonError::InternalServerError)?;
+
+    let (secret, provisioning_uri) = state.two_factor_service.setup(&user).await?;
+
+    Ok(web::HttpResponse::Ok().json(&TwoFactorSetup {
+        secret,
+        provisioning_uri,
+    }))
+}
+
+#[inline(always)]
+pub(crate) async fn enable_two_factor(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<TwoFactorCode>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let recovery_codes = state
+        .two_factor_service
+        .enable(&user_id, &form.code)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&RecoveryCodes { recovery_codes }))
+}
+
+#[inline(always)]
+pub(crate) async fn disable_two_factor(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<TwoFactorCode>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    state
+        .two_factor_service
+        .disable(&user_id, &form.code)
+        .await?;
+
+    Ok(web::HttpResponse::Ok())
+}
+
+#[inline(always)]
+pub(crate) async fn regenerate_recovery_codes(
+    req: web::HttpRequest,
+    state: web::types::State<AppState>,
+    form: web::types::Form<TwoFactorCode>,
+) -> AppResult<impl web::Responder> {
+    if form.validate(&()).is_err() {
+        Err(CommonError::ValidationFailed)?
+    }
+
+    let user_id = req
+        .extensions()
+        .get::<UserExtension>()
+        .ok_or(CommonError::InternalServerError)?
+        .id;
+
+    let recovery_codes = state
+        .two_factor_service
+        .regenerate_recovery_codes(&user_id, &form.code)
+        .await?;
+
+    Ok(web::HttpResponse::Ok().json(&RecoveryCodes { recovery_codes }))
+}
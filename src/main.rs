@@ -13,29 +13,37 @@ mod states;
 mod utils;
 
 use cache::RedisCache;
-use config::{initialize_tracing_subscriber, Database};
+use config::{initialize_tracing_subscriber, Database, JwtKeys};
 use dotenvy::{dotenv, var};
 use ntex::{http, web};
 use ntex_cors::Cors;
 use services::FirewallService;
 use states::AppState;
+use std::io;
 
 #[cfg(not(test))]
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 #[ntex::main]
-async fn main() {
+async fn main() -> io::Result<()> {
     dotenv().ok();
     initialize_tracing_subscriber();
+
+    let jwt_keys =
+        JwtKeys::load().map_err(|e| io::Error::other(format!("Unable to load jwt keys: {e}")))?;
+    jwt_keys.schedule_reload();
+
     let app_state = {
         let db = Database::default().await;
         let redis_cache = RedisCache::new(&db);
         let firewall_service = FirewallService::new();
 
-        AppState::new(&db, firewall_service, &redis_cache).await
+        AppState::new(&db, firewall_service, &redis_cache, &jwt_keys).await
     };
 
+    app_state.user_service.schedule_purge();
+
     web::server(move || {
         web::App::new()
             .configure(routes::api_routes)
@@ -56,9 +64,7 @@ async fn main() {
                     .finish(),
             )
     })
-    .bind(var("HOST").unwrap())
-    .unwrap()
+    .bind(var("HOST").unwrap())?
     .run()
     .await
-    .unwrap();
 }
@@ -1,5 +1,6 @@
 use crate::{
-    error::{CommonError, TokenError, UserError},
+    entity::ApiKeyScope,
+    error::{ApiKeyError, CommonError, TokenError, UserError},
     repositories::UserRepositoryTrait,
     services::TokenServiceTrait,
     states::AppState,
@@ -11,7 +12,8 @@ use ntex::{
 };
 use std::sync::Arc;
 
-const BEARER_PREFIX: &str = "Bearer ";
+pub(crate) const BEARER_PREFIX: &str = "Bearer ";
+pub(crate) const API_KEY_HEADER: &str = "X-Api-Key";
 
 pub struct Authentication;
 
@@ -35,7 +37,10 @@ where
 {
     type Response = web::WebResponse;
     type Error = web::Error;
-    type Future<'f> = BoxFuture<'f, Result<Self::Response, Self::Error>> where Self: 'f;
+    type Future<'f>
+        = BoxFuture<'f, Result<Self::Response, Self::Error>>
+    where
+        Self: 'f;
 
     ntex::forward_poll_ready!(service);
 
@@ -46,9 +51,38 @@ where
     ) -> Self::Future<'_> {
         let state = req.app_state::<AppState>().cloned();
         let header = req.headers().get("Authorization").cloned();
+        let api_key_header = req.headers().get(API_KEY_HEADER).cloned();
+        let required_scope = ApiKeyScope::required(req.method().as_str(), req.path());
 
         let fut = async move {
             let state = state.ok_or(CommonError::InternalServerError)?;
+
+            // Api keys act as the user but only on the routes their scopes allow
+            if let Some(api_key_header) = api_key_header {
+                let key = api_key_header
+                    .to_str()
+                    .map_err(|_| ApiKeyError::InvalidKey)?;
+
+                let api_key = state.api_key_service.authenticate(key).await?;
+
+                if !required_scope.is_some_and(|scope| api_key.scopes.contains(&scope)) {
+                    return Err(ApiKeyError::MissingScope)?;
+                }
+
+                let user = state
+                    .user_repository
+                    .find(&api_key.user_id)
+                    .await?
+                    .ok_or(UserError::NotFound)?;
+
+                if !user.active {
+                    return Err(web::Error::from(UserError::NotVerified));
+                }
+
+                req.extensions_mut().insert(Arc::new(user));
+                return ctx.call(&self.service, req).await;
+            }
+
             let header = {
                 let header = header.ok_or(TokenError::MissingToken)?;
                 let header_str = header.to_str().map_err(|_| TokenError::InvalidToken)?;
@@ -60,7 +94,7 @@ where
                 header_str[BEARER_PREFIX.len()..].to_string()
             };
 
-            let id = state.token_service.validate(&header)?.claims.sub;
+            let id = state.token_service.validate_access(&header).await?;
             let user = state
                 .user_repository
                 .find(&id)
@@ -1,5 +1,7 @@
 mod admin;
 mod authenticated;
+mod rate_limit;
 
 pub(crate) use admin::*;
 pub(crate) use authenticated::*;
+pub(crate) use rate_limit::*;
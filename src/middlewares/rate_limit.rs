@@ -0,0 +1,70 @@
+use crate::{config::RateLimitRule, error::CommonError, states::AppState};
+use ntex::{
+    service::{Middleware, Service, ServiceCtx},
+    util::BoxFuture,
+    web,
+};
+
+// Limits the requests of every ip to the route it wraps, the ip is the one of the connection
+// because the forwarded headers can be set by the client
+pub struct RateLimit {
+    rule: RateLimitRule,
+}
+
+impl RateLimit {
+    pub fn new(rule: RateLimitRule) -> Self {
+        Self { rule }
+    }
+}
+
+impl<S> Middleware<S> for RateLimit {
+    type Service = RateLimitMiddleware<S>;
+
+    fn create(&self, service: S) -> Self::Service {
+        RateLimitMiddleware {
+            service,
+            rule: self.rule,
+        }
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: S,
+    rule: RateLimitRule,
+}
+
+impl<S, Err> Service<web::WebRequest<Err>> for RateLimitMiddleware<S>
+where
+    S: Service<web::WebRequest<Err>, Response = web::WebResponse, Error = web::Error>,
+    Err: web::ErrorRenderer,
+{
+    type Response = web::WebResponse;
+    type Error = web::Error;
+    type Future<'f>
+        = BoxFuture<'f, Result<Self::Response, Self::Error>>
+    where
+        Self: 'f;
+
+    ntex::forward_poll_ready!(service);
+
+    fn call<'a>(
+        &'a self,
+        req: web::WebRequest<Err>,
+        ctx: ServiceCtx<'a, Self>,
+    ) -> Self::Future<'a> {
+        let state = req.app_state::<AppState>().cloned();
+        let ip = req.peer_addr().map(|addr| addr.ip().to_string());
+
+        let fut = async move {
+            let state = state.ok_or(CommonError::InternalServerError)?;
+
+            if let Some(ip) = ip {
+                state.rate_limit_service.check(&self.rule, &ip).await?;
+            }
+
+            ctx.call(&self.service, req).await
+        };
+
+        Box::pin(fut)
+    }
+}
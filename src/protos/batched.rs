@@ -1,9 +1,34 @@
-use super::{ChunkPacketHeader, PacketHeader};
+use super::{
+    car_motion_data::{PacketMotionData, PacketMotionDelta},
+    event_data::PacketEventData,
+    final_classification::PacketFinalClassificationData,
+    packet_header::PacketType,
+    participants::PacketParticipantsData,
+    records::NewRecord,
+    session_data::PacketSessionData,
+    session_history::PacketSessionHistoryData,
+    ChunkPacketHeader, PacketHeader,
+};
 use ntex::util::{Bytes, BytesMut};
 use prost::Message;
+use serde::Serialize;
 
 pub struct ToProtoMessageBatched {}
 
+// Decoded packet used by the json output mode
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+enum JsonPacket {
+    CarMotion(PacketMotionData),
+    EventData(PacketEventData),
+    FinalClassificationData(PacketFinalClassificationData),
+    Participants(PacketParticipantsData),
+    SessionData(PacketSessionData),
+    SessionHistoryData(PacketSessionHistoryData),
+    CarMotionDelta(PacketMotionDelta),
+    NewRecord(NewRecord),
+}
+
 impl ToProtoMessageBatched {
     #[inline(always)]
     pub fn batched_encoded(packets: Vec<PacketHeader>) -> Option<Bytes> {
@@ -14,4 +39,42 @@ impl ToProtoMessageBatched {
         data.encode_raw(&mut buf);
         Some(buf.freeze())
     }
+
+    // Converts an already encoded batch to json, packets that can't be decoded are skipped
+    #[inline(always)]
+    pub fn json_encoded(batch: &[u8]) -> Option<Bytes> {
+        let chunk = ChunkPacketHeader::decode(batch).ok()?;
+
+        let packets: Vec<JsonPacket> = chunk.packets.iter().filter_map(Self::json_packet).collect();
+
+        serde_json::to_vec(&packets).ok().map(Bytes::from)
+    }
+
+    #[inline(always)]
+    fn json_packet(packet: &PacketHeader) -> Option<JsonPacket> {
+        let payload = &packet.payload[..];
+
+        let packet = match PacketType::try_from(packet.r#type).ok()? {
+            PacketType::CarMotion => JsonPacket::CarMotion(PacketMotionData::decode(payload).ok()?),
+            PacketType::EventData => JsonPacket::EventData(PacketEventData::decode(payload).ok()?),
+            PacketType::FinalClassificationData => JsonPacket::FinalClassificationData(
+                PacketFinalClassificationData::decode(payload).ok()?,
+            ),
+            PacketType::Participants => {
+                JsonPacket::Participants(PacketParticipantsData::decode(payload).ok()?)
+            }
+            PacketType::SessionData => {
+                JsonPacket::SessionData(PacketSessionData::decode(payload).ok()?)
+            }
+            PacketType::SessionHistoryData => {
+                JsonPacket::SessionHistoryData(PacketSessionHistoryData::decode(payload).ok()?)
+            }
+            PacketType::CarMotionDelta => {
+                JsonPacket::CarMotionDelta(PacketMotionDelta::decode(payload).ok()?)
+            }
+            PacketType::NewRecord => JsonPacket::NewRecord(NewRecord::decode(payload).ok()?),
+        };
+
+        Some(packet)
+    }
 }
@@ -27,6 +27,8 @@ impl ToProtoMessage for BPacketMotionData {
                     yaw: value.yaw,
                 })
                 .collect(),
+            // Assigned by the batching when the packet is sent as a keyframe
+            keyframe: 0,
         })
     }
 }
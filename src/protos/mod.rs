@@ -5,8 +5,10 @@ pub(crate) mod car_motion_data;
 pub(crate) mod event_data;
 pub(crate) mod final_classification;
 pub(crate) mod participants;
+pub(crate) mod records;
 pub(crate) mod session_data;
 pub(crate) mod session_history;
+pub(crate) mod socket_control;
 
 use crate::protos::packet_header::PacketType;
 use prost::Message;
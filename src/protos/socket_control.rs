@@ -0,0 +1,131 @@
+include!(concat!(env!("OUT_DIR"), "/protos.socket_control.rs"));
+
+use super::{batched::ToProtoMessageBatched, packet_header::PacketType};
+use ntex::util::Bytes;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::sync::Arc;
+
+// Output format negotiated by each live data client
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Zstd,
+    Protobuf,
+    Json,
+}
+
+// Every broadcasted batch is tagged with the topic it belongs to,
+// session history batches are also split by car
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TopicKey {
+    pub topic: Topic,
+    pub car_idx: Option<u8>,
+}
+
+// Every format is encoded at most once per batch and shared between all the subscribers,
+// json is only built when the first subscriber asks for it
+#[derive(Debug, Clone)]
+pub struct TopicBatch {
+    pub key: TopicKey,
+    raw: Bytes,
+    compressed: Bytes,
+    json: Arc<OnceCell<Option<Bytes>>>,
+}
+
+impl Encoding {
+    #[inline(always)]
+    pub fn protocol(&self) -> &'static str {
+        match self {
+            Encoding::Zstd => "intelli.zstd",
+            Encoding::Protobuf => "intelli.protobuf",
+            Encoding::Json => "intelli.json",
+        }
+    }
+
+    // First supported subprotocol of the Sec-WebSocket-Protocol header, it lists them by preference
+    pub fn from_protocols(header: &str) -> Option<Self> {
+        header.split(',').map(str::trim).find_map(|protocol| {
+            [Encoding::Zstd, Encoding::Protobuf, Encoding::Json]
+                .into_iter()
+                .find(|encoding| encoding.protocol() == protocol)
+        })
+    }
+}
+
+impl TopicBatch {
+    pub fn new(key: TopicKey, raw: Bytes, compressed: Bytes) -> Self {
+        Self {
+            key,
+            raw,
+            compressed,
+            json: Arc::new(OnceCell::new()),
+        }
+    }
+
+    // Cached batches are only stored compressed
+    pub fn from_compressed(key: TopicKey, compressed: Bytes) -> Option<Self> {
+        let raw = zstd::stream::decode_all(&compressed[..]).ok()?;
+        Some(Self::new(key, Bytes::from(raw), compressed))
+    }
+
+    #[inline(always)]
+    pub fn encoded(&self, encoding: Encoding) -> Option<Bytes> {
+        match encoding {
+            Encoding::Zstd => Some(self.compressed.clone()),
+            Encoding::Protobuf => Some(self.raw.clone()),
+            Encoding::Json => self
+                .json
+                .get_or_init(|| ToProtoMessageBatched::json_encoded(&self.raw))
+                .clone(),
+        }
+    }
+}
+
+impl TopicKey {
+    pub const ALL: TopicKey = TopicKey {
+        topic: Topic::All,
+        car_idx: None,
+    };
+
+    #[inline(always)]
+    pub fn new(packet_type: PacketType, car_idx: Option<u8>) -> Self {
+        Self {
+            topic: packet_type.into(),
+            car_idx,
+        }
+    }
+}
+
+impl From<PacketType> for Topic {
+    fn from(packet_type: PacketType) -> Self {
+        match packet_type {
+            PacketType::CarMotion | PacketType::CarMotionDelta => Topic::CarMotion,
+            PacketType::EventData => Topic::EventData,
+            PacketType::FinalClassificationData => Topic::FinalClassificationData,
+            PacketType::Participants => Topic::Participants,
+            PacketType::SessionData => Topic::SessionData,
+            PacketType::SessionHistoryData => Topic::SessionHistoryData,
+            PacketType::NewRecord => Topic::NewRecord,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    #[test]
+    fn test_encoding_from_protocols() {
+        assert_eq!(
+            Encoding::from_protocols("chat, intelli.json, intelli.zstd"),
+            Some(Encoding::Json)
+        );
+        assert_eq!(
+            Encoding::from_protocols("intelli.protobuf"),
+            Some(Encoding::Protobuf)
+        );
+        assert_eq!(Encoding::from_protocols("intelli.brotli"), None);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    config::Database,
+    entity::{ApiKey, FromRow},
+    error::AppResult,
+};
+
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    database: Database,
+}
+
+impl ApiKeyRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn user_keys(&self, user_id: &i32) -> AppResult<Vec<ApiKey>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let keys_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT id, user_id, name, prefix, scopes, last_used_at, created_at
+                        FROM api_keys
+                        WHERE user_id = $1
+                        ORDER BY created_at DESC
+                    "#,
+                )
+                .await?;
+
+            conn.query(&keys_stmt, &[user_id]).await?
+        };
+
+        rows.iter().map(ApiKey::from_row).collect()
+    }
+
+    pub async fn find_by_hash(&self, key_hash: &str) -> AppResult<Option<ApiKey>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let key_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT id, user_id, name, prefix, scopes, last_used_at, created_at
+                        FROM api_keys
+                        WHERE key_hash = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&key_stmt, &[&key_hash]).await?
+        };
+
+        row.map(|row| ApiKey::from_row(&row)).transpose()
+    }
+}
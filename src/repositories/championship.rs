@@ -1,7 +1,10 @@
 use crate::{
     cache::{EntityCache, RedisCache},
     config::Database,
-    entity::{Championship, FromRow},
+    entity::{
+        Championship, ChampionshipInvite, ChampionshipJoinCode, ChampionshipJoinRequest,
+        ChampionshipRole, FromRow,
+    },
     error::{AppError, AppResult},
 };
 
@@ -154,6 +157,176 @@ impl ChampionshipRepository {
         Ok(users)
     }
 
+    pub async fn role(&self, id: &i32, user_id: &i32) -> AppResult<Option<ChampionshipRole>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let championship_role_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT role FROM user_championships
+                        WHERE championship_id = $1 AND user_id = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&championship_role_stmt, &[id, user_id])
+                .await?
+        };
+
+        let role = row.map(|row| row.try_get("role")).transpose()?;
+
+        Ok(role)
+    }
+
+    pub async fn invite(&self, invite_id: &i32) -> AppResult<Option<ChampionshipInvite>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_invite_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_invites
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_invite_stmt, &[invite_id]).await?
+        };
+
+        let invite = row
+            .map(|row| ChampionshipInvite::from_row(&row))
+            .transpose()?;
+
+        Ok(invite)
+    }
+
+    // Only pending invites, expired ones are ignored
+    pub async fn invites(&self, id: &i32) -> AppResult<Vec<ChampionshipInvite>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let championship_invites_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_invites
+                        WHERE championship_id = $1 AND expires_at > CURRENT_TIMESTAMP
+                    "#,
+                )
+                .await?;
+
+            conn.query(&championship_invites_stmt, &[id]).await?
+        };
+
+        let invites = rows
+            .iter()
+            .map(ChampionshipInvite::from_row)
+            .collect::<Result<Vec<ChampionshipInvite>, AppError>>()?;
+
+        Ok(invites)
+    }
+
+    pub async fn join_code(&self, code: &str) -> AppResult<Option<ChampionshipJoinCode>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_join_code_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_join_codes
+                        WHERE code = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_join_code_stmt, &[&code]).await?
+        };
+
+        let join_code = row
+            .map(|row| ChampionshipJoinCode::from_row(&row))
+            .transpose()?;
+
+        Ok(join_code)
+    }
+
+    pub async fn join_codes(&self, id: &i32) -> AppResult<Vec<ChampionshipJoinCode>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let championship_join_codes_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_join_codes
+                        WHERE championship_id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query(&championship_join_codes_stmt, &[id]).await?
+        };
+
+        let join_codes = rows
+            .iter()
+            .map(ChampionshipJoinCode::from_row)
+            .collect::<Result<Vec<ChampionshipJoinCode>, AppError>>()?;
+
+        Ok(join_codes)
+    }
+
+    pub async fn join_request(
+        &self,
+        id: &i32,
+        user_id: &i32,
+    ) -> AppResult<Option<ChampionshipJoinRequest>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_join_request_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_join_requests
+                        WHERE championship_id = $1 AND user_id = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_join_request_stmt, &[id, user_id])
+                .await?
+        };
+
+        let join_request = row
+            .map(|row| ChampionshipJoinRequest::from_row(&row))
+            .transpose()?;
+
+        Ok(join_request)
+    }
+
+    pub async fn join_requests(&self, id: &i32) -> AppResult<Vec<ChampionshipJoinRequest>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let championship_join_requests_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_join_requests
+                        WHERE championship_id = $1
+                        ORDER BY created_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&championship_join_requests_stmt, &[id]).await?
+        };
+
+        let join_requests = rows
+            .iter()
+            .map(ChampionshipJoinRequest::from_row)
+            .collect::<Result<Vec<ChampionshipJoinRequest>, AppError>>()?;
+
+        Ok(join_requests)
+    }
+
     pub async fn championship_len(&self, user_id: &i32) -> AppResult<usize> {
         let rows = {
             let conn = self.database.pg.get().await?;
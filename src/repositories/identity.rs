@@ -0,0 +1,60 @@
+use crate::{
+    config::Database,
+    entity::{FromRow, Identity, Provider},
+    error::AppResult,
+};
+
+#[derive(Clone)]
+pub struct IdentityRepository {
+    database: Database,
+}
+
+impl IdentityRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn find(&self, provider: &Provider, subject: &str) -> AppResult<Option<Identity>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let identity_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT provider, user_id, email, created_at
+                        FROM user_identities
+                        WHERE provider = $1 AND subject = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&identity_stmt, &[provider, &subject])
+                .await?
+        };
+
+        row.map(|row| Identity::from_row(&row)).transpose()
+    }
+
+    pub async fn user_identities(&self, user_id: &i32) -> AppResult<Vec<Identity>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let identities_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT provider, user_id, email, created_at
+                        FROM user_identities
+                        WHERE user_id = $1
+                        ORDER BY created_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&identities_stmt, &[user_id]).await?
+        };
+
+        rows.iter().map(Identity::from_row).collect()
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    config::Database,
+    entity::{FromRow, LapChart, SessionLap, TyreStint},
+    error::{AppError, AppResult},
+};
+use postgres_types::ToSql;
+
+#[derive(Clone)]
+pub struct LapRepository {
+    database: Database,
+}
+
+impl LapRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn lap_chart(&self, championship_id: &i32, session_uid: &i64) -> AppResult<LapChart> {
+        let (lap_rows, stint_rows) = {
+            let conn = self.database.pg.get().await?;
+
+            let (laps_stmt, stints_stmt) = tokio::try_join!(
+                conn.prepare_cached(
+                    r#"
+                        SELECT * FROM session_laps
+                        WHERE championship_id = $1 AND session_uid = $2
+                        ORDER BY car_idx, lap_num
+                    "#,
+                ),
+                conn.prepare_cached(
+                    r#"
+                        SELECT * FROM session_stints
+                        WHERE championship_id = $1 AND session_uid = $2
+                        ORDER BY car_idx, stint
+                    "#,
+                )
+            )?;
+
+            let bindings: [&(dyn ToSql + Sync); 2] = [championship_id, session_uid];
+
+            tokio::try_join!(
+                conn.query(&laps_stmt, &bindings),
+                conn.query(&stints_stmt, &bindings)
+            )?
+        };
+
+        let laps = lap_rows
+            .iter()
+            .map(SessionLap::from_row)
+            .collect::<Result<Vec<SessionLap>, AppError>>()?;
+
+        let stints = stint_rows
+            .iter()
+            .map(TyreStint::from_row)
+            .collect::<Result<Vec<TyreStint>, AppError>>()?;
+
+        Ok(LapChart::new(laps, stints))
+    }
+}
@@ -1,11 +1,31 @@
+mod api_key;
 mod championship;
 mod f123;
-mod google;
+mod identity;
+mod lap;
+mod oauth;
+mod record;
+mod result;
+mod round;
 mod server;
+mod stats;
+mod stewarding;
+mod team;
+mod two_factor;
 mod user;
 
+pub(crate) use api_key::*;
 pub(crate) use championship::*;
 pub(crate) use f123::*;
-pub(crate) use google::*;
+pub(crate) use identity::*;
+pub(crate) use lap::*;
+pub(crate) use oauth::*;
+pub(crate) use record::*;
+pub(crate) use result::*;
+pub(crate) use round::*;
 pub(crate) use server::*;
+pub(crate) use stats::*;
+pub(crate) use stewarding::*;
+pub(crate) use team::*;
+pub(crate) use two_factor::*;
 pub(crate) use user::*;
@@ -0,0 +1,73 @@
+use crate::{
+    dtos::{ExternalAccount, OAuthState},
+    entity::Provider,
+    error::{AppResult, OAuthError},
+};
+use ahash::AHashMap;
+use async_trait::async_trait;
+use dotenvy::var;
+use std::{collections::HashMap, sync::Arc};
+use tracing::info;
+
+mod client;
+mod steam;
+
+use client::OAuthClient;
+use steam::SteamOpenId;
+
+// Every provider sends the user back to the callback with the state it was given
+#[async_trait]
+pub trait OAuthProvider: Send + Sync {
+    fn authorize_url(&self, state: &str, oauth_state: &OAuthState) -> AppResult<String>;
+    async fn account(
+        &self,
+        query: &HashMap<String, String>,
+        oauth_state: &OAuthState,
+    ) -> AppResult<ExternalAccount>;
+}
+
+// Providers without credentials in the environment are disabled
+#[derive(Clone)]
+pub struct OAuthRepository {
+    providers: Arc<AHashMap<Provider, Arc<dyn OAuthProvider>>>,
+}
+
+impl OAuthRepository {
+    pub fn new() -> Self {
+        let reqwest_client = reqwest::Client::new();
+        let mut providers: AHashMap<Provider, Arc<dyn OAuthProvider>> = AHashMap::new();
+
+        for provider in [Provider::Google, Provider::Discord] {
+            if let Some(client) = OAuthClient::from_env(provider, &reqwest_client) {
+                providers.insert(provider, Arc::new(client));
+            }
+        }
+
+        if let Some(steam) = SteamOpenId::from_env(&reqwest_client) {
+            providers.insert(Provider::Steam, Arc::new(steam));
+        }
+
+        info!("External auth providers enabled: {}", providers.len());
+
+        Self {
+            providers: Arc::new(providers),
+        }
+    }
+
+    pub fn provider(&self, provider: &Provider) -> AppResult<&dyn OAuthProvider> {
+        let provider = self
+            .providers
+            .get(provider)
+            .ok_or(OAuthError::NotConfigured)?;
+
+        Ok(provider.as_ref())
+    }
+}
+
+// Credentials of the provider are read as {PROVIDER}_CLIENT_ID and so on
+#[inline(always)]
+fn provider_var(provider: &Provider, name: &str) -> Option<String> {
+    var(format!("{}_{name}", provider.name().to_uppercase()))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
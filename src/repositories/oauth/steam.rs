@@ -0,0 +1,171 @@
+use super::{provider_var, OAuthProvider};
+use crate::{
+    config::constants::*,
+    dtos::{ExternalAccount, OAuthState, SteamSummariesResponse},
+    entity::Provider,
+    error::{AppResult, OAuthError},
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use std::collections::HashMap;
+
+const OPENID_NS: &str = "http://specs.openid.net/auth/2.0";
+const OPENID_IDENTIFIER_SELECT: &str = "http://specs.openid.net/auth/2.0/identifier_select";
+
+// Steam only speaks OpenID 2.0, the assertion is verified by sending it back to Steam.
+// It never shares the email, so Steam accounts can only be linked to existing users
+pub(super) struct SteamOpenId {
+    api_key: String,
+    redirect_uri: String,
+    reqwest_client: reqwest::Client,
+}
+
+impl SteamOpenId {
+    pub(super) fn from_env(reqwest_client: &reqwest::Client) -> Option<Self> {
+        Some(Self {
+            api_key: provider_var(&Provider::Steam, "API_KEY")?,
+            redirect_uri: provider_var(&Provider::Steam, "REDIRECT_URI")?,
+            reqwest_client: reqwest_client.clone(),
+        })
+    }
+
+    // The state travels inside the return url, Steam adds the assertion to it
+    fn return_to(&self, state: &str) -> AppResult<Url> {
+        Url::parse_with_params(&self.redirect_uri, &[("state", state)])
+            .map_err(|_| OAuthError::NotConfigured.into())
+    }
+
+    async fn verify_assertion(&self, query: &HashMap<String, String>) -> AppResult<()> {
+        let mut params = query
+            .iter()
+            .filter(|(key, _)| key.starts_with("openid."))
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+
+        params.retain(|(key, _)| *key != "openid.mode");
+        params.push(("openid.mode", "check_authentication"));
+
+        let response = self
+            .reqwest_client
+            .post(STEAM_OPENID_URL)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        if !response.lines().any(|line| line.trim() == "is_valid:true") {
+            Err(OAuthError::Rejected)?
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OAuthProvider for SteamOpenId {
+    fn authorize_url(&self, state: &str, _: &OAuthState) -> AppResult<String> {
+        let return_to = self.return_to(state)?;
+
+        let url = Url::parse_with_params(
+            STEAM_OPENID_URL,
+            &[
+                ("openid.ns", OPENID_NS),
+                ("openid.mode", "checkid_setup"),
+                ("openid.return_to", return_to.as_str()),
+                ("openid.realm", &return_to.origin().ascii_serialization()),
+                ("openid.identity", OPENID_IDENTIFIER_SELECT),
+                ("openid.claimed_id", OPENID_IDENTIFIER_SELECT),
+            ],
+        )
+        .map_err(|_| OAuthError::NotConfigured)?;
+
+        Ok(url.into())
+    }
+
+    async fn account(
+        &self,
+        query: &HashMap<String, String>,
+        _: &OAuthState,
+    ) -> AppResult<ExternalAccount> {
+        let param = |name: &str| query.get(name).map(String::as_str);
+
+        let Some(state) = param("state") else {
+            Err(OAuthError::InvalidState)?
+        };
+
+        // The assertion must be for this callback and come from Steam
+        if param("openid.mode") != Some("id_res")
+            || param("openid.op_endpoint") != Some(STEAM_OPENID_URL)
+            || param("openid.return_to") != Some(self.return_to(state)?.as_str())
+        {
+            Err(OAuthError::Rejected)?
+        }
+
+        let Some(steam_id) = steam_id(param("openid.claimed_id")) else {
+            Err(OAuthError::Rejected)?
+        };
+
+        self.verify_assertion(query).await?;
+
+        let summaries: SteamSummariesResponse = self
+            .reqwest_client
+            .get(STEAM_PLAYER_SUMMARIES)
+            .query(&[("key", self.api_key.as_str()), ("steamids", steam_id)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let Some(player) = summaries
+            .response
+            .players
+            .into_iter()
+            .find(|player| player.steamid == steam_id)
+        else {
+            Err(OAuthError::Rejected)?
+        };
+
+        Ok(ExternalAccount {
+            provider: Provider::Steam,
+            subject: player.steamid,
+            email: None,
+            email_verified: false,
+            username: player.personaname,
+            avatar: player.avatarfull,
+        })
+    }
+}
+
+// Claimed ids are https://steamcommunity.com/openid/id/<steam id 64>
+fn steam_id(claimed_id: Option<&str>) -> Option<&str> {
+    let steam_id = claimed_id?.strip_prefix(STEAM_CLAIMED_ID_PREFIX)?;
+
+    (!steam_id.is_empty() && steam_id.bytes().all(|byte| byte.is_ascii_digit())).then_some(steam_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::steam_id;
+
+    #[test]
+    fn test_steam_id() {
+        assert_eq!(
+            steam_id(Some(
+                "https://steamcommunity.com/openid/id/76561197960287930"
+            )),
+            Some("76561197960287930")
+        );
+        assert_eq!(
+            steam_id(Some("https://steamcommunity.com/openid/id/7656/../1")),
+            None
+        );
+        assert_eq!(
+            steam_id(Some("https://evil.example/openid/id/76561197960287930")),
+            None
+        );
+        assert_eq!(steam_id(None), None);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    config::Database,
+    entity::{Category, FromRow, TrackRecord},
+    error::{AppError, AppResult},
+};
+
+const LEADERBOARD_LIMIT: i64 = 50;
+
+#[derive(Clone)]
+pub struct RecordRepository {
+    database: Database,
+}
+
+impl RecordRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    // Fastest lap of every track in the category and season
+    pub async fn track_records(
+        &self,
+        category: &Category,
+        season: &i16,
+    ) -> AppResult<Vec<TrackRecord>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let track_records_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT DISTINCT ON (track_id) * FROM track_records
+                        WHERE category = $1 AND season = $2
+                        ORDER BY track_id, lap_time_in_ms, set_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&track_records_stmt, &[category, season]).await?
+        };
+
+        let records = rows
+            .iter()
+            .map(TrackRecord::from_row)
+            .collect::<Result<Vec<TrackRecord>, AppError>>()?;
+
+        Ok(records)
+    }
+
+    // Personal bests of the track ordered from the fastest, the first one is the track record
+    pub async fn leaderboard(
+        &self,
+        track_id: &i16,
+        category: &Category,
+        season: &i16,
+    ) -> AppResult<Vec<TrackRecord>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let leaderboard_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM track_records
+                        WHERE track_id = $1 AND category = $2 AND season = $3
+                        ORDER BY lap_time_in_ms, set_at
+                        LIMIT $4
+                    "#,
+                )
+                .await?;
+
+            conn.query(
+                &leaderboard_stmt,
+                &[track_id, category, season, &LEADERBOARD_LIMIT],
+            )
+            .await?
+        };
+
+        let records = rows
+            .iter()
+            .map(TrackRecord::from_row)
+            .collect::<Result<Vec<TrackRecord>, AppError>>()?;
+
+        Ok(records)
+    }
+
+    pub async fn personal_bests(&self, user_id: &i32) -> AppResult<Vec<TrackRecord>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let personal_bests_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM track_records
+                        WHERE user_id = $1
+                        ORDER BY season DESC, category, track_id
+                    "#,
+                )
+                .await?;
+
+            conn.query(&personal_bests_stmt, &[user_id]).await?
+        };
+
+        let records = rows
+            .iter()
+            .map(TrackRecord::from_row)
+            .collect::<Result<Vec<TrackRecord>, AppError>>()?;
+
+        Ok(records)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    config::Database,
+    entity::{ChampionshipSession, ClassifiedResult, FromRow, ResultAdjustment, SessionResult},
+    error::{AppError, AppResult},
+};
+
+#[derive(Clone)]
+pub struct ResultRepository {
+    database: Database,
+}
+
+impl ResultRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn session(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+    ) -> AppResult<Option<ChampionshipSession>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let session_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_sessions
+                        WHERE championship_id = $1 AND session_uid = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&session_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let session = row
+            .map(|row| ChampionshipSession::from_row(&row))
+            .transpose()?;
+
+        Ok(session)
+    }
+
+    pub async fn sessions(&self, championship_id: &i32) -> AppResult<Vec<ChampionshipSession>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let sessions_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_sessions
+                        WHERE championship_id = $1
+                        ORDER BY created_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&sessions_stmt, &[championship_id]).await?
+        };
+
+        let sessions = rows
+            .iter()
+            .map(ChampionshipSession::from_row)
+            .collect::<Result<Vec<ChampionshipSession>, AppError>>()?;
+
+        Ok(sessions)
+    }
+
+    // Classification of the session ordered by the adjusted position
+    pub async fn results(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+    ) -> AppResult<Vec<SessionResult>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let results_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM session_results
+                        WHERE championship_id = $1 AND session_uid = $2
+                        ORDER BY adjusted_position
+                    "#,
+                )
+                .await?;
+
+            conn.query(&results_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let results = rows
+            .iter()
+            .map(SessionResult::from_row)
+            .collect::<Result<Vec<SessionResult>, AppError>>()?;
+
+        Ok(results)
+    }
+
+    // Same as `results` but every driver is matched with the team of the line-up
+    // active when the session was held, so transfers don't move past results
+    pub async fn classification(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+    ) -> AppResult<Vec<ClassifiedResult>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let classification_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT r.*, td.team_id FROM session_results r
+                        JOIN championship_sessions s
+                            ON s.championship_id = r.championship_id AND s.session_uid = r.session_uid
+                        LEFT JOIN (
+                            team_drivers td JOIN championship_teams t ON t.id = td.team_id
+                        ) ON t.championship_id = r.championship_id AND td.user_id = r.user_id
+                            AND td.joined_at <= s.created_at
+                            AND (td.left_at IS NULL OR td.left_at > s.created_at)
+                        WHERE r.championship_id = $1 AND r.session_uid = $2
+                        ORDER BY r.adjusted_position
+                    "#,
+                )
+                .await?;
+
+            conn.query(&classification_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let results = rows
+            .iter()
+            .map(ClassifiedResult::from_row)
+            .collect::<Result<Vec<ClassifiedResult>, AppError>>()?;
+
+        Ok(results)
+    }
+
+    pub async fn adjustments(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+    ) -> AppResult<Vec<ResultAdjustment>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let adjustments_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM result_adjustments
+                        WHERE championship_id = $1 AND session_uid = $2
+                        ORDER BY id
+                    "#,
+                )
+                .await?;
+
+            conn.query(&adjustments_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let adjustments = rows
+            .iter()
+            .map(ResultAdjustment::from_row)
+            .collect::<Result<Vec<ResultAdjustment>, AppError>>()?;
+
+        Ok(adjustments)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    config::Database,
+    entity::{FromRow, Round},
+    error::{AppError, AppResult},
+};
+
+#[derive(Clone)]
+pub struct RoundRepository {
+    database: Database,
+}
+
+impl RoundRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn find(&self, id: &i32) -> AppResult<Option<Round>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_round_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_rounds
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_round_stmt, &[id]).await?
+        };
+
+        let round = row.map(|row| Round::from_row(&row)).transpose()?;
+
+        Ok(round)
+    }
+
+    pub async fn find_by_num(
+        &self,
+        championship_id: &i32,
+        round_num: &i16,
+    ) -> AppResult<Option<Round>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_by_num_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_rounds
+                        WHERE championship_id = $1 AND round_num = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_by_num_stmt, &[championship_id, round_num])
+                .await?
+        };
+
+        let round = row.map(|row| Round::from_row(&row)).transpose()?;
+
+        Ok(round)
+    }
+
+    pub async fn find_all(&self, championship_id: &i32) -> AppResult<Vec<Round>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let find_all_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_rounds
+                        WHERE championship_id = $1
+                        ORDER BY round_num
+                    "#,
+                )
+                .await?;
+
+            conn.query(&find_all_stmt, &[championship_id]).await?
+        };
+
+        let rounds = rows
+            .iter()
+            .map(Round::from_row)
+            .collect::<Result<Vec<Round>, AppError>>()?;
+
+        Ok(rounds)
+    }
+}
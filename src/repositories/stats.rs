@@ -0,0 +1,108 @@
+use crate::{
+    cache::{EntityCache, RedisCache},
+    config::Database,
+    entity::{DriverStats, RACE_SESSIONS},
+    error::AppResult,
+};
+use postgres_types::ToSql;
+
+#[derive(Clone)]
+pub struct StatsRepository {
+    database: Database,
+    cache: RedisCache,
+}
+
+impl StatsRepository {
+    pub fn new(db_conn: &Database, cache: &RedisCache) -> Self {
+        Self {
+            database: db_conn.clone(),
+            cache: cache.clone(),
+        }
+    }
+
+    // Only race sessions count, positions are the adjusted ones and a fastest lap is the best
+    // lap of the session among all the cars. Result status 4, 6 and 7 are the ones the game
+    // uses for cars that didn't finish, weren't classified or retired. The totals are computed
+    // twice, with every championship and with the public ones only
+    pub async fn driver_stats(&self, user_id: &i32) -> AppResult<DriverStats> {
+        if let Some(stats) = self.cache.stats.get(user_id).await? {
+            return Ok(stats);
+        }
+
+        let (totals, public_totals, championships) = {
+            let conn = self.database.pg.get().await?;
+
+            let (totals_stmt, championships_stmt) = tokio::try_join!(
+                conn.prepare_cached(
+                    r#"
+                        WITH races AS (
+                            SELECT r.*, MIN(NULLIF(r.best_lap_time_in_ms, 0)) OVER (
+                                PARTITION BY r.championship_id, r.session_uid
+                            ) AS session_best_lap
+                            FROM session_results r
+                            JOIN championship_sessions s
+                                ON s.championship_id = r.championship_id AND s.session_uid = r.session_uid
+                            JOIN championship c ON c.id = r.championship_id
+                            WHERE s.session_type BETWEEN $2 AND $3 AND ($4 OR c.visibility = 'Public')
+                            AND (r.championship_id, r.session_uid) IN (
+                                SELECT championship_id, session_uid FROM session_results WHERE user_id = $1
+                            )
+                        )
+                        SELECT
+                            COUNT(*) FILTER (WHERE result_status > 1) AS starts,
+                            COUNT(*) FILTER (WHERE adjusted_position = 1 AND NOT disqualified) AS wins,
+                            COUNT(*) FILTER (WHERE adjusted_position <= 3 AND NOT disqualified) AS podiums,
+                            COUNT(*) FILTER (WHERE grid_position = 1) AS poles,
+                            COUNT(*) FILTER (WHERE best_lap_time_in_ms = session_best_lap) AS fastest_laps,
+                            COUNT(*) FILTER (WHERE result_status IN (4, 6, 7)) AS dnfs,
+                            (AVG(adjusted_position) FILTER (WHERE result_status = 3 AND NOT disqualified))::FLOAT8 AS average_finish,
+                            COALESCE(SUM(num_penalties), 0)::INT8 AS penalties,
+                            (
+                                SELECT COUNT(*) FROM championship_penalties p
+                                JOIN championship c ON c.id = p.championship_id
+                                WHERE p.user_id = $1 AND ($4 OR c.visibility = 'Public')
+                            ) AS steward_penalties
+                        FROM races
+                        WHERE user_id = $1
+                    "#,
+                ),
+                conn.prepare_cached(
+                    r#"
+                        SELECT c.id AS championship_id, c.name, c.visibility = 'Public' AS public,
+                            (COALESCE(SUM(r.adjusted_points), 0) - COALESCE((
+                                SELECT SUM(p.value) FROM championship_penalties p
+                                WHERE p.championship_id = c.id AND p.user_id = $1 AND p.sanction = 'PointsDeduction'
+                            ), 0))::INT8 AS points
+                        FROM session_results r
+                        JOIN championship_sessions s
+                            ON s.championship_id = r.championship_id AND s.session_uid = r.session_uid
+                        JOIN championship c ON c.id = r.championship_id
+                        WHERE r.user_id = $1 AND s.session_type BETWEEN $2 AND $3
+                        GROUP BY c.id
+                        ORDER BY c.id
+                    "#,
+                )
+            )?;
+
+            let bindings: [&(dyn ToSql + Sync); 3] =
+                [user_id, RACE_SESSIONS.start(), RACE_SESSIONS.end()];
+
+            let totals_bindings: [&(dyn ToSql + Sync); 4] =
+                [user_id, RACE_SESSIONS.start(), RACE_SESSIONS.end(), &true];
+
+            let public_totals_bindings: [&(dyn ToSql + Sync); 4] =
+                [user_id, RACE_SESSIONS.start(), RACE_SESSIONS.end(), &false];
+
+            tokio::try_join!(
+                conn.query_one(&totals_stmt, &totals_bindings),
+                conn.query_one(&totals_stmt, &public_totals_bindings),
+                conn.query(&championships_stmt, &bindings)
+            )?
+        };
+
+        let stats = DriverStats::from_rows(*user_id, &totals, &public_totals, &championships)?;
+        self.cache.stats.set(&stats).await?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    config::Database,
+    entity::{ChampionshipPenalty, FromRow, GridPenalty, Protest, SessionIncident, RACE_SESSIONS},
+    error::{AppError, AppResult},
+};
+
+#[derive(Clone)]
+pub struct StewardingRepository {
+    database: Database,
+}
+
+impl StewardingRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    // Incidents of the championship, only the ones of a session if it's set
+    pub async fn incidents(
+        &self,
+        championship_id: &i32,
+        session_uid: &Option<i64>,
+    ) -> AppResult<Vec<SessionIncident>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let incidents_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM session_incidents
+                        WHERE championship_id = $1
+                        AND ($2::BIGINT IS NULL OR session_uid = $2)
+                        ORDER BY created_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&incidents_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let incidents = rows
+            .iter()
+            .map(SessionIncident::from_row)
+            .collect::<Result<Vec<SessionIncident>, AppError>>()?;
+
+        Ok(incidents)
+    }
+
+    pub async fn find_incident(&self, id: &i32) -> AppResult<Option<SessionIncident>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_incident_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM session_incidents
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_incident_stmt, &[id]).await?
+        };
+
+        let incident = row.map(|row| SessionIncident::from_row(&row)).transpose()?;
+
+        Ok(incident)
+    }
+
+    pub async fn protests(&self, championship_id: &i32) -> AppResult<Vec<Protest>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let protests_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_protests
+                        WHERE championship_id = $1
+                        ORDER BY created_at DESC
+                    "#,
+                )
+                .await?;
+
+            conn.query(&protests_stmt, &[championship_id]).await?
+        };
+
+        let protests = rows
+            .iter()
+            .map(Protest::from_row)
+            .collect::<Result<Vec<Protest>, AppError>>()?;
+
+        Ok(protests)
+    }
+
+    pub async fn find_protest(&self, id: &i32) -> AppResult<Option<Protest>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_protest_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_protests
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_protest_stmt, &[id]).await?
+        };
+
+        let protest = row.map(|row| Protest::from_row(&row)).transpose()?;
+
+        Ok(protest)
+    }
+
+    pub async fn penalties(
+        &self,
+        championship_id: &i32,
+        session_uid: &Option<i64>,
+    ) -> AppResult<Vec<ChampionshipPenalty>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let penalties_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_penalties
+                        WHERE championship_id = $1
+                        AND ($2::BIGINT IS NULL OR session_uid = $2)
+                        ORDER BY created_at
+                    "#,
+                )
+                .await?;
+
+            conn.query(&penalties_stmt, &[championship_id, session_uid])
+                .await?
+        };
+
+        let penalties = rows
+            .iter()
+            .map(ChampionshipPenalty::from_row)
+            .collect::<Result<Vec<ChampionshipPenalty>, AppError>>()?;
+
+        Ok(penalties)
+    }
+
+    // Grid drops are served in the first race held after the session they were given in
+    pub async fn grid_penalties(
+        &self,
+        championship_id: &i32,
+        race_session_uid: &i64,
+    ) -> AppResult<Vec<GridPenalty>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let grid_penalties_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT p.user_id, SUM(p.value)::INT2 AS places FROM championship_penalties p
+                        JOIN championship_sessions s
+                            ON s.championship_id = p.championship_id AND s.session_uid = p.session_uid
+                        JOIN championship_sessions race
+                            ON race.championship_id = p.championship_id AND race.session_uid = $2
+                        WHERE p.championship_id = $1 AND p.sanction = 'GridDrop'
+                        AND s.created_at < race.created_at
+                        AND NOT EXISTS (
+                            SELECT 1 FROM championship_sessions o
+                            WHERE o.championship_id = $1 AND o.session_type BETWEEN $3 AND $4
+                            AND o.created_at > s.created_at AND o.created_at < race.created_at
+                        )
+                        GROUP BY p.user_id
+                        ORDER BY MIN(p.id)
+                    "#,
+                )
+                .await?;
+
+            conn.query(
+                &grid_penalties_stmt,
+                &[
+                    championship_id,
+                    race_session_uid,
+                    RACE_SESSIONS.start(),
+                    RACE_SESSIONS.end(),
+                ],
+            )
+            .await?
+        };
+
+        let penalties = rows
+            .iter()
+            .map(GridPenalty::from_row)
+            .collect::<Result<Vec<GridPenalty>, AppError>>()?;
+
+        Ok(penalties)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    config::Database,
+    entity::{ConstructorStanding, FromRow, Team, TeamDriver, RACE_SESSIONS},
+    error::{AppError, AppResult},
+};
+use chrono::{DateTime, Utc};
+
+#[derive(Clone)]
+pub struct TeamRepository {
+    database: Database,
+}
+
+impl TeamRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn find(&self, id: &i32) -> AppResult<Option<Team>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_team_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_teams
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_team_stmt, &[id]).await?
+        };
+
+        let team = row.map(|row| Team::from_row(&row)).transpose()?;
+
+        Ok(team)
+    }
+
+    pub async fn find_by_name(&self, championship_id: &i32, name: &str) -> AppResult<Option<Team>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let find_by_name_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_teams
+                        WHERE championship_id = $1 AND name = $2
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&find_by_name_stmt, &[championship_id, &name])
+                .await?
+        };
+
+        let team = row.map(|row| Team::from_row(&row)).transpose()?;
+
+        Ok(team)
+    }
+
+    pub async fn find_all(&self, championship_id: &i32) -> AppResult<Vec<Team>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let find_all_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT * FROM championship_teams
+                        WHERE championship_id = $1
+                        ORDER BY name
+                    "#,
+                )
+                .await?;
+
+            conn.query(&find_all_stmt, &[championship_id]).await?
+        };
+
+        let teams = rows
+            .iter()
+            .map(Team::from_row)
+            .collect::<Result<Vec<Team>, AppError>>()?;
+
+        Ok(teams)
+    }
+
+    // Drivers of every team at the given moment, used to assign results of past rounds
+    pub async fn lineup(
+        &self,
+        championship_id: &i32,
+        at: &DateTime<Utc>,
+    ) -> AppResult<Vec<TeamDriver>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let lineup_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT td.* FROM team_drivers td
+                        JOIN championship_teams t ON t.id = td.team_id
+                        WHERE t.championship_id = $1
+                        AND td.joined_at <= $2
+                        AND (td.left_at IS NULL OR td.left_at > $2)
+                    "#,
+                )
+                .await?;
+
+            conn.query(&lineup_stmt, &[championship_id, at]).await?
+        };
+
+        let drivers = rows
+            .iter()
+            .map(TeamDriver::from_row)
+            .collect::<Result<Vec<TeamDriver>, AppError>>()?;
+
+        Ok(drivers)
+    }
+
+    // Team the user is currently driving for in the championship
+    pub async fn driver_team(
+        &self,
+        championship_id: &i32,
+        user_id: &i32,
+    ) -> AppResult<Option<TeamDriver>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let driver_team_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT td.* FROM team_drivers td
+                        JOIN championship_teams t ON t.id = td.team_id
+                        WHERE t.championship_id = $1 AND td.user_id = $2 AND td.left_at IS NULL
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&driver_team_stmt, &[championship_id, user_id])
+                .await?
+        };
+
+        let driver = row.map(|row| TeamDriver::from_row(&row)).transpose()?;
+
+        Ok(driver)
+    }
+
+    // Race results and points deductions count for the team the driver was in when each
+    // session was held, drivers transferred mid-season keep their points in the previous team
+    pub async fn constructor_standings(
+        &self,
+        championship_id: &i32,
+    ) -> AppResult<Vec<ConstructorStanding>> {
+        let rows = {
+            let conn = self.database.pg.get().await?;
+
+            let standings_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT t.id AS team_id, t.name, t.primary_color,
+                            (COALESCE(SUM(r.adjusted_points), 0) - COALESCE((
+                                SELECT SUM(p.value) FROM championship_penalties p
+                                JOIN championship_sessions ps
+                                    ON ps.championship_id = p.championship_id AND ps.session_uid = p.session_uid
+                                JOIN team_drivers pd
+                                    ON pd.team_id = t.id AND pd.user_id = p.user_id
+                                    AND pd.joined_at <= ps.created_at
+                                    AND (pd.left_at IS NULL OR pd.left_at > ps.created_at)
+                                WHERE p.championship_id = t.championship_id AND p.sanction = 'PointsDeduction'
+                            ), 0))::INT8 AS points
+                        FROM championship_teams t
+                        LEFT JOIN team_drivers td ON td.team_id = t.id
+                        LEFT JOIN championship_sessions s
+                            ON s.championship_id = t.championship_id AND s.session_type BETWEEN $2 AND $3
+                            AND td.joined_at <= s.created_at
+                            AND (td.left_at IS NULL OR td.left_at > s.created_at)
+                        LEFT JOIN session_results r
+                            ON r.championship_id = s.championship_id AND r.session_uid = s.session_uid
+                            AND r.user_id = td.user_id
+                        WHERE t.championship_id = $1
+                        GROUP BY t.id
+                        ORDER BY points DESC, t.name
+                    "#,
+                )
+                .await?;
+
+            conn.query(
+                &standings_stmt,
+                &[championship_id, RACE_SESSIONS.start(), RACE_SESSIONS.end()],
+            )
+            .await?
+        };
+
+        let standings = rows
+            .iter()
+            .map(ConstructorStanding::from_row)
+            .collect::<Result<Vec<ConstructorStanding>, AppError>>()?;
+
+        Ok(standings)
+    }
+}
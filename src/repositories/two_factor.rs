@@ -0,0 +1,45 @@
+use crate::{
+    config::Database,
+    entity::{FromRow, TwoFactor},
+    error::AppResult,
+};
+
+#[derive(Clone)]
+pub struct TwoFactorRepository {
+    database: Database,
+}
+
+impl TwoFactorRepository {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            database: db_conn.clone(),
+        }
+    }
+
+    pub async fn find(&self, user_id: &i32) -> AppResult<Option<TwoFactor>> {
+        let row = {
+            let conn = self.database.pg.get().await?;
+
+            let two_factor_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT secret, enabled FROM user_two_factor
+                        WHERE user_id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&two_factor_stmt, &[user_id]).await?
+        };
+
+        let two_factor = row.map(|row| TwoFactor::from_row(&row)).transpose()?;
+
+        Ok(two_factor)
+    }
+
+    pub async fn enabled(&self, user_id: &i32) -> AppResult<bool> {
+        let two_factor = self.find(user_id).await?;
+
+        Ok(two_factor.is_some_and(|two_factor| two_factor.enabled))
+    }
+}
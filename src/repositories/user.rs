@@ -18,6 +18,7 @@ pub trait UserRepositoryTrait {
     async fn find(&self, id: &i32) -> AppResult<Option<User>>;
     async fn user_exists(&self, email: &str) -> AppResult<bool>;
     async fn status(&self, id: &i32) -> AppResult<Option<bool>>;
+    async fn verified(&self, id: &i32) -> AppResult<bool>;
     async fn find_by_email(&self, email: &str) -> AppResult<Option<User>>;
     fn validate_password(&self, password: &str, hash: &str) -> AppResult<bool>;
 }
@@ -111,6 +112,26 @@ impl UserRepositoryTrait for UserRepository {
         Ok(None)
     }
 
+    // Deactivated users are inactive as well, but they verified their email once
+    async fn verified(&self, id: &i32) -> AppResult<bool> {
+        let row = {
+            let conn = self.db_conn.pg.get().await?;
+
+            let user_verified_stmt = conn
+                .prepare_cached(
+                    r#"
+                        SELECT verified_at IS NOT NULL FROM users
+                        WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            conn.query_opt(&user_verified_stmt, &[id]).await?
+        };
+
+        Ok(row.is_some_and(|row| row.get(0)))
+    }
+
     async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
         if let Some(user) = self.cache.user.get_by_email(email).await? {
             return Ok(Some(user));
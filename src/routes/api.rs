@@ -1,18 +1,37 @@
 use crate::{
+    config::constants::{
+        FORGOT_PASSWORD_LIMIT, LOGIN_LIMIT, REGISTER_LIMIT, RESEND_VERIFICATION_LIMIT,
+        RESET_PASSWORD_LIMIT, TWO_FACTOR_LIMIT,
+    },
     handlers::{
         auth::{
-            callback, forgot_password, login, logout, refresh_token, register, reset_password,
-            verify_email,
+            callback, exchange_code, forgot_password, jwks, login, login_two_factor, logout,
+            oauth_login, refresh_token, register, resend_verification, reset_password,
+            verify_email, verify_email_change,
         },
         championships::{
-            add_user, all_championships, create_championship, get_championship, remove_user,
-            session_socket, socket_status, start_socket, stop_socket, update,
+            accept_invite, add_team_driver, all_championships, apply_adjustment,
+            approve_join_request, assign_driver, championship_invites, constructor_standings,
+            create_championship, create_invite, create_join_code, create_round, create_team,
+            decline_invite, delete_join_code, delete_round, delete_team, file_protest,
+            get_championship, incidents, invite_ticket, join_codes, join_requests, lap_chart,
+            live_snapshot, live_stream, penalties, protests, reject_join_request,
+            remove_team_driver, remove_user, request_join, result_adjustments, revoke_invite,
+            round_grid, rounds, rule_protest, session_results, session_socket, sessions,
+            socket_status, start_socket, stop_socket, team_lineup, teams, transfer_ownership,
+            update, update_member_role, update_round, update_team, viewer_ticket,
         },
         heartbeat,
         intelli_app::latest_release,
-        user::{update_user, user_data},
+        records::{personal_bests, track_leaderboard, track_records},
+        user::{
+            api_keys, change_email, create_api_key, disable_two_factor, enable_two_factor,
+            identities, link_identity, regenerate_recovery_codes, revoke_api_key, revoke_session,
+            revoke_sessions, sessions as user_sessions, setup_two_factor, unlink_identity,
+            update_user, user_data, user_stats,
+        },
     },
-    middlewares::Authentication,
+    middlewares::{Authentication, RateLimit},
 };
 use ntex::web;
 
@@ -20,13 +39,42 @@ use ntex::web;
 pub(crate) fn api_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
-            .route("/google/callback", web::get().to(callback))
-            .route("/register", web::post().to(register))
-            .route("/login", web::post().to(login))
+            .route("/{provider}/login", web::get().to(oauth_login))
+            .route("/{provider}/callback", web::get().to(callback))
+            .route("/exchange", web::post().to(exchange_code))
+            .service(
+                web::resource("/register")
+                    .route(web::post().to(register))
+                    .wrap(RateLimit::new(REGISTER_LIMIT)),
+            )
+            .service(
+                web::resource("/login")
+                    .route(web::post().to(login))
+                    .wrap(RateLimit::new(LOGIN_LIMIT)),
+            )
+            .service(
+                web::resource("/login/2fa")
+                    .route(web::post().to(login_two_factor))
+                    .wrap(RateLimit::new(TWO_FACTOR_LIMIT)),
+            )
             .route("/refresh", web::get().to(refresh_token))
             .route("/verify/email", web::get().to(verify_email))
-            .route("/forgot-password", web::post().to(forgot_password))
-            .route("/reset-password", web::post().to(reset_password)),
+            .route("/verify/email-change", web::get().to(verify_email_change))
+            .service(
+                web::resource("/verify/resend")
+                    .route(web::post().to(resend_verification))
+                    .wrap(RateLimit::new(RESEND_VERIFICATION_LIMIT)),
+            )
+            .service(
+                web::resource("/forgot-password")
+                    .route(web::post().to(forgot_password))
+                    .wrap(RateLimit::new(FORGOT_PASSWORD_LIMIT)),
+            )
+            .service(
+                web::resource("/reset-password")
+                    .route(web::post().to(reset_password))
+                    .wrap(RateLimit::new(RESET_PASSWORD_LIMIT)),
+            ),
     );
 
     cfg.service(
@@ -39,6 +87,39 @@ pub(crate) fn api_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/user")
             .route("", web::put().to(update_user))
             .route("/data", web::get().to(user_data))
+            .route("/email", web::put().to(change_email))
+            .route("/2fa/setup", web::post().to(setup_two_factor))
+            .route("/2fa/enable", web::post().to(enable_two_factor))
+            .route("/2fa/disable", web::post().to(disable_two_factor))
+            .route(
+                "/2fa/recovery-codes",
+                web::post().to(regenerate_recovery_codes),
+            )
+            .route("/sessions", web::get().to(user_sessions))
+            .route("/sessions", web::delete().to(revoke_sessions))
+            .route("/sessions/{session_id}", web::delete().to(revoke_session))
+            .route("/identities", web::get().to(identities))
+            .route("/identities/{provider}", web::post().to(link_identity))
+            .route("/identities/{provider}", web::delete().to(unlink_identity))
+            .route("/api-keys", web::get().to(api_keys))
+            .route("/api-keys", web::post().to(create_api_key))
+            .route("/api-keys/{key_id}", web::delete().to(revoke_api_key))
+            .route("/{id}/stats", web::get().to(user_stats))
+            .wrap(Authentication),
+    );
+
+    cfg.service(
+        web::scope("/invites")
+            .route("/accept", web::post().to(accept_invite))
+            .route("/decline", web::post().to(decline_invite))
+            .wrap(Authentication),
+    );
+
+    cfg.service(
+        web::scope("/records")
+            .route("", web::get().to(track_records))
+            .route("/tracks/{track_id}", web::get().to(track_leaderboard))
+            .route("/drivers/{id}", web::get().to(personal_bests))
             .wrap(Authentication),
     );
 
@@ -46,21 +127,106 @@ pub(crate) fn api_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/intelli-app").route("/releases/latest", web::get().to(latest_release)),
     );
 
+    // Live data can be read without a session, registered before the scope so it isn't shadowed
+    cfg.route(
+        "/championships/{id}/live/stream",
+        web::get().to(live_stream),
+    );
+
+    cfg.route(
+        "/championships/{id}/live/snapshot",
+        web::get().to(live_snapshot),
+    );
+
     cfg.service(
         web::scope("/championships")
             .route("", web::post().to(create_championship))
             .route("/all", web::get().to(all_championships))
+            .route("/join/{code}", web::post().to(request_join))
             .route("/{id}", web::get().to(get_championship))
             .route("/{id}", web::put().to(update))
-            .route("/{id}/user/add", web::put().to(add_user))
+            .route("/{id}/user/add", web::put().to(create_invite))
+            .route("/{id}/invites", web::get().to(championship_invites))
+            .route("/{id}/invites/{invite_id}", web::delete().to(revoke_invite))
+            .route("/{id}/join-codes", web::get().to(join_codes))
+            .route("/{id}/join-codes", web::post().to(create_join_code))
+            .route(
+                "/{id}/join-codes/{code}",
+                web::delete().to(delete_join_code),
+            )
+            .route("/{id}/join-requests", web::get().to(join_requests))
+            .route(
+                "/{id}/join-requests/{user_id}",
+                web::put().to(approve_join_request),
+            )
+            .route(
+                "/{id}/join-requests/{user_id}",
+                web::delete().to(reject_join_request),
+            )
             .route("/{id}/user/{user_id}", web::delete().to(remove_user))
+            .route(
+                "/{id}/user/{user_id}/role",
+                web::put().to(update_member_role),
+            )
+            .route("/{id}/owner", web::put().to(transfer_ownership))
+            .route("/{id}/teams", web::get().to(teams))
+            .route("/{id}/teams", web::post().to(create_team))
+            .route("/{id}/teams/lineup", web::get().to(team_lineup))
+            .route("/{id}/teams/{team_id}", web::put().to(update_team))
+            .route("/{id}/teams/{team_id}", web::delete().to(delete_team))
+            .route(
+                "/{id}/teams/{team_id}/drivers",
+                web::post().to(add_team_driver),
+            )
+            .route(
+                "/{id}/teams/{team_id}/drivers/{user_id}",
+                web::delete().to(remove_team_driver),
+            )
+            .route(
+                "/{id}/standings/constructors",
+                web::get().to(constructor_standings),
+            )
+            .route("/{id}/incidents", web::get().to(incidents))
+            .route("/{id}/protests", web::get().to(protests))
+            .route("/{id}/protests", web::post().to(file_protest))
+            .route("/{id}/protests/{protest_id}", web::put().to(rule_protest))
+            .route("/{id}/penalties", web::get().to(penalties))
+            .route("/{id}/rounds", web::get().to(rounds))
+            .route("/{id}/rounds", web::post().to(create_round))
+            .route("/{id}/rounds/{round_id}", web::put().to(update_round))
+            .route("/{id}/rounds/{round_id}", web::delete().to(delete_round))
+            .route("/{id}/rounds/{round_id}/grid", web::get().to(round_grid))
+            .route("/{id}/sessions", web::get().to(sessions))
+            .route(
+                "/{id}/sessions/{session_uid}/results",
+                web::get().to(session_results),
+            )
+            .route(
+                "/{id}/sessions/{session_uid}/laps",
+                web::get().to(lap_chart),
+            )
+            .route(
+                "/{id}/sessions/{session_uid}/adjustments",
+                web::get().to(result_adjustments),
+            )
+            .route(
+                "/{id}/sessions/{session_uid}/adjustments",
+                web::post().to(apply_adjustment),
+            )
+            .route(
+                "/{id}/sessions/{session_uid}/drivers",
+                web::put().to(assign_driver),
+            )
             .route("/{id}/socket/start", web::get().to(start_socket))
             .route("/{id}/socket/status", web::get().to(socket_status))
             .route("/{id}/socket/stop", web::get().to(stop_socket))
+            .route("/{id}/live/ticket", web::get().to(viewer_ticket))
+            .route("/{id}/live/invite", web::post().to(invite_ticket))
             .wrap(Authentication),
     );
 
     cfg.route("/heartbeat", web::get().to(heartbeat));
+    cfg.route("/.well-known/jwks.json", web::get().to(jwks));
 
     cfg.route(
         "/web_socket/championship/{id}",
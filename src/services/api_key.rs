@@ -0,0 +1,141 @@
+use crate::{
+    config::{constants::*, Database},
+    dtos::CreateApiKey,
+    entity::{ApiKey, FromRow},
+    error::{ApiKeyError, AppResult},
+    repositories::ApiKeyRepository,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::{distr::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+#[derive(Clone)]
+pub struct ApiKeyService {
+    db: Database,
+    api_key_repository: ApiKeyRepository,
+}
+
+impl ApiKeyService {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            db: db_conn.clone(),
+            api_key_repository: ApiKeyRepository::new(db_conn),
+        }
+    }
+
+    // Returns the saved key and the key itself, it can't be recovered later
+    pub async fn create(&self, user_id: &i32, form: &CreateApiKey) -> AppResult<(ApiKey, String)> {
+        let key = generate_key();
+        let prefix = &key[..API_KEY_PREFIX.len() + API_KEY_VISIBLE_CHARS];
+        let conn = self.db.pg.get().await?;
+
+        let create_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO api_keys (user_id, name, prefix, key_hash, scopes)
+                    SELECT $1, $2, $3, $4, $5
+                    WHERE (SELECT COUNT(*) FROM api_keys WHERE user_id = $1) < $6
+                    RETURNING id, user_id, name, prefix, scopes, last_used_at, created_at
+                "#,
+            )
+            .await?;
+
+        let Some(row) = conn
+            .query_opt(
+                &create_stmt,
+                &[
+                    user_id,
+                    &form.name,
+                    &prefix,
+                    &hash_key(&key),
+                    &form.scopes,
+                    &API_KEYS_LIMIT,
+                ],
+            )
+            .await?
+        else {
+            Err(ApiKeyError::LimitReached)?
+        };
+
+        Ok((ApiKey::from_row(&row)?, key))
+    }
+
+    pub async fn revoke(&self, user_id: &i32, key_id: &i32) -> AppResult<()> {
+        let conn = self.db.pg.get().await?;
+
+        let revoke_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM api_keys
+                    WHERE id = $1 AND user_id = $2
+                "#,
+            )
+            .await?;
+
+        if conn.execute(&revoke_stmt, &[key_id, user_id]).await? == 0 {
+            Err(ApiKeyError::NotFound)?
+        }
+
+        Ok(())
+    }
+
+    // The usage is saved at most once per interval so every request doesn't write
+    pub async fn authenticate(&self, key: &str) -> AppResult<ApiKey> {
+        if !key.starts_with(API_KEY_PREFIX) {
+            Err(ApiKeyError::InvalidKey)?
+        }
+
+        let Some(api_key) = self.api_key_repository.find_by_hash(&hash_key(key)).await? else {
+            Err(ApiKeyError::InvalidKey)?
+        };
+
+        let conn = self.db.pg.get().await?;
+
+        let usage_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP
+                    WHERE id = $1 AND (
+                        last_used_at IS NULL
+                        OR last_used_at < CURRENT_TIMESTAMP - make_interval(secs => $2)
+                    )
+                "#,
+            )
+            .await?;
+
+        conn.execute(&usage_stmt, &[&api_key.id, &API_KEY_USAGE_INTERVAL_SECS])
+            .await?;
+
+        Ok(api_key)
+    }
+}
+
+fn generate_key() -> String {
+    let key = rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(API_KEY_LENGTH)
+        .map(char::from)
+        .collect::<String>();
+
+    format!("{API_KEY_PREFIX}{key}")
+}
+
+// Keys are random enough to be stored with a plain hash
+fn hash_key(key: &str) -> String {
+    STANDARD.encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_key, hash_key};
+    use crate::config::constants::{API_KEY_LENGTH, API_KEY_PREFIX};
+
+    #[test]
+    fn test_generate_key() {
+        let key = generate_key();
+
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + API_KEY_LENGTH);
+        assert_ne!(hash_key(&key), hash_key(&generate_key()));
+    }
+}
@@ -1,12 +1,17 @@
 use crate::{
     cache::RedisCache,
-    config::Database,
-    dtos::{CreateChampionshipDto, UpdateChampionship},
+    config::{
+        constants::{INVITE_EXPIRATION, JOIN_CODE_LENGTH},
+        Database,
+    },
+    dtos::{AddUser, CreateChampionshipDto, CreateJoinCode, UpdateChampionship},
+    entity::{ChampionshipInvite, ChampionshipRole, FromRow, RACE_SESSIONS},
     error::{AppResult, ChampionshipError, CommonError, UserError},
     repositories::{ChampionshipRepository, UserRepository, UserRepositoryTrait},
 };
 use ahash::AHashSet;
 use chrono::{Duration, Utc};
+use deadpool_postgres::Transaction;
 use parking_lot::RwLock;
 use postgres_types::ToSql;
 use std::sync::Arc;
@@ -64,8 +69,8 @@ impl ChampionshipService {
 
             let relate_user_with_championship_stmt_fut = conn.prepare_cached(
                 r#"
-                    INSERT INTO user_championships (user_id, championship_id)
-                    VALUES ($1,$2)
+                    INSERT INTO user_championships (user_id, championship_id, role)
+                    VALUES ($1,$2,'Owner')
                 "#,
             );
 
@@ -105,7 +110,7 @@ impl ChampionshipService {
         user_id: &i32,
         form: &UpdateChampionship,
     ) -> AppResult<()> {
-        // Scope to check if championship exists and if user is owner
+        // Scope to check if championship exists and if user is admin
         {
             let Some(championship) = self.championship_repository.find(id).await? else {
                 Err(ChampionshipError::NotFound)?
@@ -115,9 +120,7 @@ impl ChampionshipService {
                 Err(ChampionshipError::IntervalNotReached)?
             };
 
-            if championship.owner_id != *user_id {
-                Err(ChampionshipError::NotOwner)?
-            }
+            self.authorize(id, user_id, ChampionshipRole::Admin).await?;
         }
 
         let (query, params) = {
@@ -155,6 +158,26 @@ impl ChampionshipService {
                 counter += 1;
             }
 
+            if let Some(visibility) = &form.visibility {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" visibility = ${}", counter));
+                params.push(visibility);
+                counter += 1;
+            }
+
+            if let Some(points_table) = &form.points_table {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" points_table = ${}", counter));
+                params.push(points_table);
+                counter += 1;
+            }
+
             if counter == 1 {
                 Err(CommonError::NotValidUpdate)?
             }
@@ -162,67 +185,378 @@ impl ChampionshipService {
             query.push_str(&format!(" WHERE id = ${}", counter));
             params.push(id);
 
-            // Check if owner_id is the same as user_id
-            query.push_str(&format!(" AND owner_id = ${}", counter + 1));
-            params.push(user_id);
-
             (query, params)
         };
 
-        // Scope to update championship
+        // Scope to update championship, the points of the races already held follow the new table
         {
-            let conn = self.db.pg.get().await?;
-            let update_championship_stmt = conn.prepare_cached(&query).await?;
-            conn.execute(&update_championship_stmt, &params).await?;
+            let mut conn = self.db.pg.get().await?;
+            let transaction = conn.transaction().await?;
+
+            let update_championship_stmt = transaction.prepare_cached(&query).await?;
+            transaction
+                .execute(&update_championship_stmt, &params)
+                .await?;
+
+            if form.points_table.is_some() {
+                let update_points_stmt = transaction
+                    .prepare_cached(
+                        r#"
+                            UPDATE session_results r
+                            SET adjusted_points = CASE WHEN r.disqualified THEN 0
+                                ELSE COALESCE(c.points_table[r.adjusted_position], 0) END,
+                                updated_at = CURRENT_TIMESTAMP
+                            FROM championship_sessions s, championship c
+                            WHERE s.championship_id = r.championship_id AND s.session_uid = r.session_uid
+                            AND c.id = r.championship_id AND r.championship_id = $1
+                            AND s.session_type BETWEEN $2 AND $3
+                        "#,
+                    )
+                    .await?;
+
+                transaction
+                    .execute(
+                        &update_points_stmt,
+                        &[id, RACE_SESSIONS.start(), RACE_SESSIONS.end()],
+                    )
+                    .await?;
+            }
+
+            transaction.commit().await?;
         }
 
         let users = self.championship_repository.users(id).await?;
+
+        // Points and the public career totals of the members depend on both
+        if form.points_table.is_some() || form.visibility.is_some() {
+            self.cache.stats.delete_all(&users).await?;
+        }
+
         // Todo: Check if this is working as expected
         self.cache.championship.delete_all(id, users).await?;
 
         Ok(())
     }
 
-    pub async fn add_user(&self, id: &i32, user_id: &i32, bind_user_email: &str) -> AppResult<()> {
-        // Scope to check if championship exists and if user is owner
+    // Users are only added to the championship once they accept the invite
+    pub async fn create_invite(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        form: &AddUser,
+    ) -> AppResult<ChampionshipInvite> {
+        let role = form.role.unwrap_or(ChampionshipRole::Driver);
+
+        // Scope to check if championship exists and if user can assign the role
         {
-            let Some(championship) = self.championship_repository.find(id).await? else {
+            if self.championship_repository.find(id).await?.is_none() {
                 Err(ChampionshipError::NotFound)?
             };
 
-            if championship.owner_id != *user_id {
-                Err(ChampionshipError::NotOwner)?
-            }
+            let user_role = self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+            Self::check_assignable(user_role, role)?;
         }
 
-        let new_user_id = {
-            let Some(bind_user) = self.user_repository.find_by_email(bind_user_email).await? else {
+        let invited_user_id = {
+            let Some(invited_user) = self.user_repository.find_by_email(&form.email).await? else {
                 Err(UserError::NotFound)?
             };
 
-            bind_user.id
+            invited_user.id
         };
 
+        if self
+            .championship_repository
+            .role(id, &invited_user_id)
+            .await?
+            .is_some()
+        {
+            Err(ChampionshipError::AlreadyMember)?
+        }
+
+        let row = {
+            let invite_id = fastrand::i32(800000000..899999999);
+            let expires_at = Utc::now() + Duration::seconds(INVITE_EXPIRATION);
+            let conn = self.db.pg.get().await?;
+
+            // Inviting the same user again refreshes the pending invite
+            let create_invite_stmt = conn
+                .prepare_cached(
+                    r#"
+                        INSERT INTO championship_invites (id, championship_id, user_id, invited_by, role, expires_at)
+                        VALUES ($1,$2,$3,$4,$5,$6)
+                        ON CONFLICT (championship_id, user_id)
+                        DO UPDATE SET invited_by = $4, role = $5, expires_at = $6
+                        RETURNING *
+                    "#,
+                )
+                .await?;
+
+            let bindings: [&(dyn ToSql + Sync); 6] = [
+                &invite_id,
+                id,
+                &invited_user_id,
+                user_id,
+                &role,
+                &expires_at,
+            ];
+
+            conn.query_one(&create_invite_stmt, &bindings).await?
+        };
+
+        ChampionshipInvite::from_row(&row)
+    }
+
+    pub async fn accept_invite(&self, invite_id: &i32, user_id: &i32) -> AppResult<()> {
+        let invite = self.pending_invite(invite_id, user_id).await?;
+        self.check_championship_limit(user_id, true).await?;
+
+        {
+            let mut conn = self.db.pg.get().await?;
+            let transaction = conn.transaction().await?;
+
+            let delete_invite_stmt = transaction
+                .prepare_cached(
+                    r#"
+                        DELETE FROM championship_invites WHERE id = $1
+                    "#,
+                )
+                .await?;
+
+            // The invite is consumed with the membership so it can't be accepted twice
+            if transaction
+                .execute(&delete_invite_stmt, &[invite_id])
+                .await?
+                == 0
+            {
+                Err(ChampionshipError::InviteNotFound)?
+            }
+
+            Self::add_user(&transaction, &invite.championship_id, user_id, invite.role).await?;
+            transaction.commit().await?;
+        }
+
+        self.cache.championship.delete_by_user_id(user_id).await
+    }
+
+    pub async fn decline_invite(&self, invite_id: &i32, user_id: &i32) -> AppResult<()> {
+        self.pending_invite(invite_id, user_id).await?;
+        self.delete_invite(invite_id).await
+    }
+
+    pub async fn revoke_invite(&self, id: &i32, user_id: &i32, invite_id: &i32) -> AppResult<()> {
+        self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+        let Some(invite) = self.championship_repository.invite(invite_id).await? else {
+            Err(ChampionshipError::InviteNotFound)?
+        };
+
+        if invite.championship_id != *id {
+            Err(ChampionshipError::InviteNotFound)?
+        }
+
+        self.delete_invite(invite_id).await
+    }
+
+    // Binds the user to the championship in the same transaction that consumes the invite
+    // or the join request, the user role limits how many championships it can join
+    async fn add_user(
+        transaction: &Transaction<'_>,
+        id: &i32,
+        new_user_id: &i32,
+        role: ChampionshipRole,
+    ) -> AppResult<()> {
+        let add_user_stmt = transaction
+            .prepare_cached(
+                r#"
+                    INSERT INTO user_championships (user_id, championship_id, role)
+                    VALUES ($1,$2,$3)
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .await?;
+
+        let bindings: [&(dyn ToSql + Sync); 3] = [new_user_id, id, &role];
+        transaction.execute(&add_user_stmt, &bindings).await?;
+
+        Ok(())
+    }
+
+    pub async fn create_join_code(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        form: &CreateJoinCode,
+    ) -> AppResult<String> {
+        if self.championship_repository.find(id).await?.is_none() {
+            Err(ChampionshipError::NotFound)?
+        };
+
+        self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+        let code: String = (0..JOIN_CODE_LENGTH)
+            .map(|_| fastrand::alphanumeric())
+            .collect();
+
+        let expires_at = form
+            .expires_in
+            .map(|hours| Utc::now() + Duration::hours(hours));
+
         let conn = self.db.pg.get().await?;
 
-        let add_user_stmt = conn
+        let create_join_code_stmt = conn
             .prepare_cached(
                 r#"
-                    INSERT INTO user_championships (user_id, championship_id)
-                    VALUES ($1,$2)
+                    INSERT INTO championship_join_codes (code, championship_id, created_by, max_uses, expires_at)
+                    VALUES ($1,$2,$3,$4,$5)
                 "#,
             )
             .await?;
 
-        let add_user_future = async {
-            let bindings: [&(dyn ToSql + Sync); 2] = [&new_user_id, id];
-            conn.execute(&add_user_stmt, &bindings).await?;
-            Ok(())
+        let bindings: [&(dyn ToSql + Sync); 5] = [&code, id, user_id, &form.max_uses, &expires_at];
+        conn.execute(&create_join_code_stmt, &bindings).await?;
+
+        Ok(code)
+    }
+
+    pub async fn delete_join_code(&self, id: &i32, user_id: &i32, code: &str) -> AppResult<()> {
+        self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+        let conn = self.db.pg.get().await?;
+
+        let delete_join_code_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM championship_join_codes
+                    WHERE code = $1 AND championship_id = $2
+                "#,
+            )
+            .await?;
+
+        if conn.execute(&delete_join_code_stmt, &[&code, id]).await? == 0 {
+            Err(ChampionshipError::InvalidJoinCode)?
+        }
+
+        Ok(())
+    }
+
+    // Consumes one use of the code and queues the request until an admin answers it
+    pub async fn request_join(&self, code: &str, user_id: &i32) -> AppResult<()> {
+        let Some(join_code) = self.championship_repository.join_code(code).await? else {
+            Err(ChampionshipError::InvalidJoinCode)?
         };
 
-        let delete_user_cache_future = self.cache.championship.delete_by_user_id(&new_user_id);
+        let id = join_code.championship_id;
+
+        if self
+            .championship_repository
+            .role(&id, user_id)
+            .await?
+            .is_some()
+        {
+            Err(ChampionshipError::AlreadyMember)?
+        }
+
+        if self
+            .championship_repository
+            .join_request(&id, user_id)
+            .await?
+            .is_some()
+        {
+            Err(ChampionshipError::JoinRequestExists)?
+        }
+
+        self.check_championship_limit(user_id, true).await?;
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let use_join_code_stmt_fut = transaction.prepare_cached(
+            r#"
+                UPDATE championship_join_codes SET uses = uses + 1
+                WHERE code = $1
+                AND (max_uses IS NULL OR uses < max_uses)
+                AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+            "#,
+        );
+
+        let create_join_request_stmt_fut = transaction.prepare_cached(
+            r#"
+                INSERT INTO championship_join_requests (championship_id, user_id, code)
+                VALUES ($1,$2,$3)
+            "#,
+        );
+
+        let (use_join_code_stmt, create_join_request_stmt) =
+            tokio::try_join!(use_join_code_stmt_fut, create_join_request_stmt_fut)?;
+
+        // The use is only counted if the request is stored
+        if transaction.execute(&use_join_code_stmt, &[&code]).await? == 0 {
+            Err(ChampionshipError::InvalidJoinCode)?
+        }
+
+        let bindings: [&(dyn ToSql + Sync); 3] = [&id, user_id, &code];
+        transaction
+            .execute(&create_join_request_stmt, &bindings)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub async fn approve_join_request(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        request_user_id: &i32,
+    ) -> AppResult<()> {
+        self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+        self.check_championship_limit(request_user_id, true).await?;
+
+        {
+            let mut conn = self.db.pg.get().await?;
+            let transaction = conn.transaction().await?;
+
+            let delete_join_request_stmt = transaction
+                .prepare_cached(
+                    r#"
+                        DELETE FROM championship_join_requests
+                        WHERE championship_id = $1 AND user_id = $2
+                    "#,
+                )
+                .await?;
+
+            if transaction
+                .execute(&delete_join_request_stmt, &[id, request_user_id])
+                .await?
+                == 0
+            {
+                Err(ChampionshipError::JoinRequestNotFound)?
+            }
+
+            Self::add_user(&transaction, id, request_user_id, ChampionshipRole::Driver).await?;
+            transaction.commit().await?;
+        }
+
+        self.cache
+            .championship
+            .delete_by_user_id(request_user_id)
+            .await
+    }
+
+    pub async fn reject_join_request(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        request_user_id: &i32,
+    ) -> AppResult<()> {
+        self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+        if !self.delete_join_request(id, request_user_id).await? {
+            Err(ChampionshipError::JoinRequestNotFound)?
+        }
 
-        tokio::try_join!(add_user_future, delete_user_cache_future)?;
         Ok(())
     }
 
@@ -232,19 +566,26 @@ impl ChampionshipService {
         user_id: &i32,
         remove_user_id: &i32,
     ) -> AppResult<()> {
-        // Scope to check if championship exists and if user is owner
+        // Scope to check if championship exists and if user can remove the member
         {
             let Some(championship) = self.championship_repository.find(id).await? else {
                 Err(ChampionshipError::NotFound)?
             };
 
-            if championship.owner_id != *user_id {
-                Err(ChampionshipError::NotOwner)?
-            }
-
             if championship.owner_id == *remove_user_id {
                 Err(ChampionshipError::CannotRemoveOwner)?
             }
+
+            let user_role = self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+            // Only the owner can remove other admins
+            if let Some(removed_role) = self
+                .championship_repository
+                .role(id, remove_user_id)
+                .await?
+            {
+                Self::check_assignable(user_role, removed_role)?;
+            }
         }
 
         if self.user_repository.find(remove_user_id).await?.is_none() {
@@ -272,6 +613,147 @@ impl ChampionshipService {
         Ok(())
     }
 
+    pub async fn update_role(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        member_id: &i32,
+        role: ChampionshipRole,
+    ) -> AppResult<()> {
+        // Scope to check if championship exists and if user can change the role of the member
+        {
+            if self.championship_repository.find(id).await?.is_none() {
+                Err(ChampionshipError::NotFound)?
+            };
+
+            let user_role = self.authorize(id, user_id, ChampionshipRole::Admin).await?;
+
+            let Some(member_role) = self.championship_repository.role(id, member_id).await? else {
+                Err(ChampionshipError::NotMember)?
+            };
+
+            // Ownership can only change through a transfer
+            if member_role == ChampionshipRole::Owner {
+                Err(ChampionshipError::InvalidRole)?
+            }
+
+            Self::check_assignable(user_role, member_role)?;
+            Self::check_assignable(user_role, role)?;
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        let update_role_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE user_championships SET role = $1
+                    WHERE user_id = $2 AND championship_id = $3
+                "#,
+            )
+            .await?;
+
+        let bindings: [&(dyn ToSql + Sync); 3] = [&role, member_id, id];
+        conn.execute(&update_role_stmt, &bindings).await?;
+
+        Ok(())
+    }
+
+    // The previous owner stays in the championship as admin
+    pub async fn transfer_ownership(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        new_owner_id: &i32,
+    ) -> AppResult<()> {
+        // Scope to check if championship exists and if user is owner
+        {
+            let Some(championship) = self.championship_repository.find(id).await? else {
+                Err(ChampionshipError::NotFound)?
+            };
+
+            if championship.owner_id != *user_id {
+                Err(ChampionshipError::NotOwner)?
+            }
+
+            if championship.owner_id == *new_owner_id {
+                Err(ChampionshipError::InvalidRole)?
+            }
+
+            if self
+                .championship_repository
+                .role(id, new_owner_id)
+                .await?
+                .is_none()
+            {
+                Err(ChampionshipError::NotMember)?
+            }
+
+            self.check_championship_limit(new_owner_id, false).await?;
+        }
+
+        {
+            let mut conn = self.db.pg.get().await?;
+            let transaction = conn.transaction().await?;
+
+            let update_owner_stmt_fut = transaction.prepare_cached(
+                r#"
+                    UPDATE championship SET owner_id = $1
+                    WHERE id = $2
+                "#,
+            );
+
+            let update_role_stmt_fut = transaction.prepare_cached(
+                r#"
+                    UPDATE user_championships SET role = $1
+                    WHERE user_id = $2 AND championship_id = $3
+                "#,
+            );
+
+            let (update_owner_stmt, update_role_stmt) =
+                tokio::try_join!(update_owner_stmt_fut, update_role_stmt_fut)?;
+
+            transaction
+                .execute(&update_owner_stmt, &[new_owner_id, id])
+                .await?;
+
+            transaction
+                .execute(&update_role_stmt, &[&ChampionshipRole::Admin, user_id, id])
+                .await?;
+
+            transaction
+                .execute(
+                    &update_role_stmt,
+                    &[&ChampionshipRole::Owner, new_owner_id, id],
+                )
+                .await?;
+
+            transaction.commit().await?;
+        }
+
+        let users = self.championship_repository.users(id).await?;
+        self.cache.championship.delete_all(id, users).await?;
+
+        Ok(())
+    }
+
+    // Returns the role of the user in the championship if it's at least the required one
+    pub async fn authorize(
+        &self,
+        id: &i32,
+        user_id: &i32,
+        required: ChampionshipRole,
+    ) -> AppResult<ChampionshipRole> {
+        let Some(role) = self.championship_repository.role(id, user_id).await? else {
+            Err(ChampionshipError::NotMember)?
+        };
+
+        if !role.at_least(required) {
+            Err(ChampionshipError::InsufficientRole)?
+        }
+
+        Ok(role)
+    }
+
     pub async fn delete(&self, id: &i32) -> AppResult<()> {
         let conn = self.db.pg.get().await?;
 
@@ -308,6 +790,97 @@ impl ChampionshipService {
         Ok(())
     }
 
+    // Invites can only be answered by the invited user before they expire
+    async fn pending_invite(
+        &self,
+        invite_id: &i32,
+        user_id: &i32,
+    ) -> AppResult<ChampionshipInvite> {
+        let Some(invite) = self.championship_repository.invite(invite_id).await? else {
+            Err(ChampionshipError::InviteNotFound)?
+        };
+
+        if invite.user_id != *user_id {
+            Err(ChampionshipError::InviteNotFound)?
+        }
+
+        if invite.expires_at <= Utc::now() {
+            Err(ChampionshipError::InviteExpired)?
+        }
+
+        Ok(invite)
+    }
+
+    // Returns false if there wasn't any request to delete
+    async fn delete_join_request(&self, id: &i32, user_id: &i32) -> AppResult<bool> {
+        let conn = self.db.pg.get().await?;
+
+        let delete_join_request_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM championship_join_requests
+                    WHERE championship_id = $1 AND user_id = $2
+                "#,
+            )
+            .await?;
+
+        let deleted = conn
+            .execute(&delete_join_request_stmt, &[id, user_id])
+            .await?;
+
+        Ok(deleted > 0)
+    }
+
+    // Members already count the championship, users joining need room for one more
+    async fn check_championship_limit(&self, user_id: &i32, joining: bool) -> AppResult<()> {
+        let Some(user) = self.user_repository.find(user_id).await? else {
+            Err(UserError::NotFound)?
+        };
+
+        if let Some(limit) = user.role.championship_limit() {
+            let championships_len = self
+                .championship_repository
+                .championship_len(user_id)
+                .await?;
+
+            if championships_len + usize::from(joining) > limit {
+                Err(ChampionshipError::LimitReached)?
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_invite(&self, invite_id: &i32) -> AppResult<()> {
+        let conn = self.db.pg.get().await?;
+
+        let delete_invite_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM championship_invites WHERE id = $1
+                "#,
+            )
+            .await?;
+
+        conn.execute(&delete_invite_stmt, &[invite_id]).await?;
+
+        Ok(())
+    }
+
+    // Admins manage the roles below them, only the owner manages admins
+    #[inline(always)]
+    fn check_assignable(user_role: ChampionshipRole, role: ChampionshipRole) -> AppResult<()> {
+        if role == ChampionshipRole::Owner {
+            Err(ChampionshipError::InvalidRole)?
+        }
+
+        if role == ChampionshipRole::Admin && user_role != ChampionshipRole::Owner {
+            Err(ChampionshipError::InsufficientRole)?
+        }
+
+        Ok(())
+    }
+
     async fn available_ports(
         championship_repository: &ChampionshipRepository,
     ) -> AppResult<AHashSet<i32>> {
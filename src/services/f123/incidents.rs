@@ -0,0 +1,151 @@
+use crate::{
+    config::Database,
+    dtos::{EventCode, PacketEventData},
+    entity::IncidentKind,
+    error::AppResult,
+};
+
+// Vehicle index used by the game when there is no other car involved
+const NO_VEHICLE: u8 = 255;
+
+// Penalty or overtake reported by the game, stored so stewards can review the session later.
+// Overtake events don't carry the lap, so it's left empty for them
+#[derive(Debug, PartialEq, Eq)]
+pub struct Incident {
+    kind: IncidentKind,
+    lap_num: Option<i16>,
+    vehicle_idx: i16,
+    other_vehicle_idx: Option<i16>,
+    penalty_type: Option<i16>,
+    infringement_type: Option<i16>,
+    time: Option<i16>,
+    places_gained: Option<i16>,
+}
+
+impl Incident {
+    #[inline(always)]
+    pub fn from_event(event: &PacketEventData) -> Option<Self> {
+        match EventCode::try_from(&event.event_string_code).ok()? {
+            EventCode::PenaltyIssued => {
+                let penalty = unsafe { event.event_details.penalty };
+
+                Some(Self {
+                    kind: IncidentKind::Penalty,
+                    lap_num: Some(penalty.lap_num as i16),
+                    vehicle_idx: penalty.vehicle_idx as i16,
+                    other_vehicle_idx: vehicle(penalty.other_vehicle_idx),
+                    penalty_type: Some(penalty.penalty_type as i16),
+                    infringement_type: Some(penalty.infringement_type as i16),
+                    time: Some(penalty.time as i16),
+                    places_gained: Some(penalty.places_gained as i16),
+                })
+            }
+
+            EventCode::Overtake => {
+                let overtake = unsafe { event.event_details.overtake };
+
+                Some(Self {
+                    kind: IncidentKind::Overtake,
+                    lap_num: None,
+                    vehicle_idx: overtake.overtaking_vehicle_idx as i16,
+                    other_vehicle_idx: vehicle(overtake.being_overtaken_vehicle_idx),
+                    penalty_type: None,
+                    infringement_type: None,
+                    time: None,
+                    places_gained: None,
+                })
+            }
+
+            _ => None,
+        }
+    }
+
+    pub async fn save(
+        &self,
+        db: &Database,
+        championship_id: i32,
+        session_uid: i64,
+    ) -> AppResult<()> {
+        let conn = db.pg.get().await?;
+
+        let save_incident_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO session_incidents (championship_id, session_uid, kind, lap_num, vehicle_idx,
+                        other_vehicle_idx, penalty_type, infringement_type, time, places_gained)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10)
+                "#,
+            )
+            .await?;
+
+        conn.execute(
+            &save_incident_stmt,
+            &[
+                &championship_id,
+                &session_uid,
+                &self.kind,
+                &self.lap_num,
+                &self.vehicle_idx,
+                &self.other_vehicle_idx,
+                &self.penalty_type,
+                &self.infringement_type,
+                &self.time,
+                &self.places_gained,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn vehicle(idx: u8) -> Option<i16> {
+    (idx != NO_VEHICLE).then_some(idx as i16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Incident;
+    use crate::{
+        dtos::{Overtake, PacketEventData, Penalty},
+        entity::IncidentKind,
+    };
+    use zerocopy::FromZeros;
+
+    #[test]
+    fn test_penalty_and_overtake_incidents() {
+        let mut event = PacketEventData::new_zeroed();
+        event.event_string_code = *b"PENA";
+        event.event_details.penalty = Penalty {
+            penalty_type: 4,
+            infringement_type: 3,
+            vehicle_idx: 5,
+            other_vehicle_idx: 255,
+            time: 5,
+            lap_num: 12,
+            places_gained: 0,
+        };
+
+        let incident = Incident::from_event(&event).unwrap();
+        assert_eq!(incident.kind, IncidentKind::Penalty);
+        assert_eq!(incident.lap_num, Some(12));
+        assert_eq!(incident.vehicle_idx, 5);
+        assert_eq!(incident.other_vehicle_idx, None);
+        assert_eq!(incident.infringement_type, Some(3));
+
+        event.event_string_code = *b"OVTK";
+        event.event_details.overtake = Overtake {
+            overtaking_vehicle_idx: 2,
+            being_overtaken_vehicle_idx: 7,
+        };
+
+        let incident = Incident::from_event(&event).unwrap();
+        assert_eq!(incident.kind, IncidentKind::Overtake);
+        assert_eq!(incident.lap_num, None);
+        assert_eq!(incident.other_vehicle_idx, Some(7));
+
+        event.event_string_code = *b"SPTP";
+        assert!(Incident::from_event(&event).is_none());
+    }
+}
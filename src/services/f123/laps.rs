@@ -0,0 +1,156 @@
+use crate::{
+    config::Database,
+    dtos::{LapHistoryData, PacketSessionHistoryData, TyreStintHistoryData},
+    error::AppResult,
+};
+
+// Laps completed by a car since the last time its history was saved, stints are
+// always sent complete because the current one changes until the car pits
+pub struct LapHistory {
+    car_idx: i16,
+    first_lap: u8,
+    laps: Vec<LapHistoryData>,
+    stints: Vec<TyreStintHistoryData>,
+}
+
+impl LapHistory {
+    #[inline(always)]
+    pub fn new(history: &PacketSessionHistoryData, saved: u8) -> Option<Self> {
+        let num_laps = (history.num_laps as usize).min(history.lap_history_data.len());
+
+        // The lap in progress doesn't have a lap time yet
+        let completed = history.lap_history_data[..num_laps]
+            .iter()
+            .take_while(|lap| lap.lap_time_in_ms > 0)
+            .count();
+
+        if completed <= saved as usize {
+            return None;
+        }
+
+        let num_stints =
+            (history.num_tyre_stints as usize).min(history.tyre_stints_history_data.len());
+
+        Some(Self {
+            car_idx: history.car_idx as i16,
+            first_lap: saved,
+            laps: history.lap_history_data[saved as usize..completed].to_vec(),
+            stints: history.tyre_stints_history_data[..num_stints].to_vec(),
+        })
+    }
+
+    #[inline(always)]
+    pub fn completed(&self) -> u8 {
+        self.first_lap + self.laps.len() as u8
+    }
+
+    pub async fn save(
+        &self,
+        db: &Database,
+        championship_id: i32,
+        session_uid: i64,
+    ) -> AppResult<()> {
+        let mut conn = db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let (save_lap_stmt, save_stint_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO session_laps (championship_id, session_uid, car_idx, lap_num, lap_time_in_ms,
+                        sector1_time_in_ms, sector2_time_in_ms, sector3_time_in_ms, lap_valid_bit_flags)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+                    ON CONFLICT (championship_id, session_uid, car_idx, lap_num) DO UPDATE
+                    SET lap_time_in_ms = EXCLUDED.lap_time_in_ms, sector1_time_in_ms = EXCLUDED.sector1_time_in_ms,
+                        sector2_time_in_ms = EXCLUDED.sector2_time_in_ms, sector3_time_in_ms = EXCLUDED.sector3_time_in_ms,
+                        lap_valid_bit_flags = EXCLUDED.lap_valid_bit_flags
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO session_stints (championship_id, session_uid, car_idx, stint, end_lap,
+                        tyre_actual_compound, tyre_visual_compound)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7)
+                    ON CONFLICT (championship_id, session_uid, car_idx, stint) DO UPDATE
+                    SET end_lap = EXCLUDED.end_lap, tyre_actual_compound = EXCLUDED.tyre_actual_compound,
+                        tyre_visual_compound = EXCLUDED.tyre_visual_compound
+                "#,
+            )
+        )?;
+
+        for (idx, lap) in self.laps.iter().enumerate() {
+            let lap_num = (self.first_lap as usize + idx + 1) as i16;
+            let lap_time_in_ms = lap.lap_time_in_ms as i32;
+
+            transaction
+                .execute(
+                    &save_lap_stmt,
+                    &[
+                        &championship_id,
+                        &session_uid,
+                        &self.car_idx,
+                        &lap_num,
+                        &lap_time_in_ms,
+                        &sector_time(lap.sector1_time_minutes, lap.sector1_time_in_ms),
+                        &sector_time(lap.sector2_time_minutes, lap.sector2_time_in_ms),
+                        &sector_time(lap.sector3_time_minutes, lap.sector3_time_in_ms),
+                        &(lap.lap_valid_bit_flags as i16),
+                    ],
+                )
+                .await?;
+        }
+
+        for (stint, tyres) in self.stints.iter().enumerate() {
+            transaction
+                .execute(
+                    &save_stint_stmt,
+                    &[
+                        &championship_id,
+                        &session_uid,
+                        &self.car_idx,
+                        &(stint as i16),
+                        &(tyres.end_lap as i16),
+                        &(tyres.tyre_actual_compound as i16),
+                        &(tyres.tyre_visual_compound as i16),
+                    ],
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn sector_time(minutes: u8, ms: u16) -> i32 {
+    minutes as i32 * 60_000 + ms as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LapHistory;
+    use crate::dtos::PacketSessionHistoryData;
+    use zerocopy::FromZeros;
+
+    #[test]
+    fn test_only_new_completed_laps() {
+        let mut history = PacketSessionHistoryData::new_zeroed();
+        history.num_laps = 3;
+        history.num_tyre_stints = 1;
+        history.lap_history_data[0].lap_time_in_ms = 90_000;
+        history.lap_history_data[1].lap_time_in_ms = 91_000;
+
+        let laps = LapHistory::new(&history, 0).unwrap();
+        assert_eq!(laps.laps.len(), 2);
+        assert_eq!(laps.stints.len(), 1);
+        assert_eq!(laps.completed(), 2);
+
+        assert!(LapHistory::new(&history, 2).is_none());
+
+        history.lap_history_data[2].lap_time_in_ms = 89_500;
+        let laps = LapHistory::new(&history, 2).unwrap();
+        assert_eq!(laps.first_lap, 2);
+        assert_eq!(laps.laps.len(), 1);
+    }
+}
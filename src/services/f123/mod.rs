@@ -1,4 +1,11 @@
+mod incidents;
+mod laps;
+mod motion_delta;
 mod packet_batching;
+mod records;
+mod results;
 mod service;
+mod subscribers;
 
 pub(crate) use service::*;
+pub(crate) use subscribers::*;
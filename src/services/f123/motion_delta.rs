@@ -0,0 +1,173 @@
+use crate::{
+    config::constants::MOTION_KEYFRAME_INTERVAL,
+    protos::{
+        car_motion_data::{CarMotionData, CarMotionDelta, PacketMotionData, PacketMotionDelta},
+        packet_header::PacketType,
+        PacketHeader,
+    },
+};
+use prost::Message;
+
+const POSITION_SCALE: f32 = 100.0;
+const YAW_SCALE: f32 = 10_000.0;
+
+// Keeps the last keyframe sent, the motion packets in between are sent as quantized deltas.
+// Velocity and direction values are only sent in the keyframes
+pub struct MotionDelta {
+    keyframe: Vec<CarMotionData>,
+    keyframe_packet: Option<PacketHeader>,
+    sequence: u32,
+    packets: u32,
+}
+
+impl MotionDelta {
+    pub fn new() -> Self {
+        Self {
+            keyframe: Vec::new(),
+            keyframe_packet: None,
+            sequence: 0,
+            packets: MOTION_KEYFRAME_INTERVAL,
+        }
+    }
+
+    // Packet of the last keyframe, clients joining between keyframes need it to decode the deltas
+    #[inline(always)]
+    pub fn keyframe(&self) -> Option<&PacketHeader> {
+        self.keyframe_packet.as_ref()
+    }
+
+    #[inline(always)]
+    pub fn encode(&mut self, mut motion: PacketMotionData) -> PacketHeader {
+        let needs_keyframe = self.packets >= MOTION_KEYFRAME_INTERVAL
+            || self.keyframe.len() != motion.car_motion_data.len();
+
+        if needs_keyframe {
+            self.sequence = self.sequence.wrapping_add(1);
+            self.packets = 1;
+            motion.keyframe = self.sequence;
+
+            let packet = PacketHeader {
+                r#type: PacketType::CarMotion.into(),
+                payload: motion.encode_to_vec(),
+            };
+
+            self.keyframe = motion.car_motion_data;
+            self.keyframe_packet = Some(packet.clone());
+
+            return packet;
+        }
+
+        self.packets += 1;
+
+        let delta = PacketMotionDelta {
+            keyframe: self.sequence,
+            car_motion_delta: self
+                .keyframe
+                .iter()
+                .zip(&motion.car_motion_data)
+                .enumerate()
+                .filter_map(|(car_idx, (base, car))| Self::car_delta(car_idx, base, car))
+                .collect(),
+        };
+
+        PacketHeader {
+            r#type: PacketType::CarMotionDelta.into(),
+            payload: delta.encode_to_vec(),
+        }
+    }
+
+    #[inline(always)]
+    fn car_delta(
+        car_idx: usize,
+        base: &CarMotionData,
+        car: &CarMotionData,
+    ) -> Option<CarMotionDelta> {
+        let delta = CarMotionDelta {
+            car_idx: car_idx as u32,
+            world_position_x: quantize(car.world_position_x, base.world_position_x, POSITION_SCALE),
+            world_position_y: quantize(car.world_position_y, base.world_position_y, POSITION_SCALE),
+            world_position_z: quantize(car.world_position_z, base.world_position_z, POSITION_SCALE),
+            yaw: quantize(car.yaw, base.yaw, YAW_SCALE),
+        };
+
+        if delta.world_position_x == 0
+            && delta.world_position_y == 0
+            && delta.world_position_z == 0
+            && delta.yaw == 0
+        {
+            return None;
+        }
+
+        Some(delta)
+    }
+}
+
+#[inline(always)]
+fn quantize(value: f32, base: f32, scale: f32) -> i32 {
+    ((value - base) * scale).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MotionDelta;
+    use crate::{
+        config::constants::MOTION_KEYFRAME_INTERVAL,
+        protos::{
+            car_motion_data::{CarMotionData, PacketMotionData, PacketMotionDelta},
+            packet_header::PacketType,
+        },
+    };
+    use prost::Message;
+
+    fn motion(x: f32, yaw: f32) -> PacketMotionData {
+        PacketMotionData {
+            car_motion_data: vec![
+                CarMotionData {
+                    world_position_x: x,
+                    yaw,
+                    ..Default::default()
+                },
+                CarMotionData::default(),
+            ],
+            keyframe: 0,
+        }
+    }
+
+    #[test]
+    fn test_keyframe_and_deltas() {
+        let mut motion_delta = MotionDelta::new();
+
+        assert!(motion_delta.keyframe().is_none());
+
+        let keyframe = motion_delta.encode(motion(100.0, 0.5));
+        assert_eq!(keyframe.r#type, i32::from(PacketType::CarMotion));
+        assert_eq!(motion_delta.keyframe(), Some(&keyframe));
+
+        let delta = motion_delta.encode(motion(112.345, 0.4));
+        assert_eq!(delta.r#type, i32::from(PacketType::CarMotionDelta));
+
+        let delta = PacketMotionDelta::decode(&delta.payload[..]).unwrap();
+        assert_eq!(delta.keyframe, 1);
+        assert_eq!(delta.car_motion_delta.len(), 1);
+
+        let car = &delta.car_motion_delta[0];
+        let x = 100.0 + car.world_position_x as f32 / 100.0;
+        let yaw = 0.5 + car.yaw as f32 / 10_000.0;
+        assert!((x - 112.345).abs() <= 0.005);
+        assert!((yaw - 0.4).abs() <= 0.00005);
+
+        for _ in 2..MOTION_KEYFRAME_INTERVAL {
+            let packet = motion_delta.encode(motion(100.0, 0.5));
+            assert_eq!(packet.r#type, i32::from(PacketType::CarMotionDelta));
+        }
+
+        let keyframe = motion_delta.encode(motion(100.0, 0.5));
+        assert_eq!(keyframe.r#type, i32::from(PacketType::CarMotion));
+        assert_eq!(
+            PacketMotionData::decode(&keyframe.payload[..])
+                .unwrap()
+                .keyframe,
+            2
+        );
+    }
+}
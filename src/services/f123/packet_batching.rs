@@ -1,34 +1,67 @@
+use super::{motion_delta::MotionDelta, TopicSubscribers};
 use crate::{
     cache::F123InsiderCache,
     config::constants::BATCHING_INTERVAL,
     error::{AppResult, F123Error},
-    protos::{batched::ToProtoMessageBatched, PacketHeader},
+    protos::{
+        batched::ToProtoMessageBatched,
+        car_motion_data::PacketMotionData,
+        packet_header::PacketType,
+        socket_control::{TopicBatch, TopicKey},
+        PacketHeader,
+    },
 };
+use ahash::AHashMap;
 use ntex::util::Bytes;
 use tokio::{sync::broadcast::Sender, time::Instant};
 use tracing::warn;
 
 // Packet Batching implementation
 pub struct PacketBatching {
-    buf: Vec<PacketHeader>,
-    tx: Sender<Bytes>,
+    buf: Vec<(TopicKey, PacketHeader)>,
+    tx: Sender<TopicBatch>,
+    subscribers: TopicSubscribers,
     last_batch_time: Instant,
     cache: F123InsiderCache,
+    motion_delta: MotionDelta,
 }
 
 impl PacketBatching {
-    pub fn new(tx: Sender<Bytes>, cache: F123InsiderCache) -> Self {
+    pub fn new(
+        tx: Sender<TopicBatch>,
+        subscribers: TopicSubscribers,
+        cache: F123InsiderCache,
+    ) -> Self {
         Self {
             tx,
+            subscribers,
             buf: Vec::with_capacity(2048),
             last_batch_time: Instant::now(),
             cache,
+            motion_delta: MotionDelta::new(),
         }
     }
 
     #[inline(always)]
     pub async fn push_and_check(&mut self, packet: PacketHeader) -> AppResult<()> {
-        self.buf.push(packet);
+        self.push(packet, None);
+        self.check().await?;
+        Ok(())
+    }
+
+    // Same as `push_and_check` but the packet is also published in the topic of the car
+    #[inline(always)]
+    pub async fn push_car_and_check(&mut self, packet: PacketHeader, car_idx: u8) -> AppResult<()> {
+        self.push(packet, Some(car_idx));
+        self.check().await?;
+        Ok(())
+    }
+
+    // Motion packets are sent as keyframes or as deltas of the last keyframe
+    #[inline(always)]
+    pub async fn push_motion_and_check(&mut self, motion: PacketMotionData) -> AppResult<()> {
+        let packet = self.motion_delta.encode(motion);
+        self.push(packet, None);
         self.check().await?;
         Ok(())
     }
@@ -38,17 +71,15 @@ impl PacketBatching {
     // Should be not used for other event that is not the end of the session
     #[inline(always)]
     pub async fn final_send(&mut self, packet: PacketHeader) -> AppResult<()> {
-        self.buf.push(packet);
+        self.push(packet, None);
 
-        let buf = self.buf.drain(..).collect::<Vec<_>>();
-        if let Some(batch) = ToProtoMessageBatched::batched_encoded(buf) {
+        let (all, topics) = self.split_batches();
+        if let Some(batch) = ToProtoMessageBatched::batched_encoded(all) {
             self.cache.prune().await?;
-            let encoded_batch = Self::compress(&batch).await.unwrap();
+            let encoded_batch = Self::compress(&batch)?;
 
-            // Todo: Check the subscribers count and only send if is at least 1 receiver `self.tx.receiver_count()`
-            if let Err(e) = self.tx.send(encoded_batch) {
-                warn!("Broadcast Channel: {}", e);
-            };
+            self.send(TopicKey::ALL, batch, encoded_batch);
+            self.send_topics(topics).await?;
         } else {
             Err(F123Error::BatchedEncoding)?
         }
@@ -57,6 +88,17 @@ impl PacketBatching {
         Ok(())
     }
 
+    #[inline(always)]
+    fn push(&mut self, packet: PacketHeader, car_idx: Option<u8>) {
+        // Unknown packet types are only published in the global batch
+        let key = match PacketType::try_from(packet.r#type) {
+            Ok(packet_type) => TopicKey::new(packet_type, car_idx),
+            Err(_) => TopicKey::ALL,
+        };
+
+        self.buf.push((key, packet));
+    }
+
     #[inline(always)]
     async fn check(&mut self) -> AppResult<()> {
         if self.last_batch_time.elapsed() < BATCHING_INTERVAL || self.buf.is_empty() {
@@ -64,29 +106,94 @@ impl PacketBatching {
         }
 
         // TODO: Implement another cache method for events
-        if let Some(batch) = ToProtoMessageBatched::batched_encoded(self.buf.clone()) {
-            let encoded_batch = Self::compress(&batch).await.unwrap();
-            self.cache.set(&encoded_batch).await?;
+        let (all, topics) = self.split_batches();
+        let cached_batch = self.cached_batch(&all)?;
 
-            // Todo: Check the subscribers count and only send if is at least 1 receiver `self.tx.receiver_count()`
-            if let Err(e) = self.tx.send(encoded_batch) {
-                warn!("Broadcast channel: {}", e);
-            };
+        if let Some(batch) = ToProtoMessageBatched::batched_encoded(all) {
+            let encoded_batch = Self::compress(&batch)?;
+            self.cache
+                .set(cached_batch.as_ref().unwrap_or(&encoded_batch))
+                .await?;
+
+            self.send(TopicKey::ALL, batch, encoded_batch);
+            self.send_topics(topics).await?;
         } else {
             Err(F123Error::BatchedEncoding)?
         }
 
         self.last_batch_time = Instant::now();
-        self.buf.clear();
         Ok(())
     }
 
+    // Drains the buffer into the global batch and the batches of the topics someone is subscribed to
+    #[inline(always)]
+    fn split_batches(&mut self) -> (Vec<PacketHeader>, AHashMap<TopicKey, Vec<PacketHeader>>) {
+        let mut all = Vec::with_capacity(self.buf.len());
+        let mut topics: AHashMap<TopicKey, Vec<PacketHeader>> = AHashMap::default();
+
+        for (key, packet) in self.buf.drain(..) {
+            if key != TopicKey::ALL && self.subscribers.wanted(&key) {
+                topics.entry(key).or_default().push(packet.clone());
+            }
+
+            all.push(packet);
+        }
+
+        (all, topics)
+    }
+
+    // The cached batch is the first thing new clients receive, batches without a keyframe
+    // are cached with the last one in front so their motion deltas can be decoded
+    #[inline(always)]
+    fn cached_batch(&self, all: &[PacketHeader]) -> AppResult<Option<Bytes>> {
+        let Some(keyframe) = self.motion_delta.keyframe() else {
+            return Ok(None);
+        };
+
+        if all.iter().any(|packet| packet.r#type == keyframe.r#type) {
+            return Ok(None);
+        }
+
+        let packets = std::iter::once(keyframe).chain(all).cloned().collect();
+        let Some(batch) = ToProtoMessageBatched::batched_encoded(packets) else {
+            Err(F123Error::BatchedEncoding)?
+        };
+
+        Ok(Some(Self::compress(&batch)?))
+    }
+
+    #[inline(always)]
+    async fn send_topics(&self, topics: AHashMap<TopicKey, Vec<PacketHeader>>) -> AppResult<()> {
+        for (key, packets) in topics {
+            let Some(batch) = ToProtoMessageBatched::batched_encoded(packets) else {
+                Err(F123Error::BatchedEncoding)?
+            };
+
+            let encoded_batch = Self::compress(&batch)?;
+            self.send(key, batch, encoded_batch);
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn send(&self, key: TopicKey, raw: Bytes, compressed: Bytes) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+
+        if let Err(e) = self.tx.send(TopicBatch::new(key, raw, compressed)) {
+            warn!("Broadcast channel: {}", e);
+        };
+    }
+
     // Testing brotli compression algorithm for batched data
     // This method is used to compress the batched data
     #[inline(always)]
-    async fn compress(data: &[u8]) -> Result<Bytes, Box<dyn std::error::Error>> {
+    fn compress(data: &[u8]) -> AppResult<Bytes> {
         // todo: Decide between level 3 or 9 for compression 280us(level3) vs 1ms(level9)
-        let compressed_data: Vec<u8> = zstd::stream::encode_all(data, 9).unwrap();
+        let compressed_data: Vec<u8> =
+            zstd::stream::encode_all(data, 9).map_err(|_| F123Error::Compression)?;
         Ok(Bytes::from(compressed_data))
     }
 }
@@ -0,0 +1,264 @@
+use crate::{
+    config::Database,
+    dtos::{
+        EventCode, PacketEventData, PacketFinalClassificationData, PacketParticipantsData,
+        PacketSessionHistoryData,
+    },
+    error::AppResult,
+    protos::{packet_header::PacketType, records::NewRecord, PacketHeader},
+};
+use ahash::AHashMap;
+use prost::Message;
+use std::ffi::CStr;
+
+const LAP_VALID: u8 = 0x01;
+
+// Keeps the personal bests and track records of the session up to date, fastest laps reported
+// by the game wait until the session history of the car says if the lap was valid.
+// Valid laps of every car are kept from the history to check the best laps of the classification
+pub struct RecordTracker {
+    db: Database,
+    championship_id: i32,
+    session_uid: u64,
+    driver_names: AHashMap<u8, String>,
+    pending: AHashMap<u8, u32>,
+    valid_laps: AHashMap<u8, Vec<u32>>,
+}
+
+impl RecordTracker {
+    pub fn new(db: &Database, championship_id: i32) -> Self {
+        Self {
+            db: db.clone(),
+            championship_id,
+            session_uid: 0,
+            driver_names: AHashMap::default(),
+            pending: AHashMap::default(),
+            valid_laps: AHashMap::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_driver_names(&mut self, participants: &PacketParticipantsData) {
+        let num_cars = (participants.num_active_cars as usize).min(participants.participants.len());
+
+        for (car_idx, participant) in participants.participants[..num_cars].iter().enumerate() {
+            let Ok(name) = CStr::from_bytes_until_nul(&participant.name) else {
+                continue;
+            };
+
+            match name.to_str() {
+                Ok(name) if !name.is_empty() => {
+                    self.driver_names.insert(car_idx as u8, name.to_owned());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn driver_names(&self) -> &AHashMap<u8, String> {
+        &self.driver_names
+    }
+
+    #[inline(always)]
+    pub fn fastest_lap(&mut self, event: &PacketEventData) {
+        if EventCode::try_from(&event.event_string_code) != Ok(EventCode::FastestLap) {
+            return;
+        }
+
+        let fastest_lap = unsafe { event.event_details.fastest_lap };
+        let lap_time = (fastest_lap.lap_time * 1000.0).round() as u32;
+
+        self.pending.insert(fastest_lap.vehicle_idx, lap_time);
+    }
+
+    // Returns the new record packet if the pending fastest lap of the car beats the track record
+    pub async fn check_history(
+        &mut self,
+        session_uid: u64,
+        track_id: i16,
+        history: &PacketSessionHistoryData,
+    ) -> AppResult<Option<PacketHeader>> {
+        self.set_session(session_uid);
+
+        let num_laps = (history.num_laps as usize).min(history.lap_history_data.len());
+
+        self.valid_laps.insert(
+            history.car_idx,
+            history.lap_history_data[..num_laps]
+                .iter()
+                .filter(|lap| lap.lap_time_in_ms > 0 && lap.lap_valid_bit_flags & LAP_VALID != 0)
+                .map(|lap| lap.lap_time_in_ms)
+                .collect(),
+        );
+
+        let Some(lap_time) = self.pending.get(&history.car_idx).copied() else {
+            return Ok(None);
+        };
+
+        // The history can arrive before the lap is closed, the game rounds the event time
+        let Some(lap) = history.lap_history_data[..num_laps]
+            .iter()
+            .find(|lap| lap.lap_time_in_ms.abs_diff(lap_time) <= 1)
+        else {
+            return Ok(None);
+        };
+
+        self.pending.remove(&history.car_idx);
+
+        if lap.lap_valid_bit_flags & LAP_VALID == 0 {
+            return Ok(None);
+        }
+
+        let lap_time_in_ms = lap.lap_time_in_ms;
+        self.submit(session_uid, track_id, history.car_idx, lap_time_in_ms)
+            .await
+    }
+
+    // Best laps of the classification only count if the history of the car had them as valid
+    pub async fn check_classification(
+        &mut self,
+        session_uid: u64,
+        track_id: i16,
+        classification: &PacketFinalClassificationData,
+    ) -> AppResult<Vec<PacketHeader>> {
+        self.set_session(session_uid);
+
+        let mut packets = Vec::new();
+        let num_cars =
+            (classification.num_cars as usize).min(classification.classification_data.len());
+
+        for (car_idx, result) in classification.classification_data[..num_cars]
+            .iter()
+            .enumerate()
+        {
+            let best_lap_time_in_ms = result.best_lap_time_in_ms;
+
+            if best_lap_time_in_ms == 0 {
+                continue;
+            }
+
+            let valid = self
+                .valid_laps
+                .get(&(car_idx as u8))
+                .is_some_and(|laps| laps.contains(&best_lap_time_in_ms));
+
+            if !valid {
+                continue;
+            }
+
+            if let Some(packet) = self
+                .submit(session_uid, track_id, car_idx as u8, best_lap_time_in_ms)
+                .await?
+            {
+                packets.push(packet);
+            }
+        }
+
+        Ok(packets)
+    }
+
+    // Pending laps and histories belong to the session they were reported in
+    #[inline(always)]
+    fn set_session(&mut self, session_uid: u64) {
+        if self.session_uid != session_uid {
+            self.session_uid = session_uid;
+            self.pending.clear();
+            self.valid_laps.clear();
+        }
+    }
+
+    // Laps are attributed to the member assigned to the car, or the one whose username
+    // matches the name of the driver while the session has no results yet
+    async fn submit(
+        &self,
+        session_uid: u64,
+        track_id: i16,
+        car_idx: u8,
+        lap_time_in_ms: u32,
+    ) -> AppResult<Option<PacketHeader>> {
+        // Laps of drivers without a name or member or in unknown tracks can't be attributed
+        let Some(driver_name) = self.driver_names.get(&car_idx) else {
+            return Ok(None);
+        };
+
+        if track_id < 0 {
+            return Ok(None);
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        // Previous record is read before the upsert, the personal best is only replaced if it's faster
+        let submit_record_stmt = conn
+            .prepare_cached(
+                r#"
+                    WITH championship AS (
+                        SELECT category, season FROM championship WHERE id = $1
+                    ), driver AS (
+                        SELECT COALESCE((
+                            SELECT user_id FROM session_results
+                            WHERE championship_id = $1 AND session_uid = $2 AND car_idx = $6
+                        ), (
+                            SELECT u.id FROM "users" u
+                            JOIN user_championships uc ON uc.user_id = u.id
+                            WHERE uc.championship_id = $1 AND u.username = $4
+                            LIMIT 1
+                        )) AS user_id
+                    ), previous AS (
+                        SELECT MIN(r.lap_time_in_ms) AS lap_time_in_ms FROM track_records r
+                        JOIN championship c ON c.category = r.category AND c.season = r.season
+                        WHERE r.track_id = $3
+                    ), personal_best AS (
+                        INSERT INTO track_records (track_id, category, season, user_id, driver_name, lap_time_in_ms, championship_id, session_uid)
+                        SELECT $3, c.category, c.season, d.user_id, $4, $5, $1, $2 FROM championship c, driver d
+                        WHERE d.user_id IS NOT NULL
+                        ON CONFLICT (track_id, category, season, user_id) DO UPDATE
+                        SET lap_time_in_ms = EXCLUDED.lap_time_in_ms, driver_name = EXCLUDED.driver_name,
+                            championship_id = EXCLUDED.championship_id,
+                            session_uid = EXCLUDED.session_uid, set_at = CURRENT_TIMESTAMP
+                        WHERE EXCLUDED.lap_time_in_ms < track_records.lap_time_in_ms
+                        RETURNING lap_time_in_ms
+                    )
+                    SELECT (SELECT lap_time_in_ms FROM previous) AS previous,
+                        EXISTS (SELECT 1 FROM personal_best) AS personal_best
+                "#,
+            )
+            .await?;
+
+        let lap_time = lap_time_in_ms as i32;
+
+        let row = conn
+            .query_one(
+                &submit_record_stmt,
+                &[
+                    &self.championship_id,
+                    &(session_uid as i64),
+                    &track_id,
+                    driver_name,
+                    &lap_time,
+                    &(car_idx as i16),
+                ],
+            )
+            .await?;
+
+        let previous: Option<i32> = row.try_get("previous")?;
+        let personal_best: bool = row.try_get("personal_best")?;
+
+        if !personal_best || previous.is_some_and(|previous| previous <= lap_time) {
+            return Ok(None);
+        }
+
+        let record = NewRecord {
+            track_id: track_id as i32,
+            car_idx: car_idx as u32,
+            driver_name: driver_name.clone(),
+            lap_time_in_ms,
+            previous_lap_time_in_ms: previous.map(|previous| previous as u32),
+        };
+
+        Ok(Some(PacketHeader {
+            r#type: PacketType::NewRecord.into(),
+            payload: record.encode_to_vec(),
+        }))
+    }
+}
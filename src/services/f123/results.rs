@@ -0,0 +1,134 @@
+use crate::{
+    cache::StatsCache,
+    config::Database,
+    dtos::PacketFinalClassificationData,
+    entity::{RACE_SESSIONS, RESULT_STATUS_DISQUALIFIED},
+    error::AppResult,
+    services::position_points,
+};
+use ahash::AHashMap;
+
+// Session type and track of the session, taken from the last session packet
+#[derive(Clone, Copy)]
+pub struct SessionInfo {
+    pub session_type: i16,
+    pub track_id: i16,
+}
+
+// Stores the classification as reported by the game, the game sends the packet more than once
+// so repeated ones are ignored and adjustments made in the meantime are kept.
+// Cars are linked to the member whose username matches the name of the driver and the
+// points of races are taken from the points table of the championship
+pub async fn save_classification(
+    db: &Database,
+    championship_id: i32,
+    session_uid: i64,
+    info: SessionInfo,
+    driver_names: &AHashMap<u8, String>,
+    classification: &PacketFinalClassificationData,
+) -> AppResult<()> {
+    let mut conn = db.pg.get().await?;
+    let transaction = conn.transaction().await?;
+
+    let (points_table_stmt, save_session_stmt, save_result_stmt) = tokio::try_join!(
+        transaction.prepare_cached(
+            r#"
+                SELECT points_table FROM championship
+                WHERE id = $1
+            "#,
+        ),
+        transaction.prepare_cached(
+            r#"
+                INSERT INTO championship_sessions (championship_id, session_uid, session_type, track_id)
+                VALUES ($1,$2,$3,$4)
+                ON CONFLICT DO NOTHING
+            "#,
+        ),
+        transaction.prepare_cached(
+            r#"
+                INSERT INTO session_results (championship_id, session_uid, car_idx, position, num_laps, grid_position,
+                    points, num_pit_stops, result_status, best_lap_time_in_ms, total_race_time, penalties_time,
+                    num_penalties, adjusted_position, adjusted_penalties_time, adjusted_points, disqualified, driver_name, user_id)
+                VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$4,$12,$16,$14,$15::VARCHAR, (
+                    SELECT u.id FROM "users" u
+                    JOIN user_championships uc ON uc.user_id = u.id
+                    WHERE uc.championship_id = $1 AND u.username = $15
+                    LIMIT 1
+                ))
+                ON CONFLICT DO NOTHING
+                RETURNING user_id
+            "#,
+        )
+    )?;
+
+    let points_table: Vec<i16> = match RACE_SESSIONS.contains(&info.session_type) {
+        true => transaction
+            .query_one(&points_table_stmt, &[&championship_id])
+            .await?
+            .try_get("points_table")?,
+        false => Vec::new(),
+    };
+
+    transaction
+        .execute(
+            &save_session_stmt,
+            &[
+                &championship_id,
+                &session_uid,
+                &info.session_type,
+                &info.track_id,
+            ],
+        )
+        .await?;
+
+    let num_cars = (classification.num_cars as usize).min(classification.classification_data.len());
+    let mut users = Vec::with_capacity(num_cars);
+
+    for (car_idx, result) in classification.classification_data[..num_cars]
+        .iter()
+        .enumerate()
+    {
+        let result_status = result.result_status as i16;
+        let best_lap_time_in_ms = result.best_lap_time_in_ms as i32;
+        let total_race_time = result.total_race_time;
+        let driver_name = driver_names.get(&(car_idx as u8));
+        let disqualified = result_status == RESULT_STATUS_DISQUALIFIED;
+        let adjusted_points = match disqualified {
+            true => 0,
+            false => position_points(&points_table, result.position as i16),
+        };
+
+        let row = transaction
+            .query_opt(
+                &save_result_stmt,
+                &[
+                    &championship_id,
+                    &session_uid,
+                    &(car_idx as i16),
+                    &(result.position as i16),
+                    &(result.num_laps as i16),
+                    &(result.grid_position as i16),
+                    &(result.points as i16),
+                    &(result.num_pit_stops as i16),
+                    &result_status,
+                    &best_lap_time_in_ms,
+                    &total_race_time,
+                    &(result.penalties_time as i16),
+                    &(result.num_penalties as i16),
+                    &disqualified,
+                    &driver_name,
+                    &adjusted_points,
+                ],
+            )
+            .await?;
+
+        if let Some(user_id) = row.and_then(|row| row.get::<_, Option<i32>>("user_id")) {
+            users.push(user_id);
+        }
+    }
+
+    transaction.commit().await?;
+    StatsCache::new(db).delete_all(&users).await?;
+
+    Ok(())
+}
@@ -3,14 +3,21 @@ use crate::{
     config::{constants::*, Database},
     dtos::{F123Data, PacketIds, SectorsLaps, SessionType},
     error::{AppResult, F123Error, SocketError},
-    protos::{packet_header::PacketType, ToProtoMessage},
-    services::f123::packet_batching::PacketBatching,
+    protos::{packet_header::PacketType, socket_control::TopicBatch, ToProtoMessage},
+    services::f123::{
+        incidents::Incident,
+        laps::LapHistory,
+        packet_batching::PacketBatching,
+        records::RecordTracker,
+        results::{save_classification, SessionInfo},
+        TopicSubscribers,
+    },
     FirewallService,
 };
 use ahash::AHashMap;
-use ntex::{rt, util::Bytes};
+use ntex::rt;
 use parking_lot::RwLock;
-use std::{cell::RefCell, sync::Arc, time::Instant};
+use std::{cell::RefCell, rc::Rc, sync::Arc, time::Instant};
 use tokio::{
     net::UdpSocket,
     sync::broadcast::{channel, Receiver, Sender},
@@ -19,11 +26,17 @@ use tokio::{
 };
 use tracing::{error, info};
 
-type ChanelData = Bytes;
-type F123Channel = Arc<Sender<ChanelData>>;
+type ChanelData = TopicBatch;
 type Channels = Arc<RwLock<AHashMap<i32, F123Channel>>>;
 type Sockets = Arc<RwLock<AHashMap<i32, JoinHandle<AppResult<()>>>>>;
 
+// Batches of a championship and the topics its websocket clients are subscribed to
+#[derive(Clone)]
+struct F123Channel {
+    tx: Sender<ChanelData>,
+    subscribers: TopicSubscribers,
+}
+
 #[derive(Clone)]
 pub struct F123Service {
     db_conn: Database,
@@ -56,13 +69,13 @@ impl F123Service {
     pub async fn subscribe_to_championship_events(
         &self,
         championship_id: &i32,
-    ) -> Option<Receiver<ChanelData>> {
+    ) -> Option<(Receiver<ChanelData>, TopicSubscribers)> {
         let channels = self.channels.read();
         let Some(channel) = channels.get(championship_id) else {
             return None;
         };
 
-        Some(channel.subscribe())
+        Some((channel.tx.subscribe(), channel.subscribers.clone()))
     }
 
     pub async fn stop_socket(&self, championship_id: i32) -> AppResult<()> {
@@ -145,19 +158,23 @@ impl F123Service {
             let mut last_car_motion_update = Instant::now();
             let mut last_participants_update = Instant::now();
             let session_type = RefCell::new(None);
+            let mut session_info: Option<SessionInfo> = None;
+            let mut records = RecordTracker::new(&db, *championship_id);
             let close_socket =
                 Self::internal_close(&channels, &sockets, &championship_id, &firewall);
 
             // Session History Data
             let mut last_car_lap_update: AHashMap<u8, Instant> = AHashMap::default();
             let mut car_lap_sector_data: AHashMap<u8, SectorsLaps> = AHashMap::default();
+            let car_saved_laps: Rc<RefCell<AHashMap<(u64, u8), u8>>> = Rc::default();
 
             // Define channel
             // Todo: Instead of having an external counter use `tx.receiver_count()` to get the active open connections
             let (tx, _) = channel::<ChanelData>(100);
+            let subscribers = TopicSubscribers::default();
 
             let cache = F123InsiderCache::new(db.redis.get().await.unwrap(), *championship_id);
-            let mut packet_batching = PacketBatching::new(tx.clone(), cache);
+            let mut packet_batching = PacketBatching::new(tx.clone(), subscribers.clone(), cache);
 
             let Ok(socket) = UdpSocket::bind(format!("{SOCKET_HOST}:{port}")).await else {
                 error!("There was an error binding to the socket for championship: {championship_id:?}");
@@ -168,7 +185,7 @@ impl F123Service {
 
             {
                 let mut channels = channels.write();
-                channels.insert(*championship_id, Arc::new(tx));
+                channels.insert(*championship_id, F123Channel { tx, subscribers });
             }
 
             firewall.open(*championship_id, port).await?;
@@ -236,12 +253,10 @@ impl F123Service {
 
                         match packet {
                             F123Data::Motion(motion_data) => {
-                                let packet = motion_data
-                                    .convert(PacketType::CarMotion)
-                                    .ok_or(F123Error::Encoding)?;
+                                let motion = motion_data.to_proto().ok_or(F123Error::Encoding)?;
 
                                 last_car_motion_update = now;
-                                packet_batching.push_and_check(packet).await?;
+                                packet_batching.push_motion_and_check(motion).await?;
                             }
 
                             F123Data::Session(session_data) => {
@@ -265,6 +280,11 @@ impl F123Service {
 
                                 let _ = session_type.borrow_mut().insert(converted_session_type);
 
+                                session_info = Some(SessionInfo {
+                                    session_type: session_data.session_type as i16,
+                                    track_id: session_data.track_id as i16,
+                                });
+
                                 let packet = session_data
                                     .convert(PacketType::SessionData)
                                     .ok_or(F123Error::Encoding)?;
@@ -278,11 +298,30 @@ impl F123Service {
                                     .convert(PacketType::Participants)
                                     .ok_or(F123Error::Encoding)?;
 
+                                records.set_driver_names(participants_data);
+
                                 last_participants_update = now;
                                 packet_batching.push_and_check(packet).await?;
                             }
 
                             F123Data::Event(event_data) => {
+                                // Saved in the background so the socket keeps reading packets
+                                if let Some(incident) = Incident::from_event(event_data) {
+                                    let db = db.clone();
+                                    let championship_id = *championship_id;
+
+                                    rt::spawn(async move {
+                                        if let Err(e) = incident
+                                            .save(&db, championship_id, session_id as i64)
+                                            .await
+                                        {
+                                            error!("Error saving incident for championship: {championship_id}: {e}");
+                                        }
+                                    });
+                                }
+
+                                records.fastest_lap(event_data);
+
                                 let Some(packet) = event_data.convert(PacketType::EventData) else {
                                     continue;
                                 };
@@ -291,6 +330,21 @@ impl F123Service {
                             }
 
                             F123Data::SessionHistory(session_history) => {
+                                if let Some(info) = session_info {
+                                    match records
+                                        .check_history(session_id, info.track_id, session_history)
+                                        .await
+                                    {
+                                        Ok(Some(packet)) => {
+                                            packet_batching.push_and_check(packet).await?
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => {
+                                            error!("Error checking records for championship: {championship_id:?}: {e}")
+                                        }
+                                    }
+                                }
+
                                 let last_update = last_car_lap_update
                                     .entry(session_history.car_idx)
                                     .or_insert(now);
@@ -323,7 +377,45 @@ impl F123Service {
                                     *last_update = now;
                                     *last_sectors = sectors;
 
-                                    packet_batching.push_and_check(packet).await?;
+                                    let car = (session_id, session_history.car_idx);
+                                    let saved_laps = car_saved_laps
+                                        .borrow()
+                                        .get(&car)
+                                        .copied()
+                                        .unwrap_or_default();
+
+                                    // The counter only advances once the laps are stored, the ones of a
+                                    // failed save are sent again with the next update of the car
+                                    if let Some(history) =
+                                        LapHistory::new(session_history, saved_laps)
+                                    {
+                                        let db = db.clone();
+                                        let championship_id = *championship_id;
+                                        let car_saved_laps = car_saved_laps.clone();
+
+                                        rt::spawn(async move {
+                                            match history
+                                                .save(&db, championship_id, session_id as i64)
+                                                .await
+                                            {
+                                                Ok(()) => {
+                                                    let mut car_saved_laps =
+                                                        car_saved_laps.borrow_mut();
+                                                    let saved_laps =
+                                                        car_saved_laps.entry(car).or_default();
+                                                    *saved_laps =
+                                                        (*saved_laps).max(history.completed());
+                                                }
+                                                Err(e) => {
+                                                    error!("Error saving lap history for championship: {championship_id}: {e}")
+                                                }
+                                            }
+                                        });
+                                    }
+
+                                    packet_batching
+                                        .push_car_and_check(packet, session_history.car_idx)
+                                        .await?;
                                 }
                             }
 
@@ -346,6 +438,40 @@ impl F123Service {
                                     }
                                 }
 
+                                // Sessions without a session packet can't be classified, they are only forwarded
+                                if let Some(info) = session_info {
+                                    if let Err(e) = save_classification(
+                                        &db,
+                                        *championship_id,
+                                        session_id as i64,
+                                        info,
+                                        records.driver_names(),
+                                        classification_data,
+                                    )
+                                    .await
+                                    {
+                                        error!("Error saving classification for championship: {championship_id:?}: {e}");
+                                    }
+
+                                    match records
+                                        .check_classification(
+                                            session_id,
+                                            info.track_id,
+                                            classification_data,
+                                        )
+                                        .await
+                                    {
+                                        Ok(packets) => {
+                                            for packet in packets {
+                                                packet_batching.push_and_check(packet).await?;
+                                            }
+                                        }
+                                        Err(e) => {
+                                            error!("Error checking records for championship: {championship_id:?}: {e}")
+                                        }
+                                    }
+                                }
+
                                 // If session type is race save all session data in the database and close the socket
                                 // Todo: this should be called after saving all data in the database
                                 packet_batching.final_send(packet).await?;
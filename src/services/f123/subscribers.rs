@@ -0,0 +1,50 @@
+use crate::protos::socket_control::TopicKey;
+use ahash::AHashMap;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+// Websocket clients subscribed to each topic of a championship, the batches of
+// topics without subscribers aren't encoded
+#[derive(Clone, Default)]
+pub struct TopicSubscribers {
+    keys: Arc<RwLock<AHashMap<TopicKey, usize>>>,
+}
+
+impl TopicSubscribers {
+    #[inline(always)]
+    pub fn add(&self, key: TopicKey) {
+        *self.keys.write().entry(key).or_default() += 1;
+    }
+
+    #[inline(always)]
+    pub fn remove(&self, key: &TopicKey) {
+        let mut keys = self.keys.write();
+
+        if let Some(count) = keys.get_mut(key) {
+            *count -= 1;
+
+            if *count == 0 {
+                keys.remove(key);
+            }
+        }
+    }
+
+    // Car batches are also wanted by the clients subscribed to the topic of every car
+    #[inline(always)]
+    pub fn wanted(&self, key: &TopicKey) -> bool {
+        let keys = self.keys.read();
+
+        if key.car_idx.is_some() {
+            let all_cars = TopicKey {
+                topic: key.topic,
+                car_idx: None,
+            };
+
+            if keys.contains_key(&all_cars) {
+                return true;
+            }
+        }
+
+        keys.contains_key(key)
+    }
+}
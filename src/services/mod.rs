@@ -1,15 +1,31 @@
+mod api_key;
 mod championship;
 mod email;
 mod f123;
 mod firewall;
+mod oauth;
+mod rate_limit;
+mod result;
+mod round;
 mod saved_session;
+mod stewarding;
+mod team;
 mod token;
+mod two_factor;
 mod user;
 
+pub(crate) use api_key::*;
 pub(crate) use championship::*;
 pub(crate) use email::*;
 pub(crate) use f123::*;
 pub(crate) use firewall::*;
+pub(crate) use oauth::*;
+pub(crate) use rate_limit::*;
+pub(crate) use result::*;
+pub(crate) use round::*;
 pub(crate) use saved_session::*;
+pub(crate) use stewarding::*;
+pub(crate) use team::*;
 pub(crate) use token::*;
+pub(crate) use two_factor::*;
 pub(crate) use user::*;
@@ -0,0 +1,205 @@
+use super::{UserService, UserServiceTrait};
+use crate::{
+    cache::RedisCache,
+    config::{constants::*, Database},
+    dtos::{ExternalAccount, OAuthOutcome, OAuthState},
+    entity::{Provider, User},
+    error::{AppResult, OAuthError, UserError},
+    repositories::{IdentityRepository, OAuthRepository, UserRepository, UserRepositoryTrait},
+};
+use rand::{distr::Alphanumeric, Rng};
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Clone)]
+pub struct OAuthService {
+    db: Database,
+    cache: RedisCache,
+    oauth_repository: OAuthRepository,
+    identity_repository: IdentityRepository,
+    user_repository: UserRepository,
+    user_service: UserService,
+}
+
+impl OAuthService {
+    pub fn new(db_conn: &Database, cache: &RedisCache, user_service: &UserService) -> Self {
+        Self {
+            db: db_conn.clone(),
+            cache: cache.clone(),
+            oauth_repository: OAuthRepository::new(),
+            identity_repository: IdentityRepository::new(db_conn),
+            user_repository: UserRepository::new(db_conn, cache),
+            user_service: user_service.clone(),
+        }
+    }
+
+    // The user is only set to link the provider to an account already logged in,
+    // otherwise the fingerprint of the client logging in is kept for the code exchange
+    pub async fn authorize_url(
+        &self,
+        provider: &Provider,
+        user_id: Option<i32>,
+        fingerprint: Option<&str>,
+    ) -> AppResult<String> {
+        let oauth_provider = self.oauth_repository.provider(provider)?;
+        let state = random_string(OAUTH_STATE_LENGTH);
+
+        let oauth_state = OAuthState {
+            provider: *provider,
+            verifier: random_string(PKCE_VERIFIER_LENGTH),
+            nonce: random_string(OAUTH_STATE_LENGTH),
+            user_id,
+            fingerprint: fingerprint.map(str::to_owned),
+        };
+
+        let url = oauth_provider.authorize_url(&state, &oauth_state)?;
+        self.cache
+            .token
+            .set_oauth_state(&state, &oauth_state)
+            .await?;
+
+        Ok(url)
+    }
+
+    pub async fn callback(
+        &self,
+        provider: &Provider,
+        query: &HashMap<String, String>,
+    ) -> AppResult<OAuthOutcome> {
+        let oauth_provider = self.oauth_repository.provider(provider)?;
+
+        let Some(state) = query.get("state") else {
+            Err(OAuthError::InvalidState)?
+        };
+
+        let Some(oauth_state) = self.cache.token.take_oauth_state(state).await? else {
+            Err(OAuthError::InvalidState)?
+        };
+
+        if oauth_state.provider != *provider {
+            Err(OAuthError::InvalidState)?
+        }
+
+        let account = oauth_provider.account(query, &oauth_state).await?;
+
+        if let Some(user_id) = oauth_state.user_id {
+            self.link(&user_id, &account).await?;
+            return Ok(OAuthOutcome::Linked);
+        }
+
+        let Some(fingerprint) = oauth_state.fingerprint else {
+            Err(OAuthError::InvalidState)?
+        };
+
+        let user_id = self.login(&account).await?;
+        Ok(OAuthOutcome::Login {
+            user_id,
+            fingerprint,
+        })
+    }
+
+    // Linked accounts log in directly. Otherwise an active user with the same verified email
+    // gets the account linked, that's also how users created with google before the
+    // identities were saved get theirs
+    async fn login(&self, account: &ExternalAccount) -> AppResult<i32> {
+        if let Some(identity) = self
+            .identity_repository
+            .find(&account.provider, &account.subject)
+            .await?
+        {
+            return Ok(identity.user_id);
+        }
+
+        let Some(email) = account.email.as_ref().filter(|_| account.email_verified) else {
+            Err(OAuthError::EmailRequired)?
+        };
+
+        let Some(user) = self.user_repository.find_by_email(email).await? else {
+            return self.user_service.create_external(account).await;
+        };
+
+        if !user.active {
+            Err(UserError::NotVerified)?
+        }
+
+        self.link(&user.id, account).await?;
+        Ok(user.id)
+    }
+
+    // Linking the same account again does nothing, a user can have one account per provider
+    async fn link(&self, user_id: &i32, account: &ExternalAccount) -> AppResult<()> {
+        let conn = self.db.pg.get().await?;
+
+        let link_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO user_identities (provider, subject, user_id, email)
+                    VALUES ($1,$2,$3,$4)
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .await?;
+
+        let linked = conn
+            .execute(
+                &link_stmt,
+                &[&account.provider, &account.subject, user_id, &account.email],
+            )
+            .await?;
+
+        if linked == 0 {
+            let identity = self
+                .identity_repository
+                .find(&account.provider, &account.subject)
+                .await?;
+
+            if identity.is_none_or(|identity| identity.user_id != *user_id) {
+                Err(OAuthError::AlreadyLinked)?
+            }
+
+            return Ok(());
+        }
+
+        info!("User {user_id} linked {}", account.provider.name());
+        Ok(())
+    }
+
+    // Users without password need another provider left to login
+    pub async fn unlink(&self, user: &User, provider: &Provider) -> AppResult<()> {
+        let identities = self.identity_repository.user_identities(&user.id).await?;
+
+        if !identities
+            .iter()
+            .any(|identity| identity.provider == *provider)
+        {
+            Err(OAuthError::NotLinked)?
+        }
+
+        if user.password.is_none() && identities.len() == 1 {
+            Err(OAuthError::LastLoginMethod)?
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        let unlink_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM user_identities
+                    WHERE user_id = $1 AND provider = $2
+                "#,
+            )
+            .await?;
+
+        conn.execute(&unlink_stmt, &[&user.id, provider]).await?;
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn random_string(length: usize) -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,120 @@
+use super::EmailService;
+use crate::{
+    cache::RedisCache,
+    config::{constants::*, RateLimitRule},
+    dtos::{AccountLocked, EmailUser},
+    entity::User,
+    error::{AppResult, RateLimitError},
+};
+use tracing::{error, warn};
+
+#[derive(Clone)]
+pub struct RateLimitService {
+    cache: RedisCache,
+    email_service: EmailService,
+}
+
+impl RateLimitService {
+    pub fn new(cache: &RedisCache) -> Self {
+        Self {
+            cache: cache.clone(),
+            email_service: EmailService::new(),
+        }
+    }
+
+    // The key is the ip or the account the request is for
+    pub async fn check(&self, rule: &RateLimitRule, key: &str) -> AppResult<()> {
+        if let Some(retry_after) = self.cache.rate_limit.hit(rule, key).await? {
+            Err(RateLimitError::TooManyRequests(retry_after.div_ceil(1000)))?
+        }
+
+        Ok(())
+    }
+
+    // Checked before the password so a locked account doesn't cost a bcrypt hash
+    pub async fn check_lockout(&self, user_id: &i32) -> AppResult<()> {
+        if let Some(retry_after) = self.cache.rate_limit.lockout(user_id).await? {
+            Err(RateLimitError::AccountLocked(retry_after))?
+        }
+
+        Ok(())
+    }
+
+    // The user is told by email every time the account gets locked
+    pub async fn login_failed(&self, user: &User) -> AppResult<()> {
+        let failed_logins = self.cache.rate_limit.failed_login(&user.id).await?;
+
+        let Some(duration) = lockout_duration(failed_logins) else {
+            return Ok(());
+        };
+
+        self.cache.rate_limit.lock(&user.id, duration).await?;
+        warn!("User {} locked for {duration}s", user.id);
+
+        let template = AccountLocked {
+            failed_logins,
+            locked_minutes: duration / 60,
+        };
+
+        let send_mail = self.email_service.send_mail(
+            EmailUser {
+                username: &user.username,
+                email: &user.email,
+            },
+            "Account Locked",
+            template,
+        );
+
+        // The lockout is already in place, a failed email shouldn't hide it
+        if let Err(e) = send_mail.await {
+            error!("Error sending lockout email: {e}");
+        }
+
+        Ok(())
+    }
+
+    pub async fn login_succeeded(&self, user_id: &i32) -> AppResult<()> {
+        self.cache.rate_limit.reset_failed_logins(user_id).await
+    }
+}
+
+// Every threshold of failed logins locks the account, doubling the last lockout
+fn lockout_duration(failed_logins: i64) -> Option<u64> {
+    if failed_logins == 0 || failed_logins % LOCKOUT_THRESHOLD != 0 {
+        return None;
+    }
+
+    let lockouts = (failed_logins / LOCKOUT_THRESHOLD - 1).min(32) as u32;
+
+    Some(
+        LOCKOUT_BASE_DURATION
+            .saturating_mul(2u64.saturating_pow(lockouts))
+            .min(LOCKOUT_MAX_DURATION),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lockout_duration;
+    use crate::config::constants::{
+        LOCKOUT_BASE_DURATION, LOCKOUT_MAX_DURATION, LOCKOUT_THRESHOLD,
+    };
+
+    #[test]
+    fn test_lockout_duration() {
+        assert_eq!(lockout_duration(LOCKOUT_THRESHOLD - 1), None);
+        assert_eq!(
+            lockout_duration(LOCKOUT_THRESHOLD),
+            Some(LOCKOUT_BASE_DURATION)
+        );
+        assert_eq!(lockout_duration(LOCKOUT_THRESHOLD + 1), None);
+        assert_eq!(
+            lockout_duration(LOCKOUT_THRESHOLD * 3),
+            Some(LOCKOUT_BASE_DURATION * 4)
+        );
+        assert_eq!(
+            lockout_duration(LOCKOUT_THRESHOLD * 100),
+            Some(LOCKOUT_MAX_DURATION)
+        );
+    }
+}
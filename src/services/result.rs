@@ -0,0 +1,441 @@
+use crate::{
+    cache::RedisCache,
+    config::Database,
+    dtos::{ApplyAdjustment, AssignDriver},
+    entity::{
+        AdjustmentKind, FromRow, ResultAdjustment, SessionResult, RACE_SESSIONS,
+        RESULT_STATUS_DISQUALIFIED, RESULT_STATUS_FINISHED,
+    },
+    error::{AppError, AppResult, ChampionshipError, ResultError},
+    repositories::ChampionshipRepository,
+};
+use deadpool_postgres::Transaction;
+use std::cmp::Ordering;
+
+#[derive(Clone)]
+pub struct ResultService {
+    db: Database,
+    cache: RedisCache,
+    championship_repository: ChampionshipRepository,
+}
+
+impl ResultService {
+    pub async fn new(db_conn: &Database, cache: &RedisCache) -> Self {
+        Self {
+            db: db_conn.clone(),
+            cache: cache.clone(),
+            championship_repository: ChampionshipRepository::new(db_conn, cache).await,
+        }
+    }
+
+    pub async fn apply_adjustment(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+        user_id: &i32,
+        form: &ApplyAdjustment,
+    ) -> AppResult<()> {
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let results =
+            Self::add_adjustment(&transaction, championship_id, session_uid, user_id, form).await?;
+
+        transaction.commit().await?;
+
+        // Every position can change, so the stats of all the drivers of the session
+        let users = results
+            .iter()
+            .filter_map(|result| result.user_id)
+            .collect::<Vec<i32>>();
+
+        self.cache.stats.delete_all(&users).await?;
+
+        Ok(())
+    }
+
+    // Every adjustment is kept, the classification is rebuilt from the game result
+    // and all of them so the order they were applied in is respected.
+    // Also used by the stewards to apply the sanctions of upheld protests
+    pub(crate) async fn add_adjustment(
+        transaction: &Transaction<'_>,
+        championship_id: &i32,
+        session_uid: &i64,
+        user_id: &i32,
+        form: &ApplyAdjustment,
+    ) -> AppResult<Vec<SessionResult>> {
+        let value = match (form.kind, form.value) {
+            (AdjustmentKind::TimePenalty, Some(value)) if value > 0 => value,
+            (AdjustmentKind::PositionChange, Some(value)) if value != 0 => value,
+            (AdjustmentKind::Disqualification, None) => 0,
+            _ => Err(ResultError::InvalidAdjustment)?,
+        };
+
+        let (
+            points_table_stmt,
+            lock_results_stmt,
+            add_adjustment_stmt,
+            adjustments_stmt,
+            update_result_stmt,
+        ) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    SELECT s.session_type, c.points_table FROM championship_sessions s
+                    JOIN championship c ON c.id = s.championship_id
+                    WHERE s.championship_id = $1 AND s.session_uid = $2
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    SELECT * FROM session_results
+                    WHERE championship_id = $1 AND session_uid = $2
+                    ORDER BY position
+                    FOR UPDATE
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO result_adjustments (championship_id, session_uid, car_idx, kind, value, reason, applied_by)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7)
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    SELECT * FROM result_adjustments
+                    WHERE championship_id = $1 AND session_uid = $2
+                    ORDER BY id
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    UPDATE session_results
+                    SET adjusted_position = $1, adjusted_penalties_time = $2, adjusted_points = $3, disqualified = $4,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE championship_id = $5 AND session_uid = $6 AND car_idx = $7
+                "#,
+            )
+        )?;
+
+        let Some(session) = transaction
+            .query_opt(&points_table_stmt, &[championship_id, session_uid])
+            .await?
+        else {
+            Err(ResultError::SessionNotFound)?
+        };
+
+        // Only races score points
+        let points_table: Vec<i16> = match session.try_get("session_type")? {
+            session_type if RACE_SESSIONS.contains(&session_type) => {
+                session.try_get("points_table")?
+            }
+            _ => Vec::new(),
+        };
+
+        let mut results = transaction
+            .query(&lock_results_stmt, &[championship_id, session_uid])
+            .await?
+            .iter()
+            .map(SessionResult::from_row)
+            .collect::<Result<Vec<SessionResult>, AppError>>()?;
+
+        if results.is_empty() {
+            Err(ResultError::SessionNotFound)?
+        }
+
+        if !results.iter().any(|result| result.car_idx == form.car_idx) {
+            Err(ResultError::CarNotFound)?
+        }
+
+        transaction
+            .execute(
+                &add_adjustment_stmt,
+                &[
+                    championship_id,
+                    session_uid,
+                    &form.car_idx,
+                    &form.kind,
+                    &value,
+                    &form.reason,
+                    user_id,
+                ],
+            )
+            .await?;
+
+        let adjustments = transaction
+            .query(&adjustments_stmt, &[championship_id, session_uid])
+            .await?
+            .iter()
+            .map(ResultAdjustment::from_row)
+            .collect::<Result<Vec<ResultAdjustment>, AppError>>()?;
+
+        classify(&mut results, &adjustments, &points_table);
+
+        for result in &results {
+            transaction
+                .execute(
+                    &update_result_stmt,
+                    &[
+                        &result.adjusted_position,
+                        &result.adjusted_penalties_time,
+                        &result.adjusted_points,
+                        &result.disqualified,
+                        championship_id,
+                        session_uid,
+                        &result.car_idx,
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(results)
+    }
+
+    // Links the car to a member when the name in the game didn't match any username,
+    // a member can only drive one car of the session
+    pub async fn assign_driver(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+        form: &AssignDriver,
+    ) -> AppResult<()> {
+        if self
+            .championship_repository
+            .role(championship_id, &form.user_id)
+            .await?
+            .is_none()
+        {
+            Err(ChampionshipError::NotMember)?
+        }
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let (lock_results_stmt, assign_driver_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    SELECT * FROM session_results
+                    WHERE championship_id = $1 AND session_uid = $2
+                    FOR UPDATE
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    UPDATE session_results
+                    SET user_id = $1, updated_at = CURRENT_TIMESTAMP
+                    WHERE championship_id = $2 AND session_uid = $3 AND car_idx = $4
+                "#,
+            )
+        )?;
+
+        let results = transaction
+            .query(&lock_results_stmt, &[championship_id, session_uid])
+            .await?
+            .iter()
+            .map(SessionResult::from_row)
+            .collect::<Result<Vec<SessionResult>, AppError>>()?;
+
+        if results.is_empty() {
+            Err(ResultError::SessionNotFound)?
+        }
+
+        let Some(result) = results.iter().find(|result| result.car_idx == form.car_idx) else {
+            Err(ResultError::CarNotFound)?
+        };
+
+        if results
+            .iter()
+            .any(|other| other.car_idx != form.car_idx && other.user_id == Some(form.user_id))
+        {
+            Err(ResultError::DriverAlreadyAssigned)?
+        }
+
+        transaction
+            .execute(
+                &assign_driver_stmt,
+                &[&form.user_id, championship_id, session_uid, &form.car_idx],
+            )
+            .await?;
+
+        transaction.commit().await?;
+
+        let mut users = vec![form.user_id];
+        users.extend(result.user_id);
+        self.cache.stats.delete_all(&users).await?;
+
+        Ok(())
+    }
+}
+
+// Finished cars are ordered by laps completed and race time plus penalties, the rest keep the
+// order reported by the game behind them and the disqualified ones go last.
+// Position changes are applied at the end, in the order they were made and the points
+// are taken from the points table with the final position
+fn classify(results: &mut [SessionResult], adjustments: &[ResultAdjustment], points_table: &[i16]) {
+    for result in results.iter_mut() {
+        let car_adjustments = adjustments
+            .iter()
+            .filter(|adjustment| adjustment.car_idx == result.car_idx);
+
+        result.adjusted_penalties_time = result.penalties_time;
+        result.disqualified = result.result_status == RESULT_STATUS_DISQUALIFIED;
+
+        for adjustment in car_adjustments {
+            match adjustment.kind {
+                AdjustmentKind::TimePenalty => {
+                    result.adjusted_penalties_time = result
+                        .adjusted_penalties_time
+                        .saturating_add(adjustment.value)
+                }
+                AdjustmentKind::Disqualification => result.disqualified = true,
+                AdjustmentKind::PositionChange => {}
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..results.len()).collect();
+    order.sort_by(|&a, &b| compare(&results[a], &results[b]));
+
+    for adjustment in adjustments
+        .iter()
+        .filter(|adjustment| adjustment.kind == AdjustmentKind::PositionChange)
+    {
+        let Some(from) = order
+            .iter()
+            .position(|&idx| results[idx].car_idx == adjustment.car_idx)
+        else {
+            continue;
+        };
+
+        let car = order.remove(from);
+        let to = (from as i32 + adjustment.value as i32).clamp(0, order.len() as i32) as usize;
+        order.insert(to, car);
+    }
+
+    for (position, idx) in order.into_iter().enumerate() {
+        let result = &mut results[idx];
+        result.adjusted_position = position as i16 + 1;
+        result.adjusted_points = match result.disqualified {
+            true => 0,
+            false => position_points(points_table, result.adjusted_position),
+        };
+    }
+}
+
+// Positions out of the points table don't score
+#[inline(always)]
+pub(crate) fn position_points(points_table: &[i16], position: i16) -> i16 {
+    usize::try_from(position - 1)
+        .ok()
+        .and_then(|idx| points_table.get(idx))
+        .copied()
+        .unwrap_or(0)
+}
+
+#[inline(always)]
+fn compare(a: &SessionResult, b: &SessionResult) -> Ordering {
+    let rank = |result: &SessionResult| match result {
+        result if result.disqualified => 2,
+        result if result.result_status == RESULT_STATUS_FINISHED => 0,
+        _ => 1,
+    };
+
+    rank(a).cmp(&rank(b)).then_with(|| {
+        if rank(a) != 0 {
+            return a.position.cmp(&b.position);
+        }
+
+        let total_time =
+            |result: &SessionResult| result.total_race_time + result.adjusted_penalties_time as f64;
+
+        b.num_laps
+            .cmp(&a.num_laps)
+            .then_with(|| total_time(a).total_cmp(&total_time(b)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classify;
+    use crate::entity::{AdjustmentKind, ResultAdjustment, SessionResult};
+    use chrono::Utc;
+
+    fn result(car_idx: i16, position: i16, num_laps: i16, total_race_time: f64) -> SessionResult {
+        SessionResult {
+            session_uid: 1,
+            car_idx,
+            position,
+            num_laps,
+            grid_position: position,
+            points: 0,
+            num_pit_stops: 1,
+            result_status: 3,
+            best_lap_time_in_ms: 90_000,
+            total_race_time,
+            penalties_time: 0,
+            num_penalties: 0,
+            adjusted_position: position,
+            adjusted_penalties_time: 0,
+            adjusted_points: 0,
+            disqualified: false,
+            driver_name: None,
+            user_id: None,
+        }
+    }
+
+    fn adjustment(car_idx: i16, kind: AdjustmentKind, value: i16) -> ResultAdjustment {
+        ResultAdjustment {
+            id: 1,
+            session_uid: 1,
+            car_idx,
+            kind,
+            value,
+            reason: String::from("Causing a collision"),
+            applied_by: Some(600000000),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn positions(results: &[SessionResult]) -> Vec<(i16, i16)> {
+        results
+            .iter()
+            .map(|result| (result.car_idx, result.adjusted_position))
+            .collect()
+    }
+
+    const POINTS_TABLE: [i16; 3] = [25, 18, 15];
+
+    #[test]
+    fn test_classification_adjustments() {
+        let mut results = vec![
+            result(0, 1, 50, 5000.0),
+            result(1, 2, 50, 5003.0),
+            result(2, 3, 50, 5010.0),
+            result(3, 4, 49, 5100.0),
+        ];
+
+        classify(
+            &mut results,
+            &[adjustment(0, AdjustmentKind::TimePenalty, 5)],
+            &POINTS_TABLE,
+        );
+        assert_eq!(positions(&results), vec![(0, 2), (1, 1), (2, 3), (3, 4)]);
+        assert_eq!(results[0].position, 1);
+        assert_eq!(results[0].adjusted_penalties_time, 5);
+        assert_eq!(results[0].adjusted_points, 18);
+        assert_eq!(results[1].adjusted_points, 25);
+
+        classify(
+            &mut results,
+            &[
+                adjustment(0, AdjustmentKind::TimePenalty, 5),
+                adjustment(1, AdjustmentKind::Disqualification, 0),
+                adjustment(3, AdjustmentKind::PositionChange, -1),
+            ],
+            &POINTS_TABLE,
+        );
+        assert_eq!(positions(&results), vec![(0, 1), (1, 4), (2, 3), (3, 2)]);
+        assert!(results[1].disqualified);
+        assert_eq!(results[1].adjusted_points, 0);
+        assert_eq!(results[3].adjusted_points, 18);
+    }
+}
@@ -0,0 +1,464 @@
+use crate::{
+    config::Database,
+    dtos::{CreateRound, UpdateRound},
+    entity::{
+        GridPenalty, GridSlot, Round, RoundGrid, SessionResult, WeekendFormat, QUALIFYING_SESSIONS,
+        RACE_SESSIONS,
+    },
+    error::{AppResult, CommonError, ResultError, RoundError},
+    repositories::{ResultRepository, RoundRepository, StewardingRepository},
+};
+use postgres_types::ToSql;
+use std::ops::RangeInclusive;
+
+#[derive(Clone)]
+pub struct RoundService {
+    db: Database,
+    round_repository: RoundRepository,
+    result_repository: ResultRepository,
+    stewarding_repository: StewardingRepository,
+}
+
+impl RoundService {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            db: db_conn.clone(),
+            round_repository: RoundRepository::new(db_conn),
+            result_repository: ResultRepository::new(db_conn),
+            stewarding_repository: StewardingRepository::new(db_conn),
+        }
+    }
+
+    pub async fn create(&self, championship_id: &i32, form: &CreateRound) -> AppResult<()> {
+        if form.format == WeekendFormat::ReverseGrid && form.reverse_grid_count.is_none() {
+            Err(RoundError::InvalidFormat)?
+        }
+
+        if self
+            .round_repository
+            .find_by_num(championship_id, &form.round_num)
+            .await?
+            .is_some()
+        {
+            Err(RoundError::AlreadyExists)?
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        let create_round_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO championship_rounds (championship_id, round_num, track_id, format, reverse_grid_count)
+                    VALUES ($1,$2,$3,$4,$5)
+                "#,
+            )
+            .await?;
+
+        conn.execute(
+            &create_round_stmt,
+            &[
+                championship_id,
+                &form.round_num,
+                &form.track_id,
+                &form.format,
+                &form.reverse_grid_count,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update(
+        &self,
+        championship_id: &i32,
+        id: &i32,
+        form: &UpdateRound,
+    ) -> AppResult<()> {
+        let round = self.championship_round(championship_id, id).await?;
+
+        let format = form.format.unwrap_or(round.format);
+        let reverse_grid_count = form.reverse_grid_count.or(round.reverse_grid_count);
+
+        if format == WeekendFormat::ReverseGrid && reverse_grid_count.is_none() {
+            Err(RoundError::InvalidFormat)?
+        }
+
+        // Only sprint rounds have sprint sessions
+        if format != WeekendFormat::Sprint
+            && (form.sprint_qualifying_session_uid.is_some() || form.sprint_session_uid.is_some())
+        {
+            Err(RoundError::InvalidFormat)?
+        }
+
+        if let Some(session_uid) = &form.qualifying_session_uid {
+            self.check_session(championship_id, session_uid, QUALIFYING_SESSIONS)
+                .await?;
+        }
+
+        if let Some(session_uid) = &form.race_session_uid {
+            self.check_session(championship_id, session_uid, RACE_SESSIONS)
+                .await?;
+        }
+
+        if let Some(session_uid) = &form.sprint_qualifying_session_uid {
+            self.check_session(championship_id, session_uid, QUALIFYING_SESSIONS)
+                .await?;
+        }
+
+        if let Some(session_uid) = &form.sprint_session_uid {
+            self.check_session(championship_id, session_uid, RACE_SESSIONS)
+                .await?;
+        }
+
+        let (query, params) = {
+            let mut counter = 1;
+            let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+            let mut query = String::from("UPDATE championship_rounds SET ");
+
+            if let Some(format) = &form.format {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" format = ${}", counter));
+                params.push(format);
+                counter += 1;
+            }
+
+            if let Some(reverse_grid_count) = &form.reverse_grid_count {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" reverse_grid_count = ${}", counter));
+                params.push(reverse_grid_count);
+                counter += 1;
+            }
+
+            if let Some(qualifying_session_uid) = &form.qualifying_session_uid {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" qualifying_session_uid = ${}", counter));
+                params.push(qualifying_session_uid);
+                counter += 1;
+            }
+
+            if let Some(race_session_uid) = &form.race_session_uid {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" race_session_uid = ${}", counter));
+                params.push(race_session_uid);
+                counter += 1;
+            }
+
+            if let Some(sprint_qualifying_session_uid) = &form.sprint_qualifying_session_uid {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" sprint_qualifying_session_uid = ${}", counter));
+                params.push(sprint_qualifying_session_uid);
+                counter += 1;
+            }
+
+            if let Some(sprint_session_uid) = &form.sprint_session_uid {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" sprint_session_uid = ${}", counter));
+                params.push(sprint_session_uid);
+                counter += 1;
+            }
+
+            if counter == 1 {
+                Err(CommonError::NotValidUpdate)?
+            }
+
+            query.push_str(&format!(
+                ", updated_at = CURRENT_TIMESTAMP WHERE id = ${}",
+                counter
+            ));
+            params.push(id);
+
+            (query, params)
+        };
+
+        let conn = self.db.pg.get().await?;
+        let update_round_stmt = conn.prepare_cached(&query).await?;
+        conn.execute(&update_round_stmt, &params).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, championship_id: &i32, id: &i32) -> AppResult<()> {
+        self.championship_round(championship_id, id).await?;
+
+        let conn = self.db.pg.get().await?;
+
+        let delete_round_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM championship_rounds WHERE id = $1
+                "#,
+            )
+            .await?;
+
+        conn.execute(&delete_round_stmt, &[id]).await?;
+
+        Ok(())
+    }
+
+    // Grids of the feature race and the sprint race of sprint rounds
+    pub async fn grid(&self, championship_id: &i32, id: &i32) -> AppResult<RoundGrid> {
+        let round = self.championship_round(championship_id, id).await?;
+
+        let sprint_sessions = match round.format {
+            WeekendFormat::Sprint => (
+                round.sprint_qualifying_session_uid,
+                round.sprint_session_uid,
+            ),
+            _ => (None, None),
+        };
+
+        let (race, sprint) = tokio::try_join!(
+            self.race_grid(
+                championship_id,
+                &round,
+                round.qualifying_session_uid,
+                round.race_session_uid
+            ),
+            self.race_grid(
+                championship_id,
+                &round,
+                sprint_sessions.0,
+                sprint_sessions.1
+            )
+        )?;
+
+        if race.is_none() && sprint.is_none() {
+            Err(RoundError::MissingSessions)?
+        }
+
+        Ok(RoundGrid { race, sprint })
+    }
+
+    // Expected grid derived from the qualifying classification and the grid drops of the
+    // stewards next to the grid reported in the race, missing until both sessions are linked
+    async fn race_grid(
+        &self,
+        championship_id: &i32,
+        round: &Round,
+        qualifying_session_uid: Option<i64>,
+        race_session_uid: Option<i64>,
+    ) -> AppResult<Option<Vec<GridSlot>>> {
+        let (Some(qualifying_session_uid), Some(race_session_uid)) =
+            (qualifying_session_uid, race_session_uid)
+        else {
+            return Ok(None);
+        };
+
+        let (qualifying, race, grid_penalties) = tokio::try_join!(
+            self.result_repository
+                .results(championship_id, &qualifying_session_uid),
+            self.result_repository
+                .results(championship_id, &race_session_uid),
+            self.stewarding_repository
+                .grid_penalties(championship_id, &race_session_uid)
+        )?;
+
+        if qualifying.is_empty() || race.is_empty() {
+            Err(ResultError::SessionNotFound)?
+        }
+
+        let expected = derive_grid(
+            round.format,
+            round.reverse_grid_count,
+            &qualifying,
+            &grid_penalties,
+        );
+
+        Ok(Some(compare_grid(&expected, &race)))
+    }
+
+    async fn check_session(
+        &self,
+        championship_id: &i32,
+        session_uid: &i64,
+        session_types: RangeInclusive<i16>,
+    ) -> AppResult<()> {
+        let Some(session) = self
+            .result_repository
+            .session(championship_id, session_uid)
+            .await?
+        else {
+            Err(RoundError::InvalidSession)?
+        };
+
+        if !session_types.contains(&session.session_type) {
+            Err(RoundError::InvalidSession)?
+        }
+
+        Ok(())
+    }
+
+    // Rounds can only be managed through the championship they belong to
+    async fn championship_round(&self, championship_id: &i32, id: &i32) -> AppResult<Round> {
+        let Some(round) = self.round_repository.find(id).await? else {
+            Err(RoundError::NotFound)?
+        };
+
+        if round.championship_id != *championship_id {
+            Err(RoundError::NotFound)?
+        }
+
+        Ok(round)
+    }
+}
+
+// Cars in grid order, the qualifying results have to be ordered by their adjusted position.
+// Grid drops are applied after the reverse grid, drivers without a car in the qualifying are skipped
+fn derive_grid(
+    format: WeekendFormat,
+    reverse_grid_count: Option<i16>,
+    qualifying: &[SessionResult],
+    grid_penalties: &[GridPenalty],
+) -> Vec<i16> {
+    let mut grid: Vec<i16> = qualifying.iter().map(|result| result.car_idx).collect();
+
+    if let (WeekendFormat::ReverseGrid, Some(count)) = (format, reverse_grid_count) {
+        let count = (count as usize).min(grid.len());
+        grid[..count].reverse();
+    }
+
+    for penalty in grid_penalties {
+        let Some(car_idx) = qualifying
+            .iter()
+            .find(|result| result.user_id == Some(penalty.user_id))
+            .map(|result| result.car_idx)
+        else {
+            continue;
+        };
+
+        let Some(from) = grid.iter().position(|&idx| idx == car_idx) else {
+            continue;
+        };
+
+        grid.remove(from);
+        let to = (from + penalty.places.max(0) as usize).min(grid.len());
+        grid.insert(to, car_idx);
+    }
+
+    grid
+}
+
+#[inline(always)]
+fn compare_grid(expected: &[i16], race: &[SessionResult]) -> Vec<GridSlot> {
+    expected
+        .iter()
+        .enumerate()
+        .map(|(idx, car_idx)| {
+            let expected_position = idx as i16 + 1;
+            let grid_position = race
+                .iter()
+                .find(|result| result.car_idx == *car_idx)
+                .map(|result| result.grid_position);
+
+            GridSlot {
+                car_idx: *car_idx,
+                expected_position,
+                grid_position,
+                irregular: grid_position.is_some_and(|position| position != expected_position),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_grid, derive_grid};
+    use crate::entity::{GridPenalty, SessionResult, WeekendFormat};
+
+    fn result(car_idx: i16, position: i16, grid_position: i16) -> SessionResult {
+        SessionResult {
+            session_uid: 1,
+            car_idx,
+            position,
+            num_laps: 10,
+            grid_position,
+            points: 0,
+            num_pit_stops: 0,
+            result_status: 3,
+            best_lap_time_in_ms: 90_000,
+            total_race_time: 900.0,
+            penalties_time: 0,
+            num_penalties: 0,
+            adjusted_position: position,
+            adjusted_penalties_time: 0,
+            adjusted_points: 0,
+            disqualified: false,
+            driver_name: None,
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn test_reverse_grid_irregularities() {
+        let qualifying = vec![
+            result(4, 1, 0),
+            result(2, 2, 0),
+            result(7, 3, 0),
+            result(1, 4, 0),
+        ];
+
+        assert_eq!(
+            derive_grid(WeekendFormat::Standard, None, &qualifying, &[]),
+            vec![4, 2, 7, 1]
+        );
+
+        let expected = derive_grid(WeekendFormat::ReverseGrid, Some(3), &qualifying, &[]);
+        assert_eq!(expected, vec![7, 2, 4, 1]);
+
+        let race = vec![result(7, 1, 1), result(4, 2, 2), result(2, 3, 3)];
+        let grid = compare_grid(&expected, &race);
+
+        assert!(!grid[0].irregular);
+        assert!(grid[1].irregular);
+        assert!(grid[2].irregular);
+        assert_eq!(grid[3].grid_position, None);
+        assert!(!grid[3].irregular);
+    }
+
+    #[test]
+    fn test_grid_drops() {
+        let mut qualifying = vec![
+            result(4, 1, 0),
+            result(2, 2, 0),
+            result(7, 3, 0),
+            result(1, 4, 0),
+        ];
+        qualifying[0].user_id = Some(600000000);
+        qualifying[2].user_id = Some(600000001);
+
+        let penalties = [
+            GridPenalty {
+                user_id: 600000000,
+                places: 2,
+            },
+            GridPenalty {
+                user_id: 600000001,
+                places: 5,
+            },
+        ];
+
+        assert_eq!(
+            derive_grid(WeekendFormat::Standard, None, &qualifying, &penalties),
+            vec![2, 4, 1, 7]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{
+    cache::RedisCache,
+    config::Database,
+    dtos::{ApplyAdjustment, FileProtest, RuleProtest},
+    entity::{AdjustmentKind, Protest, ProtestStatus, Sanction},
+    error::{AppResult, ChampionshipError, CommonError, ResultError, StewardingError},
+    repositories::{ChampionshipRepository, StewardingRepository},
+    services::ResultService,
+};
+
+#[derive(Clone)]
+pub struct StewardingService {
+    db: Database,
+    cache: RedisCache,
+    stewarding_repository: StewardingRepository,
+    championship_repository: ChampionshipRepository,
+}
+
+impl StewardingService {
+    pub async fn new(db_conn: &Database, cache: &RedisCache) -> Self {
+        Self {
+            db: db_conn.clone(),
+            cache: cache.clone(),
+            stewarding_repository: StewardingRepository::new(db_conn),
+            championship_repository: ChampionshipRepository::new(db_conn, cache).await,
+        }
+    }
+
+    pub async fn file_protest(
+        &self,
+        championship_id: &i32,
+        user_id: &i32,
+        form: &FileProtest,
+    ) -> AppResult<i32> {
+        if form.accused_id == *user_id {
+            Err(CommonError::ValidationFailed)?
+        }
+
+        if self
+            .championship_repository
+            .role(championship_id, &form.accused_id)
+            .await?
+            .is_none()
+        {
+            Err(ChampionshipError::NotMember)?
+        }
+
+        // The incident has to be one of the same session of the championship
+        if let Some(incident_id) = &form.incident_id {
+            let Some(incident) = self
+                .stewarding_repository
+                .find_incident(incident_id)
+                .await?
+            else {
+                Err(StewardingError::IncidentNotFound)?
+            };
+
+            if incident.championship_id != *championship_id
+                || incident.session_uid != form.session_uid
+            {
+                Err(StewardingError::IncidentNotFound)?
+            }
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        let file_protest_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO championship_protests (championship_id, session_uid, incident_id, filed_by, accused_id, lap_num, description)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7)
+                    RETURNING id
+                "#,
+            )
+            .await?;
+
+        let row = conn
+            .query_one(
+                &file_protest_stmt,
+                &[
+                    championship_id,
+                    &form.session_uid,
+                    &form.incident_id,
+                    user_id,
+                    &form.accused_id,
+                    &form.lap_num,
+                    &form.description,
+                ],
+            )
+            .await?;
+
+        Ok(row.try_get("id")?)
+    }
+
+    // Upheld protests can sanction the accused driver, the penalty is stored next to
+    // the protest and time penalties and disqualifications are applied to the
+    // classification of the session in the same transaction
+    pub async fn rule_protest(
+        &self,
+        championship_id: &i32,
+        id: &i32,
+        steward_id: &i32,
+        form: &RuleProtest,
+    ) -> AppResult<()> {
+        let protest = self.championship_protest(championship_id, id).await?;
+
+        if protest.status != ProtestStatus::Pending {
+            Err(StewardingError::AlreadyRuled)?
+        }
+
+        if protest.filed_by == *steward_id || protest.accused_id == *steward_id {
+            Err(StewardingError::InvolvedSteward)?
+        }
+
+        let sanction = match (form.status, form.sanction, form.value) {
+            (ProtestStatus::Pending, _, _) => Err(StewardingError::InvalidRuling)?,
+            (ProtestStatus::Upheld, Some(Sanction::Disqualification), None) => {
+                Some((Sanction::Disqualification, 0))
+            }
+            (ProtestStatus::Upheld, Some(Sanction::Disqualification), Some(_)) => {
+                Err(StewardingError::InvalidRuling)?
+            }
+            (ProtestStatus::Upheld, Some(sanction), Some(value)) => Some((sanction, value)),
+            (_, None, None) => None,
+            _ => Err(StewardingError::InvalidRuling)?,
+        };
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let rule_protest_stmt = transaction
+            .prepare_cached(
+                r#"
+                    UPDATE championship_protests
+                    SET status = $1, steward_id = $2, decision = $3, ruled_at = CURRENT_TIMESTAMP
+                    WHERE id = $4 AND status = 'Pending'
+                "#,
+            )
+            .await?;
+
+        // Guarded by the status in case other steward ruled at the same time
+        if transaction
+            .execute(
+                &rule_protest_stmt,
+                &[&form.status, steward_id, &form.decision, id],
+            )
+            .await?
+            == 0
+        {
+            Err(StewardingError::AlreadyRuled)?
+        }
+
+        if let Some((sanction, value)) = sanction {
+            let add_penalty_stmt = transaction
+                .prepare_cached(
+                    r#"
+                        INSERT INTO championship_penalties (championship_id, session_uid, user_id, protest_id, sanction, value, applied_by)
+                        VALUES ($1,$2,$3,$4,$5,$6,$7)
+                    "#,
+                )
+                .await?;
+
+            transaction
+                .execute(
+                    &add_penalty_stmt,
+                    &[
+                        championship_id,
+                        &protest.session_uid,
+                        &protest.accused_id,
+                        id,
+                        &sanction,
+                        &value,
+                        steward_id,
+                    ],
+                )
+                .await?;
+        }
+
+        let mut users = vec![protest.accused_id];
+
+        let adjustment = match sanction {
+            Some((Sanction::TimePenalty, value)) => {
+                Some((AdjustmentKind::TimePenalty, Some(value)))
+            }
+            Some((Sanction::Disqualification, _)) => Some((AdjustmentKind::Disqualification, None)),
+            _ => None,
+        };
+
+        if let Some((kind, value)) = adjustment {
+            let driver_car_stmt = transaction
+                .prepare_cached(
+                    r#"
+                        SELECT car_idx FROM session_results
+                        WHERE championship_id = $1 AND session_uid = $2 AND user_id = $3
+                    "#,
+                )
+                .await?;
+
+            let Some(row) = transaction
+                .query_opt(
+                    &driver_car_stmt,
+                    &[championship_id, &protest.session_uid, &protest.accused_id],
+                )
+                .await?
+            else {
+                Err(ResultError::CarNotFound)?
+            };
+
+            let form = ApplyAdjustment {
+                car_idx: row.try_get("car_idx")?,
+                kind,
+                value,
+                reason: format!("Protest #{}", id),
+            };
+
+            let results = ResultService::add_adjustment(
+                &transaction,
+                championship_id,
+                &protest.session_uid,
+                steward_id,
+                &form,
+            )
+            .await?;
+
+            users.extend(results.iter().filter_map(|result| result.user_id));
+        }
+
+        transaction.commit().await?;
+
+        // Other drivers of the session can change position with the sanction
+        if sanction.is_some() {
+            self.cache.stats.delete_all(&users).await?;
+        }
+
+        Ok(())
+    }
+
+    // Protests can only be ruled through the championship they belong to
+    async fn championship_protest(&self, championship_id: &i32, id: &i32) -> AppResult<Protest> {
+        let Some(protest) = self.stewarding_repository.find_protest(id).await? else {
+            Err(StewardingError::ProtestNotFound)?
+        };
+
+        if protest.championship_id != *championship_id {
+            Err(StewardingError::ProtestNotFound)?
+        }
+
+        Ok(protest)
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    cache::RedisCache,
+    config::Database,
+    dtos::{CreateTeam, UpdateTeam},
+    entity::Team,
+    error::{AppResult, ChampionshipError, CommonError, TeamError},
+    repositories::{ChampionshipRepository, TeamRepository},
+};
+use postgres_types::ToSql;
+
+const MAX_TEAM_DRIVERS: i64 = 2;
+
+#[derive(Clone)]
+pub struct TeamService {
+    db: Database,
+    team_repository: TeamRepository,
+    championship_repository: ChampionshipRepository,
+}
+
+impl TeamService {
+    pub async fn new(db_conn: &Database, cache: &RedisCache) -> Self {
+        Self {
+            db: db_conn.clone(),
+            team_repository: TeamRepository::new(db_conn),
+            championship_repository: ChampionshipRepository::new(db_conn, cache).await,
+        }
+    }
+
+    pub async fn create(&self, championship_id: &i32, form: &CreateTeam) -> AppResult<i32> {
+        if self
+            .team_repository
+            .find_by_name(championship_id, &form.name)
+            .await?
+            .is_some()
+        {
+            Err(TeamError::AlreadyExists)?
+        }
+
+        let id = fastrand::i32(900000000..999999999);
+        let conn = self.db.pg.get().await?;
+
+        let create_team_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO championship_teams (id, championship_id, name, game_team_id, primary_color, secondary_color)
+                    VALUES ($1,$2,$3,$4,$5,$6)
+                "#,
+            )
+            .await?;
+
+        let bindings: [&(dyn ToSql + Sync); 6] = [
+            &id,
+            championship_id,
+            &form.name,
+            &form.game_team_id,
+            &form.primary_color,
+            &form.secondary_color,
+        ];
+
+        conn.execute(&create_team_stmt, &bindings).await?;
+
+        Ok(id)
+    }
+
+    pub async fn update(
+        &self,
+        championship_id: &i32,
+        id: &i32,
+        form: &UpdateTeam,
+    ) -> AppResult<()> {
+        self.championship_team(championship_id, id).await?;
+
+        let (query, params) = {
+            let mut counter = 1;
+            let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+            let mut query = String::from("UPDATE championship_teams SET ");
+
+            if let Some(name) = &form.name {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" name = ${}", counter));
+                params.push(name);
+                counter += 1;
+            }
+
+            if let Some(game_team_id) = &form.game_team_id {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" game_team_id = ${}", counter));
+                params.push(game_team_id);
+                counter += 1;
+            }
+
+            if let Some(primary_color) = &form.primary_color {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" primary_color = ${}", counter));
+                params.push(primary_color);
+                counter += 1;
+            }
+
+            if let Some(secondary_color) = &form.secondary_color {
+                if counter > 1 {
+                    query.push(',');
+                }
+
+                query.push_str(&format!(" secondary_color = ${}", counter));
+                params.push(secondary_color);
+                counter += 1;
+            }
+
+            if counter == 1 {
+                Err(CommonError::NotValidUpdate)?
+            }
+
+            query.push_str(&format!(
+                ", updated_at = CURRENT_TIMESTAMP WHERE id = ${}",
+                counter
+            ));
+            params.push(id);
+
+            (query, params)
+        };
+
+        let conn = self.db.pg.get().await?;
+        let update_team_stmt = conn.prepare_cached(&query).await?;
+        conn.execute(&update_team_stmt, &params).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, championship_id: &i32, id: &i32) -> AppResult<()> {
+        self.championship_team(championship_id, id).await?;
+
+        let conn = self.db.pg.get().await?;
+
+        let delete_team_stmt = conn
+            .prepare_cached(
+                r#"
+                    DELETE FROM championship_teams WHERE id = $1
+                "#,
+            )
+            .await?;
+
+        conn.execute(&delete_team_stmt, &[id]).await?;
+
+        Ok(())
+    }
+
+    // If the driver is racing for another team of the championship it's a transfer,
+    // the previous stint is closed so past rounds keep the old line-up
+    pub async fn add_driver(
+        &self,
+        championship_id: &i32,
+        id: &i32,
+        user_id: &i32,
+    ) -> AppResult<()> {
+        self.championship_team(championship_id, id).await?;
+
+        if self
+            .championship_repository
+            .role(championship_id, user_id)
+            .await?
+            .is_none()
+        {
+            Err(ChampionshipError::NotMember)?
+        }
+
+        let current_team = self
+            .team_repository
+            .driver_team(championship_id, user_id)
+            .await?;
+
+        if let Some(current_team) = &current_team {
+            if current_team.team_id == *id {
+                Err(TeamError::DriverAlreadyInTeam)?
+            }
+        }
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        if let Some(current_team) = current_team {
+            let close_stint_stmt = transaction
+                .prepare_cached(
+                    r#"
+                        UPDATE team_drivers SET left_at = CURRENT_TIMESTAMP
+                        WHERE team_id = $1 AND user_id = $2 AND left_at IS NULL
+                    "#,
+                )
+                .await?;
+
+            transaction
+                .execute(&close_stint_stmt, &[&current_team.team_id, user_id])
+                .await?;
+        }
+
+        // The team row is locked so concurrent additions count the drivers one after another
+        let lock_team_stmt = transaction
+            .prepare_cached(
+                r#"
+                    SELECT id FROM championship_teams WHERE id = $1 FOR UPDATE
+                "#,
+            )
+            .await?;
+
+        transaction.execute(&lock_team_stmt, &[id]).await?;
+
+        let add_driver_stmt = transaction
+            .prepare_cached(
+                r#"
+                    INSERT INTO team_drivers (team_id, user_id)
+                    SELECT $1, $2
+                    WHERE (SELECT COUNT(*) FROM team_drivers WHERE team_id = $1 AND left_at IS NULL) < $3
+                "#,
+            )
+            .await?;
+
+        if transaction
+            .execute(&add_driver_stmt, &[id, user_id, &MAX_TEAM_DRIVERS])
+            .await?
+            == 0
+        {
+            Err(TeamError::Full)?
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_driver(
+        &self,
+        championship_id: &i32,
+        id: &i32,
+        user_id: &i32,
+    ) -> AppResult<()> {
+        self.championship_team(championship_id, id).await?;
+
+        let conn = self.db.pg.get().await?;
+
+        let close_stint_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE team_drivers SET left_at = CURRENT_TIMESTAMP
+                    WHERE team_id = $1 AND user_id = $2 AND left_at IS NULL
+                "#,
+            )
+            .await?;
+
+        if conn.execute(&close_stint_stmt, &[id, user_id]).await? == 0 {
+            Err(TeamError::DriverNotFound)?
+        }
+
+        Ok(())
+    }
+
+    // Teams can only be managed through the championship they belong to
+    async fn championship_team(&self, championship_id: &i32, id: &i32) -> AppResult<Team> {
+        let Some(team) = self.team_repository.find(id).await? else {
+            Err(TeamError::NotFound)?
+        };
+
+        if team.championship_id != *championship_id {
+            Err(TeamError::NotFound)?
+        }
+
+        Ok(team)
+    }
+}
@@ -1,60 +1,111 @@
 use crate::{
     cache::RedisCache,
-    dtos::{TokenClaim, TokenType},
-    error::{AppResult, TokenError},
+    config::{constants::MAX_TWO_FACTOR_ATTEMPTS, JwtKeys},
+    dtos::{DeviceInfo, RefreshClaim, RefreshSession, TokenClaim, TokenType, ViewerClaim},
+    error::{AppResult, TokenError, TwoFactorError},
 };
 use async_trait::async_trait;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
-use std::fs;
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::{Error as JwtError, ErrorKind},
+    jwk::JwkSet,
+    TokenData,
+};
+use rand::{distr::Alphanumeric, Rng};
+use serde::{de::DeserializeOwned, Serialize};
 
 #[derive(Clone)]
 pub struct TokenService {
-    header: Header,
     cache: RedisCache,
-    validation: Validation,
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    keys: JwtKeys,
 }
 
 #[async_trait]
 pub trait TokenServiceTrait {
-    fn new(cache: &RedisCache) -> Self;
+    fn new(cache: &RedisCache, keys: &JwtKeys) -> Self;
+    fn jwks(&self) -> JwkSet;
     fn validate(&self, token: &str) -> AppResult<TokenData<TokenClaim>>;
+    async fn validate_access(&self, token: &str) -> AppResult<i32>;
+    async fn revoke_user(&self, user_id: &i32) -> AppResult<()>;
     async fn save_reset_password_token(&self, token: &str) -> AppResult<()>;
     async fn save_email_token(&self, token: &str) -> AppResult<()>;
+    async fn save_email_change_token(&self, token: &str, email: &str) -> AppResult<()>;
+    async fn save_two_factor_token(&self, token: &str) -> AppResult<()>;
+    async fn two_factor_attempt(&self, token: &str) -> AppResult<i32>;
+    async fn remove_two_factor_token(&self, token: &str) -> AppResult<()>;
+    async fn save_auth_code(&self, user_id: &i32, fingerprint: &str) -> AppResult<String>;
+    async fn exchange_auth_code(&self, code: &str, fingerprint: &str) -> AppResult<i32>;
     async fn generate_token(&self, sub: i32, token_type: TokenType) -> AppResult<String>;
-    async fn remove_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<()>;
-    async fn generate_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<String>;
+    async fn generate_viewer_ticket(
+        &self,
+        championship_id: i32,
+        user_id: Option<i32>,
+        token_type: TokenType,
+    ) -> AppResult<String>;
+    async fn validate_viewer_ticket(
+        &self,
+        ticket: &str,
+        championship_id: &i32,
+    ) -> AppResult<ViewerClaim>;
+    async fn generate_refresh_token(
+        &self,
+        user_id: &i32,
+        device: &DeviceInfo<'_>,
+    ) -> AppResult<String>;
     async fn refresh_access_token(
         &self,
         refresh_token: &str,
         fingerprint: &str,
-    ) -> AppResult<String>;
+    ) -> AppResult<(String, String)>;
+    async fn sessions(&self, user_id: &i32) -> AppResult<Vec<RefreshSession>>;
+    async fn revoke_session(&self, user_id: &i32, session_id: &str) -> AppResult<()>;
+    async fn revoke_device(&self, user_id: &i32, fingerprint: &str) -> AppResult<()>;
+    async fn revoke_sessions(&self, user_id: &i32) -> AppResult<()>;
 }
 
 #[async_trait]
 impl TokenServiceTrait for TokenService {
-    fn new(cache: &RedisCache) -> Self {
+    fn new(cache: &RedisCache, keys: &JwtKeys) -> Self {
         Self {
             cache: cache.clone(),
-            header: Header::new(jsonwebtoken::Algorithm::RS256),
-            encoding_key: EncodingKey::from_rsa_pem(
-                &fs::read("certs/jsonwebtoken.key").expect("Unable to read key"),
-            )
-            .unwrap(),
-            validation: Validation::new(jsonwebtoken::Algorithm::RS256),
-            decoding_key: DecodingKey::from_rsa_pem(
-                &fs::read("certs/jsonwebtoken.crt").expect("Unable to read key"),
-            )
-            .unwrap(),
+            keys: keys.clone(),
         }
     }
 
+    fn jwks(&self) -> JwkSet {
+        self.keys.jwks()
+    }
+
     fn validate(&self, token: &str) -> AppResult<TokenData<TokenClaim>> {
-        decode::<TokenClaim>(token, &self.decoding_key, &self.validation)
+        self.decode::<TokenClaim>(token)
             .map_err(|e| TokenError::TokenCreationError(e.to_string()).into())
     }
 
+    // Returns the user of the access token if it wasn't revoked
+    async fn validate_access(&self, token: &str) -> AppResult<i32> {
+        let claims = self.validate(token)?.claims;
+
+        if claims.token_type.ne(&TokenType::Bearer) {
+            Err(TokenError::InvalidTokenType)?
+        }
+
+        if claims.generation != self.cache.token.generation(&claims.sub).await? {
+            Err(TokenError::InvalidToken)?
+        }
+
+        Ok(claims.sub)
+    }
+
+    // Revokes every access token and session of the user
+    async fn revoke_user(&self, user_id: &i32) -> AppResult<()> {
+        tokio::try_join!(
+            self.cache.token.increment_generation(user_id),
+            self.revoke_sessions(user_id)
+        )?;
+
+        Ok(())
+    }
+
     async fn save_reset_password_token(&self, token: &str) -> AppResult<()> {
         self.cache
             .token
@@ -66,58 +117,276 @@ impl TokenServiceTrait for TokenService {
         self.cache.token.set_token(token, &TokenType::Email).await
     }
 
+    async fn save_email_change_token(&self, token: &str, email: &str) -> AppResult<()> {
+        self.cache.token.set_email_change(token, email).await
+    }
+
+    async fn save_two_factor_token(&self, token: &str) -> AppResult<()> {
+        self.cache.token.set_challenge(token).await
+    }
+
+    // Returns the user of the challenge, the challenge is dropped after too many failed codes
+    async fn two_factor_attempt(&self, token: &str) -> AppResult<i32> {
+        let user_id = {
+            let token_data = self.validate(token)?;
+
+            if token_data.claims.token_type.ne(&TokenType::TwoFactor) {
+                Err(TokenError::InvalidTokenType)?
+            }
+
+            token_data.claims.sub
+        };
+
+        let Some(attempts) = self.cache.token.challenge_attempt(token).await? else {
+            Err(TokenError::InvalidToken)?
+        };
+
+        if attempts > MAX_TWO_FACTOR_ATTEMPTS {
+            self.remove_two_factor_token(token).await?;
+            Err(TwoFactorError::TooManyAttempts)?
+        }
+
+        Ok(user_id)
+    }
+
+    async fn remove_two_factor_token(&self, token: &str) -> AppResult<()> {
+        self.cache
+            .token
+            .remove_token(token, &TokenType::TwoFactor)
+            .await
+    }
+
+    async fn save_auth_code(&self, user_id: &i32, fingerprint: &str) -> AppResult<String> {
+        let code = random_id();
+        self.cache
+            .token
+            .set_auth_code(&code, user_id, fingerprint)
+            .await?;
+
+        Ok(code)
+    }
+
+    // A code leaked from the redirect is useless to a client with other fingerprint
+    async fn exchange_auth_code(&self, code: &str, fingerprint: &str) -> AppResult<i32> {
+        let Some((user_id, code_fingerprint)) = self.cache.token.take_auth_code(code).await? else {
+            Err(TokenError::InvalidToken)?
+        };
+
+        if code_fingerprint != fingerprint {
+            Err(TokenError::InvalidToken)?
+        }
+
+        Ok(user_id)
+    }
+
     async fn generate_token(&self, sub: i32, token_type: TokenType) -> AppResult<String> {
+        let generation = match token_type {
+            TokenType::Bearer => self.cache.token.generation(&sub).await?,
+            _ => 0,
+        };
+
         let token_claim = TokenClaim {
             sub,
             exp: token_type.set_expiration(),
             token_type,
+            generation,
         };
 
-        encode(&self.header, &token_claim, &self.encoding_key)
+        self.encode(&token_claim)
             .map_err(|e| TokenError::TokenCreationError(e.to_string()).into())
     }
 
-    async fn remove_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<()> {
-        self.cache
-            .token
-            .remove_refresh_token(user_id, fingerprint)
-            .await
+    async fn generate_viewer_ticket(
+        &self,
+        championship_id: i32,
+        user_id: Option<i32>,
+        token_type: TokenType,
+    ) -> AppResult<String> {
+        if !matches!(token_type, TokenType::Viewer | TokenType::ViewerInvite) {
+            Err(TokenError::InvalidTokenType)?
+        }
+
+        // Invite links are shared, a new one revokes the ones given before
+        let generation = match token_type {
+            TokenType::ViewerInvite => {
+                self.cache
+                    .token
+                    .increment_invite_generation(&championship_id)
+                    .await?
+            }
+            _ => 0,
+        };
+
+        let viewer_claim = ViewerClaim {
+            sub: championship_id,
+            user_id,
+            exp: token_type.set_expiration(),
+            token_type,
+            generation,
+        };
+
+        self.encode(&viewer_claim)
+            .map_err(|e| TokenError::TokenCreationError(e.to_string()).into())
     }
 
-    async fn generate_refresh_token(&self, user_id: &i32, fingerprint: &str) -> AppResult<String> {
-        let token = self
-            .generate_token(*user_id, TokenType::RefreshBearer)
-            .await?;
+    async fn validate_viewer_ticket(
+        &self,
+        ticket: &str,
+        championship_id: &i32,
+    ) -> AppResult<ViewerClaim> {
+        let ticket = self
+            .decode::<ViewerClaim>(ticket)
+            .map_err(|_| TokenError::InvalidToken)?;
+
+        if !matches!(
+            ticket.claims.token_type,
+            TokenType::Viewer | TokenType::ViewerInvite
+        ) {
+            Err(TokenError::InvalidTokenType)?
+        }
+
+        if ticket.claims.sub != *championship_id {
+            Err(TokenError::InvalidToken)?
+        }
+
+        if ticket.claims.token_type == TokenType::ViewerInvite
+            && ticket.claims.generation
+                != self.cache.token.invite_generation(championship_id).await?
+        {
+            Err(TokenError::InvalidToken)?
+        }
+
+        Ok(ticket.claims)
+    }
+
+    // Logging in again from the same device replaces its previous session
+    async fn generate_refresh_token(
+        &self,
+        user_id: &i32,
+        device: &DeviceInfo<'_>,
+    ) -> AppResult<String> {
+        self.revoke_device(user_id, device.fingerprint).await?;
+
+        let session_id = random_id();
+        let jti = random_id();
 
         self.cache
             .token
-            .set_refresh_token(&token, fingerprint)
+            .set_session(user_id, &session_id, &jti, device)
             .await?;
 
-        Ok(token)
+        self.encode_refresh_token(*user_id, session_id, jti)
     }
 
+    // The refresh token is rotated, using an old token of the session means it was stolen
+    // so the whole session is revoked
     async fn refresh_access_token(
         &self,
         refresh_token: &str,
         fingerprint: &str,
-    ) -> AppResult<String> {
-        let id = {
-            let token = self.validate(refresh_token)?;
+    ) -> AppResult<(String, String)> {
+        let claims = self
+            .decode::<RefreshClaim>(refresh_token)
+            .map_err(|_| TokenError::InvalidToken)?
+            .claims;
 
-            if token.claims.token_type.ne(&TokenType::RefreshBearer) {
-                Err(TokenError::InvalidTokenType)?
-            }
+        if claims.token_type.ne(&TokenType::RefreshBearer) {
+            Err(TokenError::InvalidTokenType)?
+        }
 
-            token.claims.sub
+        let Some(session) = self.cache.token.session(&claims.sub, &claims.sid).await? else {
+            Err(TokenError::InvalidToken)?
         };
 
-        let db_token = self.cache.token.get_refresh_token(&id, fingerprint).await?;
-
-        if db_token != refresh_token {
+        if session.fingerprint != fingerprint {
             Err(TokenError::InvalidToken)?
         }
 
-        self.generate_token(id, TokenType::Bearer).await
+        let new_jti = random_id();
+
+        if !self
+            .cache
+            .token
+            .rotate_session(&claims.sub, &claims.sid, &claims.jti, &new_jti)
+            .await?
+        {
+            self.revoke_session(&claims.sub, &claims.sid).await?;
+            Err(TokenError::TokenReused)?
+        }
+
+        let access_token = self.generate_token(claims.sub, TokenType::Bearer).await?;
+        let refresh_token = self.encode_refresh_token(claims.sub, claims.sid, new_jti)?;
+
+        Ok((access_token, refresh_token))
+    }
+
+    async fn sessions(&self, user_id: &i32) -> AppResult<Vec<RefreshSession>> {
+        self.cache.token.sessions(user_id).await
+    }
+
+    async fn revoke_session(&self, user_id: &i32, session_id: &str) -> AppResult<()> {
+        self.cache.token.remove_session(user_id, session_id).await
     }
+
+    async fn revoke_device(&self, user_id: &i32, fingerprint: &str) -> AppResult<()> {
+        let sessions = self.sessions(user_id).await?;
+
+        for session in sessions
+            .iter()
+            .filter(|session| session.fingerprint == fingerprint)
+        {
+            self.revoke_session(user_id, &session.id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_sessions(&self, user_id: &i32) -> AppResult<()> {
+        self.cache.token.remove_sessions(user_id).await
+    }
+}
+
+impl TokenService {
+    // Signed with the active key, its kid goes in the header
+    fn encode<T: Serialize>(&self, claims: &T) -> Result<String, JwtError> {
+        let (header, encoding_key) = self
+            .keys
+            .signing_key()
+            .ok_or(JwtError::from(ErrorKind::InvalidKeyFormat))?;
+
+        encode(&header, claims, &encoding_key)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<TokenData<T>, JwtError> {
+        let header = decode_header(token)?;
+
+        let (decoding_key, validation) = self
+            .keys
+            .verification_key(header.kid.as_deref())
+            .ok_or(JwtError::from(ErrorKind::InvalidToken))?;
+
+        decode::<T>(token, &decoding_key, &validation)
+    }
+
+    fn encode_refresh_token(&self, sub: i32, sid: String, jti: String) -> AppResult<String> {
+        let refresh_claim = RefreshClaim {
+            sub,
+            sid,
+            jti,
+            exp: TokenType::RefreshBearer.set_expiration(),
+            token_type: TokenType::RefreshBearer,
+        };
+
+        self.encode(&refresh_claim)
+            .map_err(|e| TokenError::TokenCreationError(e.to_string()).into())
+    }
+}
+
+#[inline(always)]
+fn random_id() -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
 }
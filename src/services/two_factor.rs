@@ -0,0 +1,335 @@
+use crate::{
+    config::{constants::*, Database},
+    entity::{Provider, User},
+    error::{AppResult, CommonError, TwoFactorError},
+    repositories::TwoFactorRepository,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use deadpool_postgres::Transaction;
+use rand::{distr::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+use tracing::error;
+
+#[derive(Clone)]
+pub struct TwoFactorService {
+    db: Database,
+    two_factor_repository: TwoFactorRepository,
+}
+
+impl TwoFactorService {
+    pub fn new(db_conn: &Database) -> Self {
+        Self {
+            db: db_conn.clone(),
+            two_factor_repository: TwoFactorRepository::new(db_conn),
+        }
+    }
+
+    // Starting the setup again replaces the secret until the first code is verified.
+    // Returns the secret and the provisioning uri for the QR
+    pub async fn setup(&self, user: &User) -> AppResult<(String, String)> {
+        if user.provider != Provider::Local {
+            Err(TwoFactorError::LocalOnly)?
+        }
+
+        if self.two_factor_repository.enabled(&user.id).await? {
+            Err(TwoFactorError::AlreadyEnabled)?
+        }
+
+        let Secret::Encoded(secret) = Secret::generate_secret().to_encoded() else {
+            Err(CommonError::InternalServerError)?
+        };
+
+        let provisioning_uri = totp(&secret, Some(&user.email))?.get_url();
+        let conn = self.db.pg.get().await?;
+
+        let setup_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO user_two_factor (user_id, secret)
+                    VALUES ($1,$2)
+                    ON CONFLICT (user_id) DO UPDATE
+                    SET secret = EXCLUDED.secret, last_used_step = 0, created_at = CURRENT_TIMESTAMP
+                    WHERE user_two_factor.enabled = false
+                "#,
+            )
+            .await?;
+
+        // Guarded by the enabled flag in case other setup finished at the same time
+        if conn.execute(&setup_stmt, &[&user.id, &secret]).await? == 0 {
+            Err(TwoFactorError::AlreadyEnabled)?
+        }
+
+        Ok((secret, provisioning_uri))
+    }
+
+    // The first valid code confirms the authenticator, returns the recovery codes
+    pub async fn enable(&self, user_id: &i32, code: &str) -> AppResult<Vec<String>> {
+        let Some(two_factor) = self.two_factor_repository.find(user_id).await? else {
+            Err(TwoFactorError::NotSetUp)?
+        };
+
+        if two_factor.enabled {
+            Err(TwoFactorError::AlreadyEnabled)?
+        }
+
+        self.verify_totp(user_id, &two_factor.secret, code).await?;
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let enable_stmt = transaction
+            .prepare_cached(
+                r#"
+                    UPDATE user_two_factor SET enabled = true
+                    WHERE user_id = $1 AND enabled = false
+                "#,
+            )
+            .await?;
+
+        if transaction.execute(&enable_stmt, &[user_id]).await? == 0 {
+            Err(TwoFactorError::AlreadyEnabled)?
+        }
+
+        let recovery_codes = Self::save_recovery_codes(&transaction, user_id).await?;
+        transaction.commit().await?;
+
+        Ok(recovery_codes)
+    }
+
+    pub async fn disable(&self, user_id: &i32, code: &str) -> AppResult<()> {
+        self.verify(user_id, code).await?;
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let (disable_stmt, delete_codes_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    DELETE FROM user_two_factor
+                    WHERE user_id = $1
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    DELETE FROM two_factor_recovery_codes
+                    WHERE user_id = $1
+                "#,
+            )
+        )?;
+
+        transaction.execute(&disable_stmt, &[user_id]).await?;
+        transaction.execute(&delete_codes_stmt, &[user_id]).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    // Replaces every recovery code of the user, used or not
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: &i32,
+        code: &str,
+    ) -> AppResult<Vec<String>> {
+        self.verify(user_id, code).await?;
+
+        let mut conn = self.db.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let recovery_codes = Self::save_recovery_codes(&transaction, user_id).await?;
+        transaction.commit().await?;
+
+        Ok(recovery_codes)
+    }
+
+    // Codes with the length of the authenticator ones are checked against the secret,
+    // anything else is taken as a recovery code and can only be used once
+    pub async fn verify(&self, user_id: &i32, code: &str) -> AppResult<()> {
+        let Some(two_factor) = self.two_factor_repository.find(user_id).await? else {
+            Err(TwoFactorError::NotEnabled)?
+        };
+
+        if !two_factor.enabled {
+            Err(TwoFactorError::NotEnabled)?
+        }
+
+        if code.len() == TOTP_DIGITS && code.bytes().all(|byte| byte.is_ascii_digit()) {
+            return self.verify_totp(user_id, &two_factor.secret, code).await;
+        }
+
+        let conn = self.db.pg.get().await?;
+
+        let use_code_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE two_factor_recovery_codes SET used_at = CURRENT_TIMESTAMP
+                    WHERE id = (
+                        SELECT id FROM two_factor_recovery_codes
+                        WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+                        LIMIT 1
+                    ) AND used_at IS NULL
+                "#,
+            )
+            .await?;
+
+        let code_hash = hash_recovery_code(code);
+
+        if conn.execute(&use_code_stmt, &[user_id, &code_hash]).await? == 0 {
+            Err(TwoFactorError::InvalidCode)?
+        }
+
+        Ok(())
+    }
+
+    // The step of the code is saved so the same code can't be used again
+    async fn verify_totp(&self, user_id: &i32, secret: &str, code: &str) -> AppResult<()> {
+        let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+            Err(CommonError::InternalServerError)?
+        };
+
+        let Some(step) = matching_step(&totp(secret, None)?, code, now.as_secs()) else {
+            Err(TwoFactorError::InvalidCode)?
+        };
+
+        let conn = self.db.pg.get().await?;
+
+        let use_step_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE user_two_factor SET last_used_step = $1
+                    WHERE user_id = $2 AND last_used_step < $1
+                "#,
+            )
+            .await?;
+
+        if conn
+            .execute(&use_step_stmt, &[&(step as i64), user_id])
+            .await?
+            == 0
+        {
+            Err(TwoFactorError::InvalidCode)?
+        }
+
+        Ok(())
+    }
+
+    async fn save_recovery_codes(
+        transaction: &Transaction<'_>,
+        user_id: &i32,
+    ) -> AppResult<Vec<String>> {
+        let (delete_codes_stmt, add_code_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    DELETE FROM two_factor_recovery_codes
+                    WHERE user_id = $1
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO two_factor_recovery_codes (user_id, code_hash)
+                    VALUES ($1,$2)
+                "#,
+            )
+        )?;
+
+        transaction.execute(&delete_codes_stmt, &[user_id]).await?;
+
+        let recovery_codes = generate_recovery_codes();
+
+        for code in &recovery_codes {
+            transaction
+                .execute(&add_code_stmt, &[user_id, &hash_recovery_code(code)])
+                .await?;
+        }
+
+        Ok(recovery_codes)
+    }
+}
+
+// The account name is only needed for the provisioning uri
+fn totp(secret: &str, account_name: Option<&str>) -> AppResult<TOTP> {
+    let Ok(secret) = Secret::Encoded(secret.to_owned()).to_bytes() else {
+        error!("Invalid two factor secret");
+        Err(CommonError::InternalServerError)?
+    };
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP,
+        secret,
+        Some(TOTP_ISSUER.to_owned()),
+        account_name.unwrap_or_default().to_owned(),
+    )
+    .map_err(|e| {
+        error!("Invalid two factor configuration: {e}");
+        CommonError::InternalServerError.into()
+    })
+}
+
+// Codes of the previous and next step are accepted too for clock drift
+fn matching_step(totp: &TOTP, code: &str, now: u64) -> Option<u64> {
+    let current = now / TOTP_STEP;
+
+    (current.saturating_sub(1)..=current + 1).find(|step| totp.check(code, step * TOTP_STEP))
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let code = (&mut rng)
+                .sample_iter(Alphanumeric)
+                .take(RECOVERY_CODE_LENGTH)
+                .map(|byte| (byte as char).to_ascii_lowercase())
+                .collect::<String>();
+
+            let (first, second) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+            format!("{first}-{second}")
+        })
+        .collect()
+}
+
+// Recovery codes are case and separator insensitive
+fn hash_recovery_code(code: &str) -> String {
+    let code = code
+        .chars()
+        .filter(|char| char.is_ascii_alphanumeric())
+        .map(|char| char.to_ascii_lowercase())
+        .collect::<String>();
+
+    STANDARD.encode(Sha256::digest(code.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_recovery_codes, hash_recovery_code, matching_step, totp};
+    use crate::config::constants::{RECOVERY_CODES, TOTP_STEP};
+
+    const SECRET: &str = "KRSXG5CTMVRXEZLUKN2XAZLSKNSWG4TFOQ";
+
+    #[test]
+    fn test_matching_step() {
+        let totp = totp(SECRET, None).unwrap();
+        let now = 1_700_000_000;
+        let code = totp.generate(now - TOTP_STEP);
+
+        assert_eq!(matching_step(&totp, &code, now), Some(now / TOTP_STEP - 1));
+        assert_eq!(matching_step(&totp, &code, now + 2 * TOTP_STEP), None);
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes();
+
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert_eq!(
+            hash_recovery_code(&codes[0]),
+            hash_recovery_code(&codes[0].replace('-', "").to_uppercase())
+        );
+        assert_ne!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[1]));
+    }
+}
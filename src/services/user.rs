@@ -1,10 +1,13 @@
 use super::{TokenService, TokenServiceTrait};
 use crate::{
     cache::{EntityCache, RedisCache},
-    config::Database,
-    dtos::{RegisterUserDto, TokenType, UpdateUser},
-    entity::{Provider, UserExtension},
-    error::{AppResult, TokenError, UserError},
+    config::{
+        constants::{UNVERIFIED_ACCOUNT_DAYS, UNVERIFIED_PURGE_INTERVAL},
+        Database,
+    },
+    dtos::{ExternalAccount, RegisterUserDto, TokenType, UpdateUser},
+    entity::UserExtension,
+    error::{AppResult, CommonError, OAuthError, TokenError, UserError},
     repositories::{UserRepository, UserRepositoryTrait},
 };
 use async_trait::async_trait;
@@ -24,25 +27,49 @@ pub struct UserService {
 
 #[async_trait]
 pub trait UserServiceTrait {
-    fn new(db_conn: &Database, cache: &RedisCache) -> Self;
+    fn new(db_conn: &Database, cache: &RedisCache, token_service: &TokenService) -> Self;
     async fn create(&self, register: &RegisterUserDto) -> AppResult<i32>;
+    async fn create_external(&self, account: &ExternalAccount) -> AppResult<i32>;
     async fn update(&self, user: &UserExtension, form: &UpdateUser) -> AppResult<()>;
     async fn delete(&self, id: &i32) -> AppResult<()>;
     async fn reset_password(&self, id: &i32, password: &str) -> AppResult<()>;
     async fn reset_password_with_token(&self, token: &str, password: &str) -> AppResult<i32>;
     async fn activate(&self, id: &i32) -> AppResult<()>;
     async fn activate_with_token(&self, token: &str) -> AppResult<i32>;
+    async fn change_email_with_token(&self, token: &str) -> AppResult<i32>;
     async fn deactivate(&self, id: &i32) -> AppResult<()>;
+    async fn purge_unverified(&self) -> AppResult<u64>;
+}
+
+impl UserService {
+    // Runs once at startup and then every interval for the lifetime of the server
+    pub fn schedule_purge(&self) {
+        let user_service = self.clone();
+
+        ntex::rt::spawn(async move {
+            let mut interval = tokio::time::interval(UNVERIFIED_PURGE_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                match user_service.purge_unverified().await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("Purged {} unverified users", purged),
+                    Err(e) => error!("Failed to purge unverified users: {}", e),
+                }
+            }
+        });
+    }
 }
 
 #[async_trait]
 impl UserServiceTrait for UserService {
-    fn new(db_conn: &Database, cache: &RedisCache) -> Self {
+    fn new(db_conn: &Database, cache: &RedisCache, token_service: &TokenService) -> Self {
         Self {
             cache: cache.clone(),
             db_conn: db_conn.clone(),
             user_repo: UserRepository::new(db_conn, cache),
-            token_service: TokenService::new(cache),
+            token_service: token_service.clone(),
         }
     }
 
@@ -54,62 +81,96 @@ impl UserServiceTrait for UserService {
             Err(UserError::AlreadyExists)?
         }
 
+        let Some(password) = &register.password else {
+            Err(CommonError::ValidationFailed)?
+        };
+
         let id = fastrand::i32(600000000..699999999);
         let conn = self.db_conn.pg.get().await?;
+        let hashed_password = hash(password, DEFAULT_COST)?;
 
-        match &register.provider {
-            Some(provider) if provider.eq(&Provider::Google) => {
-                let create_user_stmt = conn
-                    .prepare_cached(
-                        r#"
-                            INSERT INTO users (id, email, username, avatar, provider, active)
-                            VALUES ($1,$2,$3,$4,$5, true)
-                        "#,
-                    )
-                    .await?;
-
-                conn.execute(
-                    &create_user_stmt,
-                    &[
-                        &id,
-                        &register.email,
-                        &register.username,
-                        &register.avatar,
-                        provider,
-                    ],
-                )
-                .await?;
-            }
+        let create_user_stmt = conn
+            .prepare_cached(
+                r#"
+                    INSERT INTO users (id, email, username, password, avatar, active)
+                    VALUES ($1,$2,$3,$4,$5, false)
+                "#,
+            )
+            .await?;
 
-            None => {
-                let hashed_password = hash(register.password.clone().unwrap(), DEFAULT_COST)?;
+        conn.execute(
+            &create_user_stmt,
+            &[
+                &id,
+                &register.email,
+                &register.username,
+                &hashed_password,
+                &format!("https://ui-avatars.com/api/?name={}", &register.username),
+            ],
+        )
+        .await?;
 
-                let create_google_user_stmt = conn
-                    .prepare_cached(
-                        r#"
-                            INSERT INTO users (id, email, username, password, avatar, active)
-                            VALUES ($1,$2,$3,$4,$5, false)
-                        "#,
-                    )
-                    .await?;
-
-                conn.execute(
-                    &create_google_user_stmt,
-                    &[
-                        &id,
-                        &register.email,
-                        &register.username,
-                        &hashed_password,
-                        &format!("https://ui-avatars.com/api/?name={}", &register.username),
-                    ],
-                )
-                .await?;
-            }
+        info!("User created: {}", register.username);
+        Ok(id)
+    }
+
+    // Users created with a provider are active and don't have a password,
+    // the external account is linked in the same transaction
+    async fn create_external(&self, account: &ExternalAccount) -> AppResult<i32> {
+        let Some(email) = account.email.as_ref().filter(|_| account.email_verified) else {
+            Err(OAuthError::EmailRequired)?
+        };
 
-            _ => Err(UserError::InvalidProvider)?,
+        if self.user_repo.user_exists(email).await? {
+            Err(UserError::AlreadyExists)?
         }
 
-        info!("User created: {}", register.username);
+        let id = fastrand::i32(600000000..699999999);
+        let username = account.username.chars().take(20).collect::<String>();
+        let avatar = account
+            .avatar
+            .clone()
+            .unwrap_or_else(|| format!("https://ui-avatars.com/api/?name={username}"));
+
+        let mut conn = self.db_conn.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let (create_user_stmt, create_identity_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO users (id, email, username, avatar, provider, active, verified_at)
+                    VALUES ($1,$2,$3,$4,$5, true, CURRENT_TIMESTAMP)
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    INSERT INTO user_identities (provider, subject, user_id, email)
+                    VALUES ($1,$2,$3,$4)
+                "#,
+            )
+        )?;
+
+        transaction
+            .execute(
+                &create_user_stmt,
+                &[&id, email, &username, &avatar, &account.provider],
+            )
+            .await?;
+
+        transaction
+            .execute(
+                &create_identity_stmt,
+                &[&account.provider, &account.subject, &id, email],
+            )
+            .await?;
+
+        transaction.commit().await?;
+
+        info!(
+            "User created with {}: {}",
+            account.provider.name(),
+            username
+        );
         Ok(id)
     }
 
@@ -178,7 +239,7 @@ impl UserServiceTrait for UserService {
 
         let delete_user_stmt_fut = conn.prepare_cached(
             r#"
-                DELETE FROM user
+                DELETE FROM users
                 WHERE id = $1
             "#,
         );
@@ -195,7 +256,9 @@ impl UserServiceTrait for UserService {
         };
         let cache_del_fut = self.cache.user.delete(id);
 
-        tokio::try_join!(user_deletion_fut, cache_del_fut)?;
+        let revoke_tokens_fut = self.token_service.revoke_user(id);
+
+        tokio::try_join!(user_deletion_fut, cache_del_fut, revoke_tokens_fut)?;
         info!("User deleted with success: {}", id);
 
         Ok(())
@@ -252,6 +315,7 @@ impl UserServiceTrait for UserService {
         };
 
         self.reset_password(&user_id, password).await?;
+        self.token_service.revoke_user(&user_id).await?;
         self.cache
             .token
             .remove_token(token, &TokenType::ResetPassword)
@@ -267,7 +331,7 @@ impl UserServiceTrait for UserService {
             .prepare_cached(
                 r#"
                     UPDATE users
-                    SET active = true
+                    SET active = true, verified_at = COALESCE(verified_at, CURRENT_TIMESTAMP)
                     WHERE id = $1
                 "#,
             )
@@ -307,12 +371,63 @@ impl UserServiceTrait for UserService {
         Ok(user_id)
     }
 
+    // The address is only swapped once the link sent to the new one is opened
+    async fn change_email_with_token(&self, token: &str) -> AppResult<i32> {
+        let Some(email) = self.cache.token.take_email_change(token).await? else {
+            Err(TokenError::InvalidToken)?
+        };
+
+        let user_id = {
+            let token_data = self.token_service.validate(token)?;
+            if token_data.claims.token_type.ne(&TokenType::EmailChange) {
+                Err(TokenError::InvalidToken)?
+            }
+
+            token_data.claims.sub
+        };
+
+        let Some(user) = self.user_repo.find(&user_id).await? else {
+            Err(UserError::NotFound)?
+        };
+
+        if self.user_repo.user_exists(&email).await? {
+            Err(UserError::AlreadyExists)?
+        }
+
+        let conn = self.db_conn.pg.get().await?;
+        let change_email_stmt = conn
+            .prepare_cached(
+                r#"
+                    UPDATE users
+                    SET email = $1
+                    WHERE id = $2
+                "#,
+            )
+            .await?;
+
+        let bindings: [&(dyn ToSql + Sync); 2] = [&email, &user_id];
+        let change_email_fut = async {
+            conn.execute(&change_email_stmt, &bindings).await?;
+            Ok(())
+        };
+
+        tokio::try_join!(
+            change_email_fut,
+            self.cache.user.delete(&user_id),
+            self.cache.user.delete_email(&user.email)
+        )?;
+
+        info!("User email changed with success: {}", user_id);
+
+        Ok(user_id)
+    }
+
     async fn deactivate(&self, id: &i32) -> AppResult<()> {
         let conn = self.db_conn.pg.get().await?;
         let deactivate_user_stmt = conn
             .prepare_cached(
                 r#"
-                    UPDATE user
+                    UPDATE users
                     SET active = false
                     WHERE id = $1
                 "#,
@@ -326,8 +441,52 @@ impl UserServiceTrait for UserService {
             Ok(())
         };
 
-        tokio::try_join!(deactivate_user_fut, delete_cache_fut)?;
+        let revoke_tokens_fut = self.token_service.revoke_user(id);
+
+        tokio::try_join!(deactivate_user_fut, delete_cache_fut, revoke_tokens_fut)?;
         info!("User activated with success: {}", id);
         Ok(())
     }
+
+    // Accounts that never verified their email are removed after a few days, their
+    // address can be used to register again
+    async fn purge_unverified(&self) -> AppResult<u64> {
+        let mut conn = self.db_conn.pg.get().await?;
+        let transaction = conn.transaction().await?;
+
+        let (delete_relations_stmt, delete_users_stmt) = tokio::try_join!(
+            transaction.prepare_cached(
+                r#"
+                    DELETE FROM user_championships
+                    WHERE user_id IN (
+                        SELECT id FROM users
+                        WHERE verified_at IS NULL AND active = false
+                        AND created_at < CURRENT_TIMESTAMP - make_interval(days => $1)
+                    )
+                "#,
+            ),
+            transaction.prepare_cached(
+                r#"
+                    DELETE FROM users
+                    WHERE verified_at IS NULL AND active = false
+                    AND created_at < CURRENT_TIMESTAMP - make_interval(days => $1)
+                    RETURNING id
+                "#,
+            )
+        )?;
+
+        let bindings: [&(dyn ToSql + Sync); 1] = [&UNVERIFIED_ACCOUNT_DAYS];
+        transaction
+            .execute(&delete_relations_stmt, &bindings)
+            .await?;
+
+        let rows = transaction.query(&delete_users_stmt, &bindings).await?;
+        transaction.commit().await?;
+
+        for row in &rows {
+            self.cache.user.delete(&row.get(0)).await?;
+        }
+
+        Ok(rows.len() as u64)
+    }
 }
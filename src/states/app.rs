@@ -1,13 +1,17 @@
 use crate::{
     cache::RedisCache,
-    config::Database,
+    config::{Database, JwtKeys},
     repositories::{
-        ChampionshipRepository, F123Repository, GoogleRepository, ServerRepository, UserRepository,
+        ApiKeyRepository, ChampionshipRepository, F123Repository, IdentityRepository,
+        LapRepository, RecordRepository, ResultRepository, RoundRepository, ServerRepository,
+        StatsRepository, StewardingRepository, TeamRepository, TwoFactorRepository, UserRepository,
         UserRepositoryTrait,
     },
     services::{
-        ChampionshipService, EmailService, F123Service, FirewallService, SavedSessionService,
-        TokenService, TokenServiceTrait, UserService, UserServiceTrait,
+        ApiKeyService, ChampionshipService, EmailService, F123Service, FirewallService,
+        OAuthService, RateLimitService, ResultService, RoundService, SavedSessionService,
+        StewardingService, TeamService, TokenService, TokenServiceTrait, TwoFactorService,
+        UserService, UserServiceTrait,
     },
 };
 
@@ -22,8 +26,25 @@ pub struct AppState {
     pub f123_service: F123Service,
     pub f123_repository: F123Repository,
     pub saved_session_service: SavedSessionService,
-    pub google_repository: GoogleRepository,
+    pub oauth_service: OAuthService,
+    pub rate_limit_service: RateLimitService,
+    pub identity_repository: IdentityRepository,
     pub server_repository: ServerRepository,
+    pub team_service: TeamService,
+    pub team_repository: TeamRepository,
+    pub stewarding_service: StewardingService,
+    pub stewarding_repository: StewardingRepository,
+    pub result_service: ResultService,
+    pub result_repository: ResultRepository,
+    pub lap_repository: LapRepository,
+    pub record_repository: RecordRepository,
+    pub round_service: RoundService,
+    pub round_repository: RoundRepository,
+    pub stats_repository: StatsRepository,
+    pub two_factor_service: TwoFactorService,
+    pub two_factor_repository: TwoFactorRepository,
+    pub api_key_service: ApiKeyService,
+    pub api_key_repository: ApiKeyRepository,
 }
 
 impl AppState {
@@ -31,19 +52,40 @@ impl AppState {
         db_conn: &Database,
         firewall_service: FirewallService,
         cache: &RedisCache,
+        jwt_keys: &JwtKeys,
     ) -> Self {
+        let token_service = TokenService::new(cache, jwt_keys);
+        let user_service = UserService::new(db_conn, cache, &token_service);
+
         Self {
-            user_service: UserService::new(db_conn, cache),
+            oauth_service: OAuthService::new(db_conn, cache, &user_service),
+            user_service,
             f123_service: F123Service::new(db_conn, firewall_service),
             f123_repository: F123Repository::new(db_conn),
             user_repository: UserRepository::new(db_conn, cache),
-            token_service: TokenService::new(cache),
+            token_service,
             championship_service: ChampionshipService::new(db_conn, cache).await,
             championship_repository: ChampionshipRepository::new(db_conn, cache).await,
             email_service: EmailService::new(),
             saved_session_service: SavedSessionService::new(db_conn, cache),
-            google_repository: GoogleRepository::new(),
+            rate_limit_service: RateLimitService::new(cache),
+            identity_repository: IdentityRepository::new(db_conn),
             server_repository: ServerRepository::new(db_conn),
+            team_service: TeamService::new(db_conn, cache).await,
+            team_repository: TeamRepository::new(db_conn),
+            stewarding_service: StewardingService::new(db_conn, cache).await,
+            stewarding_repository: StewardingRepository::new(db_conn),
+            result_service: ResultService::new(db_conn, cache).await,
+            result_repository: ResultRepository::new(db_conn),
+            lap_repository: LapRepository::new(db_conn),
+            record_repository: RecordRepository::new(db_conn),
+            round_service: RoundService::new(db_conn),
+            round_repository: RoundRepository::new(db_conn),
+            stats_repository: StatsRepository::new(db_conn, cache),
+            two_factor_service: TwoFactorService::new(db_conn),
+            two_factor_repository: TwoFactorRepository::new(db_conn),
+            api_key_service: ApiKeyService::new(db_conn),
+            api_key_repository: ApiKeyRepository::new(db_conn),
         }
     }
 }